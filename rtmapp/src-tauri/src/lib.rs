@@ -1,27 +1,90 @@
-use chrono::{Datelike, Local};
+mod reminders;
+
+use chrono::{Duration, Local};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Mutex;
-use todotxt::{TodoContext, TodoItem, TodoLibrary, TodoPriority, TodoProject};
-
-#[derive(Serialize, Deserialize, Clone, Debug)]
+use todotxt::todo_deps;
+use todotxt::todo_names::NameGrammar;
+use todotxt::todo_reminder::ReminderLog;
+use todotxt::todo_stats::{compute_stats, StatsOptions, StatsPeriod, TodoStats, MAX_PERIODS};
+use todotxt::todo_tags::{context_summaries, project_summaries, TagMetadataFile, TagSummary};
+use todotxt::{TodoItem, TodoLibrary, TodoPatch, TodoRenameError, TodoWorkspace};
+
+/// How many entries `AppConfig::recent_files` keeps.
+const MAX_RECENT_FILES: usize = 10;
+
+// Every field is defaulted, so a config.toml written by rtmcli, or before a field existed,
+// still loads instead of failing as a whole.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
 pub struct AppConfig {
     file_name: Option<String>,
     show_completed_items: bool,
     show_future_items: bool,
     hide_no_date: bool,
     reverse_sort: bool,
+    /// Hide items waiting for an open `dep:` item.
+    hide_blocked: bool,
+    recent_files: Vec<String>,
+    lists: Vec<ListConfig>,
+    // Shared with rtmcli. The app uses done_file_name for statistics and hands date_format
+    // to the frontend; the others are CLI settings, modelled here so that saving the config
+    // from the app doesn't drop them.
+    done_file_name: Option<String>,
+    date_format: Option<String>,
+    default_filter: Option<String>,
+    sort: Option<String>,
+    theme: Option<String>,
+    confirm_above: Option<usize>,
+    time_log_file_name: Option<String>,
+    /// Descriptions, colors and archived flags for projects and contexts, which the app
+    /// uses to display them; `projects.toml` next to the todo file if unset.
+    projects_file_name: Option<String>,
+    /// How project and context names are read, shared with rtmcli as the `[names]` table.
+    names: NameGrammar,
+    /// Extra tags rtmcli's `doctor` accepts; kept so saving the config doesn't drop them.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    known_tags: Vec<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    aliases: BTreeMap<String, String>,
+    reminders: reminders::ReminderConfig,
+}
+
+/// A named todo list. Stored as `[[lists]]` in config.toml, which rtmcli also reads for its
+/// `--list` option, so field names here are shared with it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ListConfig {
+    pub name: String,
+    pub file_name: String,
+    #[serde(default)]
+    pub done_file_name: Option<String>,
 }
 
-impl Default for AppConfig {
-    fn default() -> Self {
-        Self {
-            file_name: None,
-            show_completed_items: false,
-            show_future_items: false,
-            hide_no_date: false,
-            reverse_sort: false,
+impl AppConfig {
+    fn push_recent_file(&mut self, file_name: &str) {
+        self.recent_files.retain(|f| f != file_name);
+        self.recent_files.insert(0, file_name.to_string());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+
+    fn workspace(&self) -> TodoWorkspace {
+        let mut workspace = TodoWorkspace::new();
+        for list in &self.lists {
+            let _ = workspace.add_list(
+                list.name.clone(),
+                list.file_name.clone(),
+                list.done_file_name.clone(),
+            );
         }
+        for list in &mut workspace.lists {
+            list.library.grammar = self.names.clone();
+            if let Some(ref mut done) = list.done {
+                done.grammar = self.names.clone();
+            }
+        }
+        workspace
     }
 }
 
@@ -36,12 +99,22 @@ pub struct TodoItemDto {
     pub projects: Vec<String>,
     pub contexts: Vec<String>,
     pub due: Option<String>,
+    /// HH:MM, for items due at a time of day.
+    pub due_time: Option<String>,
     pub recurrence: Option<String>,
     pub threshold: Option<String>,
+    pub threshold_time: Option<String>,
+    pub uuid: Option<String>,
+    /// The uuid of the parent item, for subtasks.
+    pub sub: Option<String>,
+    /// The uuids of the items this one waits for.
+    pub deps: Vec<String>,
+    /// Whether any of those is still open.
+    pub blocked: bool,
 }
 
-impl From<(usize, &TodoItem)> for TodoItemDto {
-    fn from((idx, item): (usize, &TodoItem)) -> Self {
+impl From<(usize, &TodoItem, bool)> for TodoItemDto {
+    fn from((idx, item, blocked): (usize, &TodoItem, bool)) -> Self {
         TodoItemDto {
             index: idx,
             done: item.done,
@@ -54,8 +127,14 @@ impl From<(usize, &TodoItem)> for TodoItemDto {
             projects: item.projects.iter().map(|p| p.name.clone()).collect(),
             contexts: item.contexts.iter().map(|c| c.name.clone()).collect(),
             due: item.due.map(|d| d.format("%Y-%m-%d").to_string()),
+            due_time: item.due_time.map(|t| t.format("%H:%M").to_string()),
             recurrence: item.recurrence.as_ref().map(|r| r.to_string()),
             threshold: item.threshold.map(|t| t.format("%Y-%m-%d").to_string()),
+            threshold_time: item.threshold_time.map(|t| t.format("%H:%M").to_string()),
+            uuid: item.uuid.map(|u| u.to_string()),
+            sub: item.sub.map(|u| u.to_string()),
+            deps: item.deps.iter().map(|u| u.to_string()).collect(),
+            blocked,
         }
     }
 }
@@ -64,47 +143,70 @@ pub struct AppState {
     lib: Mutex<Option<TodoLibrary>>,
     config: Mutex<AppConfig>,
     config_path: PathBuf,
+    /// Why config.toml couldn't be loaded, if it couldn't. The defaults are used instead, and
+    /// the file is never saved over, so the settings in it aren't lost.
+    config_error: Option<String>,
+    reminder_log: Mutex<ReminderLog>,
 }
 
 impl AppState {
     fn new() -> Self {
         let config_path = Self::get_config_path();
-        let config: AppConfig = std::fs::read_to_string(&config_path)
-            .ok()
-            .and_then(|s| toml::from_str(&s).ok())
-            .unwrap_or_default();
+        let (config, config_error) = match std::fs::read_to_string(&config_path) {
+            Ok(content) => match toml::from_str::<AppConfig>(&content) {
+                Ok(config) => (config, None),
+                Err(e) => (
+                    AppConfig::default(),
+                    Some(format!("Error parsing '{}': {}", config_path.display(), e)),
+                ),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (AppConfig::default(), None),
+            Err(e) => (
+                AppConfig::default(),
+                Some(format!("Error reading '{}': {}", config_path.display(), e)),
+            ),
+        };
+        if let Some(ref e) = config_error {
+            eprintln!("{}", e);
+        }
 
         let mut lib: Option<TodoLibrary> = None;
         if let Some(ref file_name) = config.file_name {
             let path = PathBuf::from(file_name);
             if path.exists() {
                 let mut library = TodoLibrary::new(file_name.clone());
+                library.grammar = config.names.clone();
                 if library.load().is_ok() {
                     lib = Some(library);
                 }
             }
         }
 
+        let reminder_log = reminders::load_log(&reminders::log_path(&config_path));
         AppState {
             lib: Mutex::new(lib),
             config: Mutex::new(config),
             config_path,
+            config_error,
+            reminder_log: Mutex::new(reminder_log),
         }
     }
 
     fn get_config_path() -> PathBuf {
-        let mut path = dirs::config_dir().unwrap_or_else(|| std::env::temp_dir());
+        let mut path = dirs::config_dir().unwrap_or_else(std::env::temp_dir);
         path.push("rtm");
         std::fs::create_dir_all(&path).unwrap();
         path.push("config.toml");
         path
     }
 
-    fn save_config(&self) {
-        let config = self.config.lock().unwrap();
-        if let Ok(toml_str) = toml::to_string(&*config) {
-            let _ = std::fs::write(&self.config_path, toml_str);
+    fn save_config(&self) -> Result<(), String> {
+        if let Some(ref e) = self.config_error {
+            return Err(format!("Settings not saved. {}", e));
         }
+        let config = self.config.lock().unwrap();
+        let toml_str = toml::to_string(&*config).map_err(|e| e.to_string())?;
+        std::fs::write(&self.config_path, toml_str).map_err(|e| e.to_string())
     }
 }
 
@@ -116,16 +218,188 @@ fn load_file(path: String, state: tauri::State<AppState>) -> Result<bool, String
     let canonical_str = canonical_path.to_string_lossy().to_string();
 
     let mut library = TodoLibrary::new(canonical_str.clone());
+    library.grammar = state.config.lock().unwrap().names.clone();
     library.load().map_err(|e| e.to_string())?;
 
     let mut lib_guard = state.lib.lock().unwrap();
     *lib_guard = Some(library);
 
     let mut config = state.config.lock().unwrap();
+    config.push_recent_file(&canonical_str);
     config.file_name = Some(canonical_str);
     drop(config);
-    state.save_config();
+    // Remembering the file is a convenience; a config that can't be saved is reported by
+    // get_config instead of failing the load.
+    let _ = state.save_config();
+
+    Ok(true)
+}
+
+#[tauri::command]
+fn get_recent_files(state: tauri::State<AppState>) -> Vec<String> {
+    let config = state.config.lock().unwrap();
+    config.recent_files.clone()
+}
+
+#[tauri::command]
+fn get_lists(state: tauri::State<AppState>) -> Vec<ListConfig> {
+    let config = state.config.lock().unwrap();
+    config.lists.clone()
+}
+
+#[tauri::command]
+fn add_list(
+    name: String,
+    file_name: String,
+    done_file_name: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<bool, String> {
+    let mut config = state.config.lock().unwrap();
+    if config.lists.iter().any(|l| l.name == name) {
+        return Err(format!("A list named '{}' already exists", name));
+    }
+    config.lists.push(ListConfig {
+        name,
+        file_name,
+        done_file_name,
+    });
+    drop(config);
+    state.save_config()?;
+    Ok(true)
+}
+
+#[tauri::command]
+fn remove_list(name: String, state: tauri::State<AppState>) -> Result<bool, String> {
+    let mut config = state.config.lock().unwrap();
+    let before = config.lists.len();
+    config.lists.retain(|l| l.name != name);
+    if config.lists.len() == before {
+        return Err(format!("No list named '{}'", name));
+    }
+    drop(config);
+    state.save_config()?;
+    Ok(true)
+}
+
+#[tauri::command]
+fn open_list(name: String, state: tauri::State<AppState>) -> Result<bool, String> {
+    let file_name = {
+        let config = state.config.lock().unwrap();
+        config
+            .lists
+            .iter()
+            .find(|l| l.name == name)
+            .map(|l| l.file_name.clone())
+            .ok_or_else(|| format!("No list named '{}'", name))?
+    };
+    load_file(file_name, state)
+}
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WorkspaceItemDto {
+    pub list: String,
+    pub item: TodoItemDto,
+}
+
+/// Every item across all configured lists whose description, projects or contexts contain
+/// `query` (case-insensitively); an empty query matches everything.
+#[tauri::command]
+fn search_lists(
+    query: String,
+    state: tauri::State<AppState>,
+) -> Result<Vec<WorkspaceItemDto>, String> {
+    let mut workspace = state.config.lock().unwrap().workspace();
+    workspace.load().map_err(|e| e.to_string())?;
+    let query = query.to_lowercase();
+    let all_items: Vec<TodoItem> = workspace
+        .list_names()
+        .iter()
+        .flat_map(|name| workspace.list(name).unwrap().library.items.clone())
+        .collect();
+    let open = todo_deps::open_uuids(&all_items);
+    Ok(workspace
+        .query(|item| {
+            item.description.to_lowercase().contains(&query)
+                || item
+                    .projects
+                    .iter()
+                    .any(|p| p.name.to_lowercase().contains(&query))
+                || item
+                    .contexts
+                    .iter()
+                    .any(|c| c.name.to_lowercase().contains(&query))
+        })
+        .into_iter()
+        .map(|found| WorkspaceItemDto {
+            list: found.list.to_string(),
+            item: TodoItemDto::from((
+                found.index,
+                found.item,
+                todo_deps::is_blocked(found.item, &open),
+            )),
+        })
+        .collect())
+}
+
+/// Moves the item at `index` in the open file to the end of the list named `to`.
+#[tauri::command]
+fn move_item_to_list(
+    index: usize,
+    to: String,
+    state: tauri::State<AppState>,
+) -> Result<bool, String> {
+    let target = {
+        let config = state.config.lock().unwrap();
+        config
+            .lists
+            .iter()
+            .find(|l| l.name == to)
+            .cloned()
+            .ok_or_else(|| format!("No list named '{}'", to))?
+    };
+
+    let mut lib_guard = state.lib.lock().unwrap();
+    let Some(ref mut lib) = *lib_guard else {
+        return Err("No file loaded".to_string());
+    };
+    let target_path = std::path::Path::new(&target.file_name)
+        .canonicalize()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| target.file_name.clone());
+    if target_path == lib.file_name {
+        return Err(format!("Item is already in list '{}'", to));
+    }
+
+    let mut workspace = TodoWorkspace::new();
+    workspace
+        .add_list(String::new(), lib.file_name.clone(), None)
+        .map_err(|e| e.to_string())?;
+    workspace
+        .add_list(to.clone(), target.file_name, None)
+        .map_err(|e| e.to_string())?;
+    let source = workspace.list_mut("").unwrap();
+    source.library.items = lib.items.clone();
+    // A target file that doesn't exist yet is created, but one that can't be read must not
+    // be overwritten.
+    match workspace.list_mut(&to).unwrap().load() {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            return Err(format!("Error loading '{}': {}", target_path, e))
+        }
+        _ => {}
+    }
+
+    workspace
+        .move_item("", index, &to)
+        .map_err(|e| e.to_string())?;
+    // The target first: if it can't be written, the source still has the item.
+    for name in [to.as_str(), ""] {
+        workspace
+            .list(name)
+            .unwrap()
+            .save()
+            .map_err(|e| e.to_string())?;
+    }
+    lib.items = workspace.list("").unwrap().library.items.clone();
     Ok(true)
 }
 
@@ -152,44 +426,130 @@ fn save_file(state: tauri::State<AppState>) -> Result<bool, String> {
     }
 }
 
+/// The items the list view shows, with their indices, in display order.
+fn visible_items<'a>(lib: &'a TodoLibrary, config: &AppConfig) -> Vec<(usize, &'a TodoItem)> {
+    let today = Local::now().date_naive();
+    let open = todo_deps::open_uuids(lib.list_items());
+    let mut items: Vec<(usize, &TodoItem)> = lib.list_items().iter().enumerate().collect();
+
+    items.sort_by_key(|(_, item)| {
+        let priority_key = item.priority.priority.map(|p| p as i32).unwrap_or(26);
+        match item.due_at() {
+            None => (0i32, 0i64, priority_key),
+            Some(due) => {
+                let due_key = due.and_utc().timestamp();
+                let date_key = if config.reverse_sort {
+                    due_key
+                } else {
+                    -due_key
+                };
+                (1i32, date_key, priority_key)
+            }
+        }
+    });
+
+    items
+        .into_iter()
+        .filter(|(_, item)| {
+            (config.show_future_items || item.due.is_none_or(|d| d <= today))
+                && (config.show_completed_items || !item.done)
+                && !(config.hide_no_date && item.due.is_none())
+                && !(config.hide_blocked && todo_deps::is_blocked(item, &open))
+        })
+        .collect()
+}
+
 #[tauri::command]
 fn get_items(state: tauri::State<AppState>) -> Vec<TodoItemDto> {
     let lib_guard = state.lib.lock().unwrap();
     if let Some(ref lib) = *lib_guard {
-        let today = Local::now().date_naive();
         let config = state.config.lock().unwrap();
-
-        let mut items: Vec<(usize, &TodoItem)> = lib.list_items().iter().enumerate().collect();
-
-        items.sort_by_key(|(_, item)| {
-            let priority_key = item.priority.priority.map(|p| p as i32).unwrap_or(26);
-            match item.due {
-                None => (0i32, 0i64, priority_key),
-                Some(d) => {
-                    let date_key = if config.reverse_sort {
-                        d.num_days_from_ce() as i64
-                    } else {
-                        -d.num_days_from_ce() as i64
-                    };
-                    (1i32, date_key, priority_key)
-                }
-            }
-        });
-
-        items
+        let open = todo_deps::open_uuids(&lib.items);
+        visible_items(lib, &config)
             .into_iter()
-            .filter(|(_, item)| {
-                !(item.due.map_or(false, |d| d > today) && !config.show_future_items)
-                    && !(item.done && !config.show_completed_items)
-                    && !(config.hide_no_date && item.due.is_none())
-            })
-            .map(|item| TodoItemDto::from(item))
+            .map(|(i, item)| TodoItemDto::from((i, item, todo_deps::is_blocked(item, &open))))
             .collect()
     } else {
         vec![]
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TodoTreeDto {
+    pub item: TodoItemDto,
+    pub children: Vec<TodoTreeDto>,
+}
+
+/// The index of the item `items[index]` is a subtask of, if it's in the file.
+fn parent_of(items: &[TodoItem], index: usize) -> Option<usize> {
+    let sub = items[index].sub?;
+    items.iter().position(|item| item.uuid == Some(sub))
+}
+
+/// Whether `index` is `ancestor` or one of its subtasks, however deep.
+fn is_descendant(items: &[TodoItem], mut index: usize, ancestor: usize) -> bool {
+    // A file edited by hand can contain a cycle of sub: tags; no chain is longer than the
+    // file without one.
+    for _ in 0..=items.len() {
+        if index == ancestor {
+            return true;
+        }
+        match parent_of(items, index) {
+            Some(parent) => index = parent,
+            None => return false,
+        }
+    }
+    false
+}
+
+/// The visible items nested under their parents, in the same order as `get_items`. A subtask
+/// whose parent is hidden or missing is shown at the top level, as is every item of a cycle
+/// of `sub:` tags.
+#[tauri::command]
+fn get_item_tree(state: tauri::State<AppState>) -> Vec<TodoTreeDto> {
+    let lib_guard = state.lib.lock().unwrap();
+    let Some(ref lib) = *lib_guard else {
+        return vec![];
+    };
+    let config = state.config.lock().unwrap();
+    let visible = visible_items(lib, &config);
+    let items = lib.list_items();
+    let open = todo_deps::open_uuids(items);
+
+    let shown: Vec<usize> = visible.iter().map(|(i, _)| *i).collect();
+    let parents: BTreeMap<usize, usize> = shown
+        .iter()
+        .filter_map(|&i| {
+            let parent = parent_of(items, i)?;
+            let acyclic = !is_descendant(items, parent, i);
+            (acyclic && shown.contains(&parent)).then_some((i, parent))
+        })
+        .collect();
+
+    fn build(
+        index: usize,
+        visible: &[(usize, &TodoItem)],
+        parents: &BTreeMap<usize, usize>,
+        blocked: &dyn Fn(&TodoItem) -> bool,
+    ) -> TodoTreeDto {
+        let item = visible.iter().find(|(i, _)| *i == index).unwrap().1;
+        TodoTreeDto {
+            item: TodoItemDto::from((index, item, blocked(item))),
+            children: visible
+                .iter()
+                .filter(|(i, _)| parents.get(i) == Some(&index))
+                .map(|(i, _)| build(*i, visible, parents, blocked))
+                .collect(),
+        }
+    }
+    let blocked = |item: &TodoItem| todo_deps::is_blocked(item, &open);
+    shown
+        .iter()
+        .filter(|i| !parents.contains_key(i))
+        .map(|&i| build(i, &visible, &parents, &blocked))
+        .collect()
+}
+
 #[tauri::command]
 fn get_item_count(state: tauri::State<AppState>) -> usize {
     let lib_guard = state.lib.lock().unwrap();
@@ -245,78 +605,260 @@ fn uncomplete_item(index: usize, state: tauri::State<AppState>) -> Result<bool,
     }
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct UpdateItemRequest {
-    pub index: usize,
-    pub description: String,
-    pub priority: Option<i32>,
-    pub due: Option<String>,
-    pub recurrence: Option<String>,
-    pub threshold: Option<String>,
-    pub projects: Vec<String>,
-    pub contexts: Vec<String>,
+#[tauri::command]
+fn delete_item(index: usize, state: tauri::State<AppState>) -> Result<bool, String> {
+    let mut lib_guard = state.lib.lock().unwrap();
+    let Some(ref mut lib) = *lib_guard else {
+        return Err("No file loaded".to_string());
+    };
+    lib.remove_item(index).ok_or("Invalid index")?;
+    lib.save().map_err(|e| e.to_string())?;
+    Ok(true)
 }
 
+/// Adds `text` as a subtask of the item at `parent`, giving the parent a uuid if it has
+/// none. Returns the new item's index.
 #[tauri::command]
-fn update_item(request: UpdateItemRequest, state: tauri::State<AppState>) -> Result<bool, String> {
+fn add_subtask(
+    parent: usize,
+    text: String,
+    state: tauri::State<AppState>,
+) -> Result<usize, String> {
+    let child: TodoItem = text.parse().map_err(|_| "Failed to parse item")?;
+
     let mut lib_guard = state.lib.lock().unwrap();
-    if let Some(ref mut lib) = *lib_guard {
-        if request.index >= lib.items.len() {
-            return Err("Invalid index".to_string());
+    let Some(ref mut lib) = *lib_guard else {
+        return Err("No file loaded".to_string());
+    };
+    if parent >= lib.items.len() {
+        return Err("Invalid index".to_string());
+    }
+    let (new_parent, new_child) = lib.items[parent].add_subtask(&child);
+    if let Some(new_parent) = new_parent {
+        lib.items[parent] = new_parent;
+    }
+    lib.add_item(new_child);
+    lib.save().map_err(|e| e.to_string())?;
+    Ok(lib.item_count() - 1)
+}
+
+/// Makes the item at `index` a subtask of the item at `parent`, or a top-level item when
+/// `parent` is `None`. Its own subtasks move with it.
+#[tauri::command]
+fn reparent_item(
+    index: usize,
+    parent: Option<usize>,
+    state: tauri::State<AppState>,
+) -> Result<bool, String> {
+    let mut lib_guard = state.lib.lock().unwrap();
+    let Some(ref mut lib) = *lib_guard else {
+        return Err("No file loaded".to_string());
+    };
+    let len = lib.items.len();
+    if index >= len || parent.is_some_and(|p| p >= len) {
+        return Err("Invalid index".to_string());
+    }
+    match parent {
+        Some(parent) => {
+            if is_descendant(&lib.items, parent, index) {
+                return Err("An item can't be moved under itself or its subtasks".to_string());
+            }
+            let (new_parent, new_child) = lib.items[parent].add_subtask(&lib.items[index]);
+            if let Some(new_parent) = new_parent {
+                lib.items[parent] = new_parent;
+            }
+            lib.items[index] = new_child;
         }
+        None => lib.items[index].sub = None,
+    }
+    lib.save().map_err(|e| e.to_string())?;
+    Ok(true)
+}
 
-        let item = &mut lib.items[request.index];
+/// Changes the fields of the item at `index` that `patch` names. Nothing is changed if any
+/// of them is invalid; the error lists every one that is.
+#[tauri::command]
+fn update_item(
+    index: usize,
+    patch: TodoPatch,
+    state: tauri::State<AppState>,
+) -> Result<TodoItemDto, String> {
+    let mut lib_guard = state.lib.lock().unwrap();
+    let Some(ref mut lib) = *lib_guard else {
+        return Err("No file loaded".to_string());
+    };
+    let item = lib.items.get(index).ok_or("Invalid index")?;
+    lib.items[index] = patch.apply_with(item, &lib.grammar).map_err(|errors| {
+        errors
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    })?;
+    lib.save().map_err(|e| e.to_string())?;
+    let blocked = todo_deps::is_blocked(&lib.items[index], &todo_deps::open_uuids(&lib.items));
+    Ok(TodoItemDto::from((index, &lib.items[index], blocked)))
+}
 
-        item.description = request.description;
+/// The done file of the list `file_name` belongs to, or else the configured
+/// `done_file_name`.
+fn done_file_for(config: &AppConfig, file_name: &str) -> Option<String> {
+    config
+        .lists
+        .iter()
+        .find(|l| {
+            std::path::Path::new(&l.file_name)
+                .canonicalize()
+                .is_ok_and(|p| p.to_string_lossy() == file_name)
+        })
+        .map_or(config.done_file_name.clone(), |l| l.done_file_name.clone())
+}
 
-        if let Some(p) = request.priority {
-            item.priority = TodoPriority {
-                priority: Some(p as u8),
-            };
-        } else {
-            item.priority = TodoPriority { priority: None };
+/// The items of `lib` followed by those of its done file, if it has one.
+fn items_with_done(config: &AppConfig, lib: &TodoLibrary) -> Vec<TodoItem> {
+    let mut items = lib.items.clone();
+    if let Some(done_file) = done_file_for(config, &lib.file_name) {
+        let mut done_lib = TodoLibrary::new(done_file);
+        done_lib.grammar = config.names.clone();
+        // Nothing archived yet is not an error.
+        if done_lib.load().is_ok() {
+            items.extend(done_lib.items);
         }
+    }
+    items
+}
 
-        item.due = request
-            .due
-            .and_then(|s| chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok());
-        item.threshold = request
-            .threshold
-            .and_then(|s| chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok());
-
-        if let Some(ref rec_str) = request.recurrence {
-            if rec_str.is_empty() {
-                item.recurrence = None;
-            } else {
-                item.recurrence = rec_str.parse().ok();
-            }
-        } else {
-            item.recurrence = None;
+/// The configured `projects_file_name`, or else `projects.toml` next to `file_name`.
+fn projects_file_for(config: &AppConfig, file_name: &str) -> String {
+    config.projects_file_name.clone().unwrap_or_else(|| {
+        std::path::Path::new(file_name)
+            .with_file_name("projects.toml")
+            .to_string_lossy()
+            .to_string()
+    })
+}
+
+/// Summaries of the projects or contexts in the open file and its done file, with their
+/// descriptions, colors and archived flags from the projects file.
+fn tag_summaries(
+    state: &AppState,
+    summaries: fn(&[TodoItem], &TagMetadataFile) -> Vec<TagSummary>,
+) -> Result<Vec<TagSummary>, String> {
+    let lib_guard = state.lib.lock().unwrap();
+    let Some(ref lib) = *lib_guard else {
+        return Err("No file loaded".to_string());
+    };
+    let config = state.config.lock().unwrap();
+    let items = items_with_done(&config, lib);
+    let mut metadata = TagMetadataFile::new(projects_file_for(&config, &lib.file_name));
+    metadata.load().map_err(|e| e.to_string())?;
+    Ok(summaries(&items, &metadata))
+}
+
+#[tauri::command]
+fn get_projects(state: tauri::State<AppState>) -> Result<Vec<TagSummary>, String> {
+    tag_summaries(&state, project_summaries)
+}
+
+#[tauri::command]
+fn get_contexts(state: tauri::State<AppState>) -> Result<Vec<TagSummary>, String> {
+    tag_summaries(&state, context_summaries)
+}
+
+/// Renames in the open file and, with `include_done`, in its done file, then saves both.
+/// Returns the number of items changed.
+fn rename_in_files(
+    state: &AppState,
+    include_done: bool,
+    rename: impl Fn(&mut TodoLibrary) -> Result<usize, TodoRenameError>,
+) -> Result<usize, String> {
+    let mut lib_guard = state.lib.lock().unwrap();
+    let Some(ref mut lib) = *lib_guard else {
+        return Err("No file loaded".to_string());
+    };
+    let mut done_lib = None;
+    if include_done {
+        let done_file = done_file_for(&state.config.lock().unwrap(), &lib.file_name)
+            .ok_or("No done file configured for this file")?;
+        let mut done = TodoLibrary::new(done_file);
+        done.grammar = lib.grammar.clone();
+        // Nothing archived yet is not an error, but a done file that can't be read is.
+        match done.load() {
+            Ok(()) => done_lib = Some(done),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Error loading '{}': {}", done.file_name, e)),
         }
+    }
 
-        item.projects = request
-            .projects
-            .into_iter()
-            .map(|n| TodoProject { name: n })
-            .collect();
+    let mut changed = rename(lib).map_err(|e| e.to_string())?;
+    if let Some(ref mut done) = done_lib {
+        let done_changed = rename(done).map_err(|e| e.to_string())?;
+        if done_changed > 0 {
+            done.save().map_err(|e| e.to_string())?;
+        }
+        changed += done_changed;
+    }
+    lib.save().map_err(|e| e.to_string())?;
+    Ok(changed)
+}
 
-        item.contexts = request
-            .contexts
-            .into_iter()
-            .map(|n| TodoContext { name: n })
-            .collect();
+/// Renames project `from`, with its subprojects, to `to`; renaming to an existing project
+/// merges the two. Returns the number of items changed.
+#[tauri::command]
+fn rename_project(
+    from: String,
+    to: String,
+    include_done: bool,
+    state: tauri::State<AppState>,
+) -> Result<usize, String> {
+    rename_in_files(&state, include_done, |lib| lib.rename_project(&from, &to))
+}
 
-        lib.save().map_err(|e| e.to_string())?;
-        Ok(true)
-    } else {
-        Err("No file loaded".to_string())
+/// Renames context `from`, with the contexts within it, to `to`, like rename_project.
+#[tauri::command]
+fn rename_context(
+    from: String,
+    to: String,
+    include_done: bool,
+    state: tauri::State<AppState>,
+) -> Result<usize, String> {
+    rename_in_files(&state, include_done, |lib| lib.rename_context(&from, &to))
+}
+
+/// Statistics for the dashboard view over the open file and its done file: the one of the
+/// list it belongs to, or else the configured `done_file_name`.
+#[tauri::command]
+fn get_stats(
+    weekly: bool,
+    periods: Option<usize>,
+    state: tauri::State<AppState>,
+) -> Result<TodoStats, String> {
+    let lib_guard = state.lib.lock().unwrap();
+    let Some(ref lib) = *lib_guard else {
+        return Err("No file loaded".to_string());
+    };
+    let items = items_with_done(&state.config.lock().unwrap(), lib);
+
+    let mut options = StatsOptions::new(Local::now().naive_local());
+    if weekly {
+        options.period = StatsPeriod::Week;
+    }
+    if let Some(periods) = periods {
+        if periods > MAX_PERIODS {
+            return Err(format!("At most {} periods can be shown", MAX_PERIODS));
+        }
+        options.periods = periods;
     }
+    Ok(compute_stats(&items, &options))
 }
 
 #[tauri::command]
-fn get_config(state: tauri::State<AppState>) -> AppConfig {
+fn get_config(state: tauri::State<AppState>) -> Result<AppConfig, String> {
+    if let Some(ref e) = state.config_error {
+        return Err(e.clone());
+    }
     let config = state.config.lock().unwrap();
-    config.clone()
+    Ok(config.clone())
 }
 
 #[tauri::command]
@@ -324,6 +866,7 @@ fn save_config(
     show_completed_items: Option<bool>,
     show_future_items: Option<bool>,
     hide_no_date: Option<bool>,
+    hide_blocked: Option<bool>,
     reverse_sort: Option<bool>,
     state: tauri::State<AppState>,
 ) -> Result<bool, String> {
@@ -337,33 +880,80 @@ fn save_config(
     if let Some(v) = hide_no_date {
         config.hide_no_date = v;
     }
+    if let Some(v) = hide_blocked {
+        config.hide_blocked = v;
+    }
     if let Some(v) = reverse_sort {
         config.reverse_sort = v;
     }
     drop(config);
-    state.save_config();
+    state.save_config()?;
     Ok(true)
 }
 
+#[tauri::command]
+fn save_reminder_config(
+    reminders: reminders::ReminderConfig,
+    state: tauri::State<AppState>,
+) -> Result<bool, String> {
+    reminders.settings()?;
+    state.config.lock().unwrap().reminders = reminders;
+    state.save_config()?;
+    Ok(true)
+}
+
+/// Shows the reminder with `key`, as sent with the `reminder` event, again in `minutes`.
+#[tauri::command]
+fn snooze_reminder(key: String, minutes: u32, state: tauri::State<AppState>) -> bool {
+    let until = Local::now().naive_local() + Duration::minutes(minutes as i64);
+    let mut log = state.reminder_log.lock().unwrap();
+    log.snooze(&key, until);
+    reminders::save_log(&reminders::log_path(&state.config_path), &log);
+    true
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(AppState::new())
+        .setup(|app| {
+            reminders::spawn(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             load_file,
+            get_recent_files,
+            get_lists,
+            add_list,
+            remove_list,
+            open_list,
+            search_lists,
+            move_item_to_list,
             get_file_name,
             has_file_loaded,
             save_file,
             get_items,
+            get_item_tree,
             get_item_count,
             add_item,
             complete_item,
             uncomplete_item,
+            delete_item,
+            add_subtask,
+            reparent_item,
             update_item,
+            rename_project,
+            rename_context,
+            get_stats,
+            get_projects,
+            get_contexts,
             get_config,
             save_config,
+            save_reminder_config,
+            snooze_reminder,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
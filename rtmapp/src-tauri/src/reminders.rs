@@ -0,0 +1,130 @@
+//! Background reminders: a thread that checks the open file once a minute and shows a
+//! desktop notification for each reminder that comes up.
+
+use crate::AppState;
+use chrono::{Duration, Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use todotxt::todo_reminder::{ReminderLog, ReminderSettings};
+use todotxt::TodoLibrary;
+
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How long shown reminders are remembered.
+const LOG_DAYS: i64 = 30;
+
+/// The `[reminders]` table of config.toml.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ReminderConfig {
+    pub enabled: bool,
+    /// HH:MM, for items without a `remind:` tag.
+    pub time: String,
+    pub days_before: Option<u32>,
+    pub on_threshold: bool,
+}
+
+impl Default for ReminderConfig {
+    fn default() -> Self {
+        ReminderConfig {
+            enabled: true,
+            time: "09:00".to_string(),
+            days_before: None,
+            on_threshold: true,
+        }
+    }
+}
+
+/// The most days before a due date a reminder can be set for.
+const MAX_DAYS_BEFORE: u32 = 365;
+
+impl ReminderConfig {
+    pub fn settings(&self) -> Result<ReminderSettings, String> {
+        let time = NaiveTime::parse_from_str(&self.time, "%H:%M")
+            .map_err(|_| format!("Invalid reminder time '{}', expected HH:MM", self.time))?;
+        if let Some(days) = self.days_before.filter(|&d| d > MAX_DAYS_BEFORE) {
+            return Err(format!(
+                "Reminders can be at most {} days before the due date, not {}",
+                MAX_DAYS_BEFORE, days
+            ));
+        }
+        Ok(ReminderSettings {
+            time,
+            days_before: self.days_before,
+            on_threshold: self.on_threshold,
+        })
+    }
+}
+
+/// Where the record of shown and snoozed reminders is kept, next to config.toml.
+pub fn log_path(config_path: &std::path::Path) -> PathBuf {
+    config_path.with_file_name("reminders.json")
+}
+
+pub fn load_log(path: &std::path::Path) -> ReminderLog {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_log(path: &std::path::Path, log: &ReminderLog) {
+    if let Ok(json) = serde_json::to_string_pretty(log) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+pub fn spawn(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        check(&app);
+        std::thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+/// Shows the reminders that are due now. The file is read from disk rather than taken from
+/// the app's state, so items added with rtmcli in the meantime are included.
+fn check(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let (file_name, reminders, grammar) = {
+        let config = state.config.lock().unwrap();
+        (
+            config.file_name.clone(),
+            config.reminders.clone(),
+            config.names.clone(),
+        )
+    };
+    let (Some(file_name), true) = (file_name, reminders.enabled) else {
+        return;
+    };
+    let Ok(settings) = reminders.settings() else {
+        return;
+    };
+    let mut lib = TodoLibrary::new(file_name);
+    lib.grammar = grammar;
+    if lib.load().is_err() {
+        return;
+    }
+
+    let now = Local::now().naive_local();
+    let mut log = state.reminder_log.lock().unwrap();
+    let before = log.clone();
+    for reminder in log.pending(&lib.items, &settings, now) {
+        let shown = app
+            .notification()
+            .builder()
+            .title(reminder.title())
+            .body(&reminder.description)
+            .show();
+        if shown.is_ok() {
+            // Lets the window offer a snooze button for it.
+            let _ = app.emit("reminder", &reminder);
+            log.mark_notified(&reminder.key, now);
+        }
+    }
+    log.prune(now - Duration::days(LOG_DAYS));
+    if *log != before {
+        save_log(&log_path(&state.config_path), &log);
+    }
+}
@@ -4,6 +4,12 @@ const { open } = window.__TAURI__.dialog;
 let items = [];
 let fileLoaded = false;
 let currentEditIndex = null;
+// The project or context being renamed: { kind: 'project' | 'context', name }.
+let currentRename = null;
+// Summaries of the projects and contexts in use by name, with their color, description and
+// archived flag from the projects file.
+let projectInfo = {};
+let contextInfo = {};
 let dueDatePicker = null;
 let thresholdDatePicker = null;
 
@@ -47,6 +53,7 @@ async function checkFileLoaded() {
 async function refreshItems() {
   try {
     items = await invoke('get_items');
+    await refreshTags();
     renderItems();
     await updateItemCount();
   } catch (error) {
@@ -54,6 +61,41 @@ async function refreshItems() {
   }
 }
 
+async function refreshTags() {
+  if (!fileLoaded) return;
+  try {
+    const projects = await invoke('get_projects');
+    const contexts = await invoke('get_contexts');
+    projectInfo = Object.fromEntries(projects.map(p => [p.name, p]));
+    contextInfo = Object.fromEntries(contexts.map(c => [c.name, c]));
+    renderProjects(projects);
+  } catch (error) {
+    console.error('Failed to get projects:', error);
+  }
+}
+
+// The sidebar list of projects that aren't archived, with their open item counts.
+function renderProjects(projects) {
+  const listEl = document.getElementById('project-list');
+  const shown = projects.filter(p => !p.archived);
+  listEl.innerHTML = shown.map(p => `
+    <li class="project-summary" style="padding-left: ${p.depth}em" title="${escapeHtml(p.description || '')}">
+      <span class="project-swatch" style="${p.color ? `background: ${escapeHtml(p.color)}` : ''}"></span>
+      +${escapeHtml(p.name)}
+      <span class="project-count">${p.open}</span>
+    </li>
+  `).join('');
+}
+
+// The chip for a project or context, in its color and with its description as tooltip.
+function tagChip(kind, name, displayIndex, tagIndex) {
+  const info = (kind === 'project' ? projectInfo : contextInfo)[name] || {};
+  const sigil = kind === 'project' ? '+' : '@';
+  const title = info.description ? `${info.description} (click to rename)` : 'Rename';
+  const style = info.color ? `style="color: ${escapeHtml(info.color)}"` : '';
+  return `<span class="${kind} ${info.archived ? 'archived' : ''}" ${style} title="${escapeHtml(title)}" onclick="openRenameDialog(event, ${displayIndex}, '${kind}', ${tagIndex})">${sigil}${escapeHtml(name)}</span>`;
+}
+
 async function updateItemCount() {
   const count = await invoke('get_item_count');
   document.getElementById('item-count').textContent = `Total items: ${count}`;
@@ -149,16 +191,21 @@ async function saveEdit() {
     return;
   }
 
-  const due = dueDate ? flatpickr.formatDate(dueDate, 'Y-m-d') : null;
-  const threshold = thresholdDate ? flatpickr.formatDate(thresholdDate, 'Y-m-d') : null;
-  const priority = priorityStr ? parseInt(priorityStr, 10) : null;
+  // The pickers only choose dates; keep the item's times of day.
+  const edited = items.find(i => i.index === currentEditIndex);
+  const withTime = (date, time) => (time ? `${date}T${time}` : date);
+  const due = dueDate ? withTime(flatpickr.formatDate(dueDate, 'Y-m-d'), edited && edited.due_time) : null;
+  const threshold = thresholdDate
+    ? withTime(flatpickr.formatDate(thresholdDate, 'Y-m-d'), edited && edited.threshold_time)
+    : null;
+  const priority = priorityStr ? String.fromCharCode(65 + parseInt(priorityStr, 10)) : null;
   const projects = projectsStr ? projectsStr.split(',').map(p => p.trim()).filter(p => p) : [];
   const contexts = contextsStr ? contextsStr.split(',').map(c => c.trim()).filter(c => c) : [];
 
   try {
     await invoke('update_item', {
-      request: {
-        index: currentEditIndex,
+      index: currentEditIndex,
+      patch: {
         description,
         priority,
         due,
@@ -176,6 +223,45 @@ async function saveEdit() {
   }
 }
 
+function openRenameDialog(event, index, kind, tagIndex) {
+  // The click would otherwise also open the item's edit dialog.
+  event.stopPropagation();
+  const item = items[index];
+  if (!item) return;
+  const name = kind === 'project' ? item.projects[tagIndex] : item.contexts[tagIndex];
+  currentRename = { kind, name };
+  const sigil = kind === 'project' ? '+' : '@';
+  document.getElementById('rename-title').textContent = `Rename ${sigil}${name}`;
+  document.getElementById('rename-to').value = name;
+  document.getElementById('rename-include-done').checked = false;
+  document.getElementById('rename-dialog').style.display = 'flex';
+}
+
+function closeRenameDialog() {
+  document.getElementById('rename-dialog').style.display = 'none';
+  currentRename = null;
+}
+
+async function saveRename() {
+  if (currentRename === null) return;
+  const to = document.getElementById('rename-to').value.trim().replace(/^[+@]/, '');
+  const includeDone = document.getElementById('rename-include-done').checked;
+  if (!to || to === currentRename.name) {
+    closeRenameDialog();
+    return;
+  }
+
+  try {
+    const command = currentRename.kind === 'project' ? 'rename_project' : 'rename_context';
+    await invoke(command, { from: currentRename.name, to, includeDone });
+    closeRenameDialog();
+    await refreshItems();
+  } catch (error) {
+    console.error('Failed to rename:', error);
+    alert('Failed to rename: ' + error);
+  }
+}
+
 function renderItems() {
   const listEl = document.getElementById('todo-list');
 
@@ -197,24 +283,65 @@ function renderItems() {
     }
 
     return `
-      <div class="todo-item ${item.done ? 'completed' : ''}">
+      <div class="todo-item ${item.done ? 'completed' : ''} ${item.blocked ? 'blocked' : ''}">
         ${!item.done 
           ? `<button class="btn-complete item-complete-btn" onclick="completeItem(${displayIndex})" title="Complete">☐</button>` 
           : `<button class="btn-uncomplete item-complete-btn" onclick="uncompleteItem(${displayIndex})" title="Uncomplete">☑</button>`}
         <div class="item-text" onclick="openEditDialog(${displayIndex})">
           ${item.priority !== null ? `<span class="priority ${priorityClass}">(${String.fromCharCode(65 + item.priority)})</span> ` : ''}
-          ${escapeHtml(item.description)}
-          ${item.projects.map(p => `<span class="project">+${escapeHtml(p)}</span>`).join(' ')}
-          ${item.contexts.map(c => `<span class="context">@${escapeHtml(c)}</span>`).join(' ')}
-          ${item.due ? `<span class="due">due:${item.due}</span>` : ''}
+          ${renderDescription(item, displayIndex)}
+          ${item.projects.map((p, i) => isInline(item, '+' + p) ? '' : tagChip('project', p, displayIndex, i)).join(' ')}
+          ${item.contexts.map((c, i) => isInline(item, '@' + c) ? '' : tagChip('context', c, displayIndex, i)).join(' ')}
+          ${item.due ? `<span class="due">${item.due_time ? `due ${formatTime(item.due, item.due_time)}` : `due:${item.due}`}</span>` : ''}
           ${item.recurrence ? `<span class="recurrence">rec:${item.recurrence}</span>` : ''}
-          ${item.threshold ? `<span class="threshold">t:${item.threshold}</span>` : ''}
+          ${item.blocked ? `<span class="blocked-badge" title="Waiting for another item">blocked</span>` : ''}
+          ${item.threshold ? `<span class="threshold">t:${item.threshold}${item.threshold_time ? ' ' + item.threshold_time : ''}</span>` : ''}
         </div>
       </div>
     `;
   }).join('');
 }
 
+// Whether a description word is the tag (`+garden`) typed in place, perhaps followed by
+// punctuation as in `+garden,`; the same test as TodoItem::is_inline.
+function isTagWord(word, tag) {
+  return word.startsWith(tag) && !/[\p{L}\p{N}]/u.test(word.slice(tag.length));
+}
+
+function isInline(item, tag) {
+  return item.description.split(/\s+/).some(word => isTagWord(word, tag));
+}
+
+// The description as typed, with the projects and contexts in it shown as chips in place.
+function renderDescription(item, displayIndex) {
+  return item.description.split(/\s+/).map(word => {
+    const project = item.projects.findIndex(p => isTagWord(word, '+' + p));
+    if (project >= 0) {
+      const name = item.projects[project];
+      return tagChip('project', name, displayIndex, project) + escapeHtml(word.slice(name.length + 1));
+    }
+    const context = item.contexts.findIndex(c => isTagWord(word, '@' + c));
+    if (context >= 0) {
+      const name = item.contexts[context];
+      return tagChip('context', name, displayIndex, context) + escapeHtml(word.slice(name.length + 1));
+    }
+    return escapeHtml(word);
+  }).join(' ');
+}
+
+// "in 3h" or "40m ago" for times less than a day away, otherwise the date and time.
+function formatTime(date, time) {
+  const minutes = Math.trunc((new Date(`${date}T${time}`) - new Date()) / 60000);
+  if (Math.abs(minutes) < 60) {
+    return minutes >= 0 ? `in ${minutes}m` : `${-minutes}m ago`;
+  }
+  if (Math.abs(minutes) < 24 * 60) {
+    const hours = Math.trunc(minutes / 60);
+    return hours > 0 ? `in ${hours}h` : `${-hours}h ago`;
+  }
+  return `${date} ${time}`;
+}
+
 function escapeHtml(text) {
   const div = document.createElement('div');
   div.textContent = text;
@@ -227,9 +354,10 @@ async function loadConfig() {
     document.getElementById('show-completed').checked = config.show_completed_items;
     document.getElementById('show-future').checked = config.show_future_items;
     document.getElementById('hide-no-date').checked = config.hide_no_date;
+    document.getElementById('hide-blocked').checked = config.hide_blocked;
     document.getElementById('reverse-sort').checked = config.reverse_sort;
   } catch (error) {
-    console.error('Failed to load config:', error);
+    alert('Failed to load settings: ' + error);
   }
 }
 
@@ -237,6 +365,7 @@ async function saveConfig() {
   const showCompleted = document.getElementById('show-completed').checked;
   const showFuture = document.getElementById('show-future').checked;
   const hideNoDate = document.getElementById('hide-no-date').checked;
+  const hideBlocked = document.getElementById('hide-blocked').checked;
   const reverseSort = document.getElementById('reverse-sort').checked;
 
   try {
@@ -244,6 +373,7 @@ async function saveConfig() {
       showCompletedItems: showCompleted,
       showFutureItems: showFuture,
       hideNoDate: hideNoDate,
+      hideBlocked: hideBlocked,
       reverseSort: reverseSort
     });
     await refreshItems();
@@ -278,6 +408,7 @@ window.addEventListener('DOMContentLoaded', async () => {
   document.getElementById('show-completed').addEventListener('change', saveConfig);
   document.getElementById('show-future').addEventListener('change', saveConfig);
   document.getElementById('hide-no-date').addEventListener('change', saveConfig);
+  document.getElementById('hide-blocked').addEventListener('change', saveConfig);
   document.getElementById('reverse-sort').addEventListener('change', saveConfig);
 
   document.getElementById('edit-cancel').addEventListener('click', closeEditDialog);
@@ -286,6 +417,12 @@ window.addEventListener('DOMContentLoaded', async () => {
     saveEdit();
   });
 
+  document.getElementById('rename-cancel').addEventListener('click', closeRenameDialog);
+  document.getElementById('rename-form').addEventListener('submit', (e) => {
+    e.preventDefault();
+    saveRename();
+  });
+
   await loadConfig();
   await updateFileName();
   await checkFileLoaded();
@@ -295,3 +432,4 @@ window.addEventListener('DOMContentLoaded', async () => {
 window.completeItem = completeItem;
 window.uncompleteItem = uncompleteItem;
 window.openEditDialog = openEditDialog;
+window.openRenameDialog = openRenameDialog;
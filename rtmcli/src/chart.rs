@@ -0,0 +1,211 @@
+//! Terminal and SVG rendering for `rtmcli chart`.
+
+use todotxt::todo_stats::FlowPoint;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Widest bar drawn in the terminal, in columns.
+const BAR_WIDTH: usize = 40;
+
+const SVG_WIDTH: usize = 600;
+const SVG_HEIGHT: usize = 240;
+const SVG_MARGIN: usize = 30;
+
+/// One character per value, scaled so the largest value is a full block.
+pub fn sparkline(values: &[usize]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| SPARKS[(v * (SPARKS.len() - 1)).checked_div(max).unwrap_or(0)])
+        .collect()
+}
+
+fn bar_length(value: usize, max: usize) -> usize {
+    if max == 0 {
+        0
+    } else {
+        (value * BAR_WIDTH).div_ceil(max)
+    }
+}
+
+/// A sparkline of the open count followed by one bar per day.
+pub fn render_burndown(title: &str, points: &[FlowPoint]) -> String {
+    let open: Vec<usize> = points.iter().map(|p| p.open).collect();
+    let max = open.iter().copied().max().unwrap_or(0);
+    let mut out = format!("{}\n{}\n\n", title, sparkline(&open));
+    for point in points {
+        out.push_str(&format!(
+            "{} {:<width$} {}\n",
+            point.date.format("%Y-%m-%d"),
+            "█".repeat(bar_length(point.open, max)),
+            point.open,
+            width = BAR_WIDTH
+        ));
+    }
+    out
+}
+
+/// One stacked bar per day: done items (`░`) then open items (`█`).
+pub fn render_flow(title: &str, points: &[FlowPoint]) -> String {
+    let max = points.iter().map(|p| p.open + p.done).max().unwrap_or(0);
+    let mut out = format!("{}\n░ done  █ open\n\n", title);
+    for point in points {
+        let done = bar_length(point.done, max);
+        let open = bar_length(point.open + point.done, max) - done;
+        out.push_str(&format!(
+            "{} {:<width$} {}/{}\n",
+            point.date.format("%Y-%m-%d"),
+            "░".repeat(done) + &"█".repeat(open),
+            point.done,
+            point.open,
+            width = BAR_WIDTH
+        ));
+    }
+    out
+}
+
+/// Maps the `index`th of `count` points and a value to SVG coordinates.
+fn svg_point(index: usize, count: usize, value: usize, max: usize) -> (usize, usize) {
+    let plot_width = SVG_WIDTH - 2 * SVG_MARGIN;
+    let plot_height = SVG_HEIGHT - 2 * SVG_MARGIN;
+    let x = SVG_MARGIN + index * plot_width / count.saturating_sub(1).max(1);
+    let y = SVG_HEIGHT - SVG_MARGIN - value * plot_height / max.max(1);
+    (x, y)
+}
+
+fn svg_document(title: &str, points: &[FlowPoint], max: usize, body: &str) -> String {
+    let axis_y = SVG_HEIGHT - SVG_MARGIN;
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"11\">\n\
+         <text x=\"{m}\" y=\"18\" font-size=\"14\">{title}</text>\n\
+         <line x1=\"{m}\" y1=\"{axis_y}\" x2=\"{right}\" y2=\"{axis_y}\" stroke=\"#888\"/>\n\
+         <line x1=\"{m}\" y1=\"{m}\" x2=\"{m}\" y2=\"{axis_y}\" stroke=\"#888\"/>\n\
+         <text x=\"{label_x}\" y=\"{m}\" text-anchor=\"end\">{max}</text>\n",
+        w = SVG_WIDTH,
+        h = SVG_HEIGHT,
+        m = SVG_MARGIN,
+        right = SVG_WIDTH - SVG_MARGIN,
+        label_x = SVG_MARGIN - 4,
+        title = escape_xml(title),
+    );
+    out.push_str(body);
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        out.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\">{}</text>\n<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n",
+            SVG_MARGIN,
+            SVG_HEIGHT - 10,
+            first.date.format("%Y-%m-%d"),
+            SVG_WIDTH - SVG_MARGIN,
+            SVG_HEIGHT - 10,
+            last.date.format("%Y-%m-%d")
+        ));
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+fn polyline(points: &[FlowPoint], max: usize, value: impl Fn(&FlowPoint) -> usize) -> String {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let (x, y) = svg_point(i, points.len(), value(p), max);
+            format!("{},{}", x, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A line chart of the open count.
+pub fn burndown_svg(title: &str, points: &[FlowPoint]) -> String {
+    let max = points.iter().map(|p| p.open).max().unwrap_or(0);
+    let body = format!(
+        "<polyline fill=\"none\" stroke=\"#d33\" stroke-width=\"2\" points=\"{}\"/>\n",
+        polyline(points, max, |p| p.open)
+    );
+    svg_document(title, points, max, &body)
+}
+
+/// Stacked areas: done items at the bottom, open items above them.
+pub fn flow_svg(title: &str, points: &[FlowPoint]) -> String {
+    let max = points.iter().map(|p| p.open + p.done).max().unwrap_or(0);
+    let (first, last) = match points.len() {
+        0 => return svg_document(title, points, max, ""),
+        n => (svg_point(0, n, 0, max).0, svg_point(n - 1, n, 0, max).0),
+    };
+    let base = SVG_HEIGHT - SVG_MARGIN;
+    let done = polyline(points, max, |p| p.done);
+    let total = polyline(points, max, |p| p.open + p.done);
+    let body = format!(
+        "<polygon fill=\"#4a4\" points=\"{first},{base} {done} {last},{base}\"/>\n\
+         <polygon fill=\"#48c\" points=\"{total} {done_reversed}\"/>\n",
+        done_reversed = done.split(' ').rev().collect::<Vec<_>>().join(" "),
+    );
+    svg_document(title, points, max, &body)
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn points() -> Vec<FlowPoint> {
+        [(4, 0), (3, 1), (1, 3)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(open, done))| FlowPoint {
+                date: NaiveDate::from_ymd_opt(2026, 10, 16 + i as u32).unwrap(),
+                open,
+                done,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 4, 7, 2]), "▁▅█▃");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+    }
+
+    #[test]
+    fn test_render_burndown() {
+        let chart = render_burndown("+work", &points());
+        let lines: Vec<&str> = chart.lines().collect();
+        assert_eq!(lines[1], "█▆▂");
+        assert_eq!(lines[3], format!("2026-10-16 {} 4", "█".repeat(BAR_WIDTH)));
+        assert_eq!(
+            lines[5],
+            format!("2026-10-18 {}{} 1", "█".repeat(10), " ".repeat(30))
+        );
+    }
+
+    #[test]
+    fn test_render_flow() {
+        let chart = render_flow("All items", &points());
+        assert!(chart.contains(&format!(
+            "2026-10-17 {}{} 1/3\n",
+            "░".repeat(10),
+            "█".repeat(30)
+        )));
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = flow_svg("A & B", &points());
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains("A &amp; B"));
+        assert!(
+            svg.contains(
+                "<polygon fill=\"#4a4\" points=\"30,210 30,210 300,165 570,75 570,210\"/>"
+            )
+        );
+        assert!(burndown_svg("x", &points()).contains("points=\"30,30 300,75 570,165\""));
+    }
+}
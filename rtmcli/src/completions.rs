@@ -0,0 +1,274 @@
+//! Shell completion. `rtmcli completions <shell>` prints a script that hands completion back
+//! to rtmcli (through clap_complete's `COMPLETE` environment variable), so besides commands
+//! and options it can offer what's in the todo file: projects, contexts and item ids.
+
+use crate::config::CliConfig;
+use clap::ValueEnum;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::{Bash, EnvCompleter, Fish, Powershell, Zsh};
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use todotxt::todo_hierarchy;
+use todotxt::{TodoItem, TodoLibrary};
+
+/// The environment variable the registration scripts set when calling back into rtmcli.
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// The filter names accepted by `list` and `complete`.
+const FILTERS: [&str; 5] = ["today", "week", "all", "overdue", "future"];
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+/// Writes the registration script for `shell` to standard output.
+pub fn print_registration(shell: Shell) -> std::io::Result<()> {
+    let completer: &dyn EnvCompleter = match shell {
+        Shell::Bash => &Bash,
+        Shell::Zsh => &Zsh,
+        Shell::Fish => &Fish,
+        Shell::Powershell => &Powershell,
+    };
+    completer.write_registration(
+        COMPLETE_VAR,
+        "rtmcli",
+        "rtmcli",
+        "rtmcli",
+        &mut std::io::stdout(),
+    )
+}
+
+/// Items of the todo file named on the command line being completed, as `-f`/`--file` or
+/// `-l`/`--list`, falling back to `TODOTXT` and `todo.txt` as rtmcli itself does. A file that
+/// can't be read completes nothing.
+fn items() -> Vec<TodoItem> {
+    let args: Vec<String> = std::env::args().collect();
+    let value_of = |short: &str, long: &str| {
+        args.windows(2).find_map(|pair| {
+            if pair[0] == short || pair[0] == long {
+                Some(pair[1].clone())
+            } else {
+                pair[0]
+                    .strip_prefix(&format!("{}=", long))
+                    .map(str::to_string)
+            }
+        })
+    };
+    let file_name = match value_of("-l", "--list") {
+        Some(list) => match CliConfig::load().unwrap_or_default().list(&list) {
+            Some(list) => list.file_name.clone(),
+            None => return Vec::new(),
+        },
+        None => value_of("-f", "--file")
+            .or_else(|| std::env::var("TODOTXT").ok())
+            .unwrap_or_else(|| "todo.txt".to_string()),
+    };
+    let mut lib = TodoLibrary::new(file_name);
+    lib.grammar = CliConfig::load().unwrap_or_default().names;
+    match lib.load() {
+        Ok(()) => lib.items,
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Every project in use, with the parents of subprojects, which `list` accepts too.
+fn projects(items: &[TodoItem]) -> BTreeSet<String> {
+    items
+        .iter()
+        .flat_map(|item| &item.projects)
+        .flat_map(|p| todo_hierarchy::ancestors(&p.name))
+        .map(str::to_string)
+        .collect()
+}
+
+fn contexts(items: &[TodoItem]) -> BTreeSet<String> {
+    items
+        .iter()
+        .flat_map(|item| &item.contexts)
+        .flat_map(|c| todo_hierarchy::ancestors(&c.name))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Open items by their 1-based index and, where they have one, their uuid, with the
+/// description as help text.
+fn item_ids(items: &[TodoItem]) -> Vec<CompletionCandidate> {
+    let mut candidates = Vec::new();
+    for (n, item) in items.iter().filter(|item| !item.done).enumerate() {
+        let help = Some(item.description.clone().into());
+        candidates.push(CompletionCandidate::new((n + 1).to_string()).help(help.clone()));
+        if let Some(uuid) = item.uuid {
+            candidates.push(CompletionCandidate::new(uuid.to_string()).help(help));
+        }
+    }
+    candidates
+}
+
+/// For an item id, as taken by `move`.
+pub fn complete_id(current: &OsStr) -> Vec<CompletionCandidate> {
+    starting_with(item_ids(&items()), current)
+}
+
+/// For `complete`'s first argument, which is a filter name or an item id.
+pub fn complete_filter_or_id(current: &OsStr) -> Vec<CompletionCandidate> {
+    let candidates = FILTERS
+        .iter()
+        .map(CompletionCandidate::new)
+        .chain(item_ids(&items()))
+        .collect();
+    starting_with(candidates, current)
+}
+
+/// For a `list` term: a filter name, `actionable`, `blocked`, `unblocked`, `+project` or
+/// `@context`.
+pub fn complete_list_term(current: &OsStr) -> Vec<CompletionCandidate> {
+    let items = items();
+    let candidates = FILTERS
+        .iter()
+        .chain(["actionable", "blocked", "unblocked"].iter())
+        .map(CompletionCandidate::new)
+        .chain(
+            projects(&items)
+                .into_iter()
+                .map(|p| CompletionCandidate::new(format!("+{}", p))),
+        )
+        .chain(
+            contexts(&items)
+                .into_iter()
+                .map(|c| CompletionCandidate::new(format!("@{}", c))),
+        )
+        .collect();
+    starting_with(candidates, current)
+}
+
+/// For a `+project` argument.
+pub fn complete_project(current: &OsStr) -> Vec<CompletionCandidate> {
+    let candidates = projects(&items())
+        .into_iter()
+        .map(|name| CompletionCandidate::new(format!("+{}", name)))
+        .collect();
+    starting_with(candidates, current)
+}
+
+/// For a project name given without its `+`.
+pub fn complete_project_name(current: &OsStr) -> Vec<CompletionCandidate> {
+    let candidates = projects(&items())
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect();
+    starting_with(candidates, current)
+}
+
+/// For a context name given without its `@`.
+pub fn complete_context_name(current: &OsStr) -> Vec<CompletionCandidate> {
+    let candidates = contexts(&items())
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect();
+    starting_with(candidates, current)
+}
+
+/// For a list name from the config file.
+pub fn complete_list(current: &OsStr) -> Vec<CompletionCandidate> {
+    let candidates = CliConfig::load()
+        .unwrap_or_default()
+        .lists
+        .iter()
+        .map(|list| CompletionCandidate::new(&list.name))
+        .collect();
+    starting_with(candidates, current)
+}
+
+/// For a todo.txt line: completes a trailing `+project` or `@context` word, keeping the rest
+/// of the line as typed.
+pub fn complete_description(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let (head, word) = match current.rfind(' ') {
+        Some(i) => current.split_at(i + 1),
+        None => ("", current.as_ref()),
+    };
+    let items = items();
+    let names = match word.chars().next() {
+        Some('+') => projects(&items),
+        Some('@') => contexts(&items),
+        _ => return Vec::new(),
+    };
+    let sigil = &word[..1];
+    names
+        .into_iter()
+        .map(|name| format!("{}{}", sigil, name))
+        .filter(|tag| tag.starts_with(word))
+        .map(|tag| CompletionCandidate::new(format!("{}{}", head, tag)))
+        .collect()
+}
+
+fn starting_with(
+    candidates: Vec<CompletionCandidate>,
+    current: &OsStr,
+) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    candidates
+        .into_iter()
+        .filter(|c| {
+            c.get_value()
+                .to_string_lossy()
+                .starts_with(current.as_ref())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<TodoItem> {
+        [
+            "Plan release +work @office uuid:67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "x Book venue +party",
+            "Buy milk +home @store",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect()
+    }
+
+    fn values(candidates: Vec<CompletionCandidate>) -> Vec<String> {
+        candidates
+            .iter()
+            .map(|c| c.get_value().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_item_ids_skip_done_items() {
+        assert_eq!(
+            values(item_ids(&items())),
+            vec!["1", "67e55044-10b1-426f-9247-bb680e5fe0c8", "2"]
+        );
+    }
+
+    #[test]
+    fn test_projects_and_contexts() {
+        assert_eq!(
+            projects(&items()).into_iter().collect::<Vec<_>>(),
+            vec!["home", "party", "work"]
+        );
+        assert_eq!(
+            contexts(&items()).into_iter().collect::<Vec<_>>(),
+            vec!["office", "store"]
+        );
+    }
+
+    #[test]
+    fn test_starting_with() {
+        let candidates = FILTERS.iter().map(CompletionCandidate::new).collect();
+        assert_eq!(
+            values(starting_with(candidates, OsStr::new("w"))),
+            vec!["week"]
+        );
+    }
+}
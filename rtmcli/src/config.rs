@@ -0,0 +1,223 @@
+use crate::render::Theme;
+use chrono::format::{Item, StrftimeItems};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use todotxt::TodoWorkspace;
+use todotxt::todo_names::NameGrammar;
+
+/// The parts of the shared `rtm/config.toml` (written by rtmapp) that rtmcli reads. Keys the
+/// CLI doesn't know about are ignored, so the GUI is free to keep its own settings there.
+#[derive(Deserialize, Default, Debug)]
+pub struct CliConfig {
+    /// Todo file used when neither `-f`, `--list` nor `TODOTXT` names one. rtmapp keeps the
+    /// file it last opened here.
+    #[serde(default)]
+    pub file_name: Option<String>,
+    /// Done file for `report` and `chart` when the todo file isn't part of a list.
+    #[serde(default)]
+    pub done_file_name: Option<String>,
+    /// `list` terms used when none are given, e.g. `"week"` or `"+work actionable"`.
+    #[serde(default)]
+    pub default_filter: Option<String>,
+    #[serde(default)]
+    pub sort: Option<SortOrder>,
+    /// chrono format for dates shown by `list` beyond a week away, e.g. `"%d.%m.%Y"`. The
+    /// file always keeps todo.txt's `YYYY-MM-DD`.
+    #[serde(default)]
+    pub date_format: Option<String>,
+    #[serde(default)]
+    pub theme: Option<Theme>,
+    /// `modify` asks before changing more items than this; 10 if unset.
+    #[serde(default)]
+    pub confirm_above: Option<usize>,
+    /// Where `timer` keeps its log; `timelog.txt` next to the todo file if unset.
+    #[serde(default)]
+    pub time_log_file_name: Option<String>,
+    /// Descriptions, colors and archived flags for projects and contexts; `projects.toml`
+    /// next to the todo file if unset.
+    #[serde(default)]
+    pub projects_file_name: Option<String>,
+    /// How `+project` and `@context` names are read and matched, as a `[names]` table with
+    /// `extra_chars`, `strip_trailing` and `case_insensitive`.
+    #[serde(default)]
+    pub names: NameGrammar,
+    /// `key:value` tags that `doctor` accepts besides the ones todotxt reads, e.g. `["color"]`
+    /// for another tool's extension.
+    #[serde(default)]
+    pub known_tags: Vec<String>,
+    /// Extra commands, e.g. `w = "list +work actionable"` makes `rtmcli w` run that.
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    #[serde(default)]
+    pub lists: Vec<ListConfig>,
+}
+
+/// Order of items in `list` output.
+#[derive(Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// As they are in the file
+    #[default]
+    File,
+    /// Highest priority first
+    Priority,
+    /// Earliest due date first
+    Due,
+}
+
+/// A named todo list, as stored in the `[[lists]]` array of the config file.
+#[derive(Deserialize, Clone, Debug)]
+pub struct ListConfig {
+    pub name: String,
+    pub file_name: String,
+    #[serde(default)]
+    pub done_file_name: Option<String>,
+}
+
+impl CliConfig {
+    /// Reads the config file. A missing file yields the default (empty) config, matching
+    /// how rtmapp treats it, so `-f`/`TODOTXT` keep working without one. A file that can't
+    /// be read or parsed is an error rather than silently ignored.
+    pub fn load() -> Result<Self, String> {
+        let Some(path) = config_path() else {
+            return Ok(Self::default());
+        };
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(format!("Error reading '{}': {}", path.display(), e)),
+        };
+        Self::parse(&content).map_err(|e| format!("Error in '{}': {}", path.display(), e))
+    }
+
+    /// Parses the config file's content, rejecting a `date_format` chrono can't format with,
+    /// which would otherwise only fail once a date is printed.
+    fn parse(content: &str) -> Result<Self, String> {
+        let config: CliConfig = toml::from_str(content).map_err(|e| e.to_string())?;
+        if let Some(ref format) = config.date_format
+            && StrftimeItems::new(format).any(|item| item == Item::Error)
+        {
+            return Err(format!("invalid date_format '{}'", format));
+        }
+        Ok(config)
+    }
+
+    /// Expands an alias in `args` (the program name first, as from `std::env::args`): the
+    /// first word that isn't a global option is replaced by the words of its alias. Built-in
+    /// commands can't be shadowed, and aliases aren't expanded recursively.
+    pub fn expand_alias(&self, mut args: Vec<String>, builtins: &[&str]) -> Vec<String> {
+        let mut i = 1;
+        while i < args.len() {
+            match args[i].as_str() {
+                "-f" | "--file" | "-l" | "--list" => i += 2,
+                arg if arg.starts_with('-') => i += 1,
+                _ => break,
+            }
+        }
+        if let Some(command) = args.get(i)
+            && !builtins.contains(&command.as_str())
+            && let Some(expansion) = self.aliases.get(command)
+        {
+            let words: Vec<String> = expansion.split_whitespace().map(str::to_string).collect();
+            args.splice(i..=i, words);
+        }
+        args
+    }
+
+    pub fn list(&self, name: &str) -> Option<&ListConfig> {
+        self.lists.iter().find(|l| l.name == name)
+    }
+
+    pub fn workspace(&self) -> TodoWorkspace {
+        let mut workspace = TodoWorkspace::new();
+        for list in &self.lists {
+            // A duplicated name in a hand-edited config keeps its first definition, which is
+            // also the one `list` resolves to.
+            let _ = workspace.add_list(
+                list.name.clone(),
+                list.file_name.clone(),
+                list.done_file_name.clone(),
+            );
+        }
+        for list in &mut workspace.lists {
+            list.library.grammar = self.names.clone();
+            if let Some(ref mut done) = list.done {
+                done.grammar = self.names.clone();
+            }
+        }
+        workspace
+    }
+}
+
+/// `RTM_CONFIG` overrides the location, otherwise it is the same file rtmapp uses:
+/// `<config dir>/rtm/config.toml`.
+pub fn config_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("RTM_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let mut path = dirs::config_dir()?;
+    path.push("rtm");
+    path.push("config.toml");
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_shared_config() {
+        let config = CliConfig::parse(
+            r#"
+            file_name = "/tmp/todo.txt"
+            show_completed_items = true
+            sort = "due"
+
+            [aliases]
+            w = "list +work actionable"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.file_name.as_deref(), Some("/tmp/todo.txt"));
+        assert_eq!(config.sort, Some(SortOrder::Due));
+        assert_eq!(config.aliases["w"], "list +work actionable");
+        assert!(config.lists.is_empty());
+
+        assert!(CliConfig::parse("sort = ").is_err());
+        assert_eq!(
+            CliConfig::parse("date_format = \"%Q\"").unwrap_err(),
+            "invalid date_format '%Q'"
+        );
+        assert!(CliConfig::parse("date_format = \"%d.%m.%Y\"").is_ok());
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let config = CliConfig {
+            aliases: BTreeMap::from([
+                ("w".to_string(), "list +work actionable".to_string()),
+                ("list".to_string(), "add oops".to_string()),
+            ]),
+            ..Default::default()
+        };
+        let builtins = ["list", "add"];
+        assert_eq!(
+            config.expand_alias(args("rtmcli -f todo.txt w today"), &builtins),
+            args("rtmcli -f todo.txt list +work actionable today")
+        );
+        assert_eq!(
+            config.expand_alias(args("rtmcli list"), &builtins),
+            args("rtmcli list")
+        );
+        assert_eq!(
+            config.expand_alias(args("rtmcli x"), &builtins),
+            args("rtmcli x")
+        );
+    }
+}
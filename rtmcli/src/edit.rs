@@ -0,0 +1,252 @@
+//! `rtmcli edit`: round-trips selected items through `$EDITOR`.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use todotxt::todo_names::NameGrammar;
+use todotxt::{TodoItem, TodoLibrary};
+
+const HEADER: &str = "# Edit, delete or add todo.txt lines; removing a line deletes its item.\n\
+                      # Lines starting with # are ignored.\n";
+
+/// What applying an edit did.
+#[derive(Debug, Default, PartialEq)]
+pub struct EditSummary {
+    pub updated: usize,
+    pub deleted: usize,
+    pub added: usize,
+}
+
+impl EditSummary {
+    pub fn is_empty(&self) -> bool {
+        *self == EditSummary::default()
+    }
+}
+
+/// Writes the items at `selected` (indices into `lib`, in file order) to a temp file, opens
+/// it in the user's editor and applies the result to `lib`. Nothing is applied unless every
+/// line parses; the errors are returned instead, and the temp file is kept so the edit isn't
+/// lost.
+pub fn edit_in_editor(lib: &mut TodoLibrary, selected: &[usize]) -> Result<EditSummary, String> {
+    let mut content = HEADER.to_string();
+    for &index in selected {
+        content.push_str(&lib.items[index].to_string());
+        content.push('\n');
+    }
+    let (path, mut file) =
+        create_temp_file().map_err(|e| format!("Error creating temp file: {}", e))?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| format!("Error writing '{}': {}", path.display(), e))?;
+    drop(file);
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Split so that editors configured with arguments, like `code --wait`, work.
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status()
+        .map_err(|e| format!("Error running editor '{}': {}", editor, e))?;
+    if !status.success() {
+        return Err(format!(
+            "Editor '{}' failed; edits left in '{}'",
+            editor,
+            path.display()
+        ));
+    }
+
+    let edited = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Error reading '{}': {}", path.display(), e))?;
+    let items = parse_lines(&edited, &lib.grammar).map_err(|errors| {
+        format!(
+            "{}\nNothing was changed; edits left in '{}'",
+            errors.join("\n"),
+            path.display()
+        )
+    })?;
+    let _ = std::fs::remove_file(&path);
+    Ok(apply(lib, selected, items))
+}
+
+/// Creates a new file in the temp dir that only the user can read. The file must not exist
+/// yet, so a file or symlink planted at a guessable name by someone else is never written
+/// through; the next name is tried instead.
+fn create_temp_file() -> std::io::Result<(PathBuf, File)> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut attempt = 0;
+    loop {
+        let path = std::env::temp_dir().join(format!(
+            "rtmcli-edit-{}-{}.txt",
+            std::process::id(),
+            attempt
+        ));
+        match options.open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && attempt < 100 => {
+                attempt += 1
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Parses the edited file, skipping blank and `#` comment lines, or returns one message per
+/// line that isn't a valid todo.
+fn parse_lines(text: &str, grammar: &NameGrammar) -> Result<Vec<TodoItem>, Vec<String>> {
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match TodoItem::parse_with(line, grammar) {
+            Ok(item) => items.push(item),
+            Err(e) => errors.push(format!("Line {}: {:?}: {}", n + 1, line, e)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(items)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Applies the edited lines to the items at `selected`. Lines that are unchanged are matched
+/// to their items first (a longest common subsequence), so deleting or inserting a line
+/// doesn't turn every line after it into an update. The remaining items are updated in order
+/// with the remaining lines; items left over are deleted and lines left over are added.
+fn apply(lib: &mut TodoLibrary, selected: &[usize], edited: Vec<TodoItem>) -> EditSummary {
+    let old: Vec<String> = selected.iter().map(|&i| lib.items[i].to_string()).collect();
+    let new: Vec<String> = edited.iter().map(|item| item.to_string()).collect();
+
+    // lcs[i][j]: length of the common subsequence of old[i..] and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut unmatched_old = Vec::new();
+    let mut unmatched_new = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            unmatched_old.push(selected[i]);
+            i += 1;
+        } else {
+            unmatched_new.push(j);
+            j += 1;
+        }
+    }
+
+    let mut summary = EditSummary::default();
+    let mut edited: Vec<Option<TodoItem>> = edited.into_iter().map(Some).collect();
+    let pairs = unmatched_old.len().min(unmatched_new.len());
+    for (&index, &line) in unmatched_old.iter().zip(&unmatched_new) {
+        lib.items[index] = edited[line].take().unwrap();
+        summary.updated += 1;
+    }
+    for &index in unmatched_old[pairs..].iter().rev() {
+        lib.remove_item(index);
+        summary.deleted += 1;
+    }
+    for &line in &unmatched_new[pairs..] {
+        lib.add_item(edited[line].take().unwrap());
+        summary.added += 1;
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library(lines: &[&str]) -> TodoLibrary {
+        let mut lib = TodoLibrary::new(String::new());
+        lib.items = lines.iter().map(|s| s.parse().unwrap()).collect();
+        lib
+    }
+
+    fn parse(lines: &[&str]) -> Vec<TodoItem> {
+        lines.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    fn texts(lib: &TodoLibrary) -> Vec<String> {
+        lib.items.iter().map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn test_deleting_a_line_only_deletes_that_item() {
+        let mut lib = library(&["A", "B", "C", "D"]);
+        let summary = apply(&mut lib, &[0, 1, 2], parse(&["B", "C"]));
+        assert_eq!(
+            summary,
+            EditSummary {
+                deleted: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(texts(&lib), vec!["B", "C", "D"]);
+    }
+
+    #[test]
+    fn test_update_and_add() {
+        let mut lib = library(&["2026-10-01 A", "2026-10-01 B", "2026-10-01 C"]);
+        let summary = apply(
+            &mut lib,
+            &[0, 2],
+            parse(&["2026-10-01 A", "2026-10-01 C changed", "2026-10-02 New"]),
+        );
+        assert_eq!(
+            summary,
+            EditSummary {
+                updated: 1,
+                added: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            texts(&lib),
+            vec![
+                "2026-10-01 A",
+                "2026-10-01 B",
+                "2026-10-01 C changed",
+                "2026-10-02 New"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_lines_reports_every_error() {
+        let errors = parse_lines(
+            "# header\n\nGood line\n(A) ok due:tomorrow\nbad rec:zz\n",
+            &NameGrammar::default(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("Line 4: "));
+        assert!(errors[1].starts_with("Line 5: "));
+        assert_eq!(
+            parse_lines("# only a comment\n", &NameGrammar::default())
+                .unwrap()
+                .len(),
+            0
+        );
+    }
+}
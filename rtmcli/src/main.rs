@@ -1,7 +1,35 @@
-use chrono::{Duration, Local};
-use clap::{Parser, Subcommand, ValueEnum};
+mod chart;
+mod completions;
+mod config;
+mod edit;
+mod modify;
+mod render;
+mod serve;
+mod timer;
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use clap::builder::RangedU64ValueParser;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::engine::ArgValueCompleter;
+use clap_complete::env::CompleteEnv;
+use config::{CliConfig, SortOrder};
+use render::{ColorChoice, Renderer, Row};
+use std::collections::HashSet;
 use todotxt::TodoItem;
 use todotxt::TodoLibrary;
+use todotxt::TodoWorkspace;
+use todotxt::todo_deps;
+use todotxt::todo_ical::{IcalComponent, IcalExportOptions};
+use todotxt::todo_item::parse_date_time;
+use todotxt::todo_lint::{self, LintOptions};
+use todotxt::todo_names::NameGrammar;
+use todotxt::todo_report::{ReportFormat, ReportGrouping, ReportOptions};
+use todotxt::todo_stats::{self, StatsOptions, StatsPeriod};
+use todotxt::todo_tags::{TagMetadataFile, context_summaries, project_summaries};
+use uuid::Uuid;
+
+/// Upper bound on `report -n` and `chart -n`; see [`todo_stats::MAX_PERIODS`].
+const MAX_PERIODS: u64 = todo_stats::MAX_PERIODS as u64;
 
 #[derive(Parser)]
 #[command(name = "rtmcli")]
@@ -11,6 +39,10 @@ struct Cli {
     #[arg(short = 'f', long)]
     file: Option<String>,
 
+    /// Named todo list from the config file, instead of a file name
+    #[arg(short = 'l', long, conflicts_with = "file", add = ArgValueCompleter::new(completions::complete_list))]
+    list: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -23,23 +55,370 @@ enum Commands {
         #[arg(short, long)]
         completed: bool,
 
-        /// Filter by due date
-        #[arg(value_enum)]
-        filter: Option<Filter>,
+        /// Date filter (today, week, all, overdue, future), +project or @context (including
+        /// subprojects like +project.sub and subcontexts like @context:sub),
+        /// actionable (not waiting on a future threshold date), blocked (waiting on an open
+        /// dep: item) or unblocked; all must match
+        #[arg(add = ArgValueCompleter::new(completions::complete_list_term))]
+        terms: Vec<String>,
+
+        /// Sort order; defaults to `sort` in the config file, else file order
+        #[arg(short, long, value_enum)]
+        sort: Option<SortOrder>,
+
+        /// Show items under a heading per project or context
+        #[arg(short, long, value_enum)]
+        group: Option<Grouping>,
+
+        /// Print plain todo.txt lines instead of the aligned, colored layout
+        #[arg(long)]
+        raw: bool,
+
+        /// When to use colors
+        #[arg(long, value_enum, default_value_t)]
+        color: ColorChoice,
+
+        /// List items from every list in the config file
+        #[arg(long)]
+        all_lists: bool,
     },
     /// Add a new item
     Add {
         /// Todo description in Todo.txt format
+        #[arg(add = ArgValueCompleter::new(completions::complete_description))]
         description: String,
     },
     /// Complete items by filter or identifier
     Complete {
         /// First arg: filter or identifier
+        #[arg(add = ArgValueCompleter::new(completions::complete_filter_or_id))]
         arg1: String,
 
         /// Second arg: optional index or identifier
+        #[arg(add = ArgValueCompleter::new(completions::complete_id))]
         arg2: Option<String>,
     },
+    /// Edit items in $VISUAL or $EDITOR: one item by index or uuid, or the open items
+    /// matching list terms (all of them if none are given)
+    Edit {
+        /// An index or uuid, or list terms such as `today` or `+work`
+        #[arg(add = ArgValueCompleter::new(completions::complete_list_term))]
+        terms: Vec<String>,
+    },
+    /// Change every open item matching the terms: tags, priority, dates or recurrence
+    Modify {
+        /// An index or uuid, or list terms such as `today` or `+work`
+        #[arg(required = true, add = ArgValueCompleter::new(completions::complete_list_term))]
+        terms: Vec<String>,
+
+        #[command(flatten)]
+        changes: modify::ChangeArgs,
+
+        /// Show what would change without saving
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
+        /// Don't ask for confirmation, however many items match
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Make an item wait for another with a dep: tag, or stop it waiting
+    Depend {
+        /// Index or uuid of the item that waits
+        #[arg(add = ArgValueCompleter::new(completions::complete_id))]
+        id: String,
+
+        /// Index or uuid of the item it waits for
+        #[arg(add = ArgValueCompleter::new(completions::complete_id))]
+        on: String,
+
+        /// Remove the dependency instead
+        #[arg(short, long)]
+        remove: bool,
+    },
+    /// Rename or merge projects
+    Project {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+    /// Rename or merge contexts
+    Context {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+    /// Show every project in use with its open and done items, next due date and last
+    /// activity
+    Projects(TagListArgs),
+    /// Show every context in use with its open and done items, next due date and last
+    /// activity
+    Contexts(TagListArgs),
+    /// Check the todo and done files for mistakes: lines that don't parse, contradicting
+    /// dates or priorities, duplicate or dangling uuids, overdue recurring items, stray
+    /// whitespace, unknown tags and suspicious names
+    Doctor {
+        /// Repair what can be repaired safely: trailing whitespace, blank lines and a
+        /// pri: tag repeating the (A) priority
+        #[arg(long)]
+        fix: bool,
+
+        /// Done file to check too; defaults to the list's done file, or done.txt next to
+        /// the todo file
+        #[arg(short, long)]
+        done: Option<String>,
+    },
+    /// Show the todo lists defined in the config file
+    Lists,
+    /// Move an item to another todo list
+    Move {
+        /// Index or uuid of the item to move
+        #[arg(add = ArgValueCompleter::new(completions::complete_id))]
+        id: String,
+
+        /// Name of the list to move it to
+        #[arg(add = ArgValueCompleter::new(completions::complete_list))]
+        to: String,
+    },
+    /// Export items to another format
+    Export {
+        #[command(subcommand)]
+        format: ExportFormat,
+    },
+    /// Import items from another format
+    Import {
+        #[command(subcommand)]
+        format: ImportFormat,
+    },
+    /// Show statistics: throughput, lead time, overdue items and per-tag breakdowns
+    Report {
+        /// Output JSON instead of text
+        #[arg(long)]
+        json: bool,
+
+        /// Count throughput per week instead of per day
+        #[arg(short, long)]
+        weekly: bool,
+
+        /// Number of days (or weeks) of throughput to show
+        #[arg(
+            short = 'n',
+            long,
+            default_value_t = 14,
+            value_parser = RangedU64ValueParser::<usize>::new().range(0..=MAX_PERIODS)
+        )]
+        periods: usize,
+
+        /// Done file to include; defaults to the list's done file, or done.txt next to
+        /// the todo file
+        #[arg(short, long)]
+        done: Option<String>,
+    },
+    /// Chart open items over time
+    Chart {
+        #[command(subcommand)]
+        kind: ChartKind,
+    },
+    /// Print a shell completion script
+    ///
+    /// For example, add `source <(rtmcli completions bash)` to ~/.bashrc. Completion calls
+    /// back into rtmcli, so it also offers the projects, contexts and ids in the todo file.
+    Completions {
+        #[arg(value_enum)]
+        shell: completions::Shell,
+    },
+    /// Track time spent on items
+    Timer {
+        #[command(subcommand)]
+        action: timer::TimerAction,
+    },
+    /// Serve a REST/JSON API for the todo file on localhost. Requests whose Host or Origin
+    /// isn't localhost are refused, so web pages can't reach it.
+    Serve {
+        /// Port to listen on
+        #[arg(short, long, default_value_t = 8765)]
+        port: u16,
+
+        /// Require `Authorization: Bearer <token>` on every request
+        #[arg(long, env = "RTM_TOKEN")]
+        token: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ChartKind {
+    /// Open items per day
+    Burndown(ChartArgs),
+    /// Done and open items per day, stacked
+    Flow(ChartArgs),
+}
+
+#[derive(Args)]
+struct ChartArgs {
+    /// Only items in this project or its subprojects, e.g. +work
+    #[arg(add = ArgValueCompleter::new(completions::complete_project))]
+    project: Option<String>,
+
+    /// Number of days to chart, ending today
+    #[arg(
+        short = 'n',
+        long,
+        default_value_t = 14,
+        value_parser = RangedU64ValueParser::<u32>::new().range(0..=MAX_PERIODS)
+    )]
+    days: u32,
+
+    /// Write an SVG chart to this file instead of drawing in the terminal
+    #[arg(long)]
+    svg: Option<String>,
+
+    /// Done file to include; defaults to the list's done file, or done.txt next to
+    /// the todo file
+    #[arg(short, long)]
+    done: Option<String>,
+}
+
+#[derive(Args)]
+struct TagListArgs {
+    /// Include archived ones, as marked in the projects file
+    #[arg(short, long)]
+    all: bool,
+
+    /// Output JSON instead of text
+    #[arg(long)]
+    json: bool,
+
+    /// Report suspicious names instead: ones with trailing punctuation, without letters,
+    /// differing only in case, or not valid under the `[names]` settings
+    #[arg(long)]
+    lint: bool,
+
+    /// Done file to include; defaults to the list's done file, or done.txt next to
+    /// the todo file
+    #[arg(short, long)]
+    done: Option<String>,
+}
+
+/// What `project` and `context` do. Names may be given with or without their sigil.
+#[derive(Subcommand)]
+enum TagAction {
+    /// Rename one, and everything within it, in every item, including the tags typed in
+    /// the description; other words are left alone
+    Rename {
+        from: String,
+        to: String,
+
+        /// Rename in the done file too: the list's, or done.txt next to the todo file
+        #[arg(short, long)]
+        done: bool,
+    },
+    /// Merge several into one, which may already exist
+    Merge {
+        #[arg(required = true)]
+        from: Vec<String>,
+
+        #[arg(long)]
+        into: String,
+
+        /// Merge in the done file too: the list's, or done.txt next to the todo file
+        #[arg(short, long)]
+        done: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ImportFormat {
+    /// Tasks (VTODOs) from an iCalendar file
+    Ics {
+        /// The .ics file to read
+        file: String,
+    },
+    /// Tasks from Taskwarrior JSON, as written by `task export`
+    Taskwarrior {
+        /// The JSON file to read, or - for standard input
+        file: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportFormat {
+    /// iCalendar file of the items that have a due or threshold date
+    Ics {
+        /// Write to this file instead of standard output
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Export all-day events instead of tasks
+        #[arg(long)]
+        events: bool,
+
+        /// Include completed items
+        #[arg(short, long)]
+        completed: bool,
+    },
+    /// Taskwarrior JSON, as read by `task import`, of all items
+    Taskwarrior {
+        /// Write to this file instead of standard output
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Markdown checklist report grouped by project or context
+    Md(ReportArgs),
+    /// Org-mode report grouped by project or context
+    Org(ReportArgs),
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    /// Write to this file instead of standard output
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Group items by project or by context
+    #[arg(long, value_enum, default_value = "project")]
+    by: Grouping,
+
+    /// Include completed items
+    #[arg(short, long)]
+    completed: bool,
+
+    /// Only items in this project or its subprojects (repeatable, without the +)
+    #[arg(short, long, add = ArgValueCompleter::new(completions::complete_project_name))]
+    project: Vec<String>,
+
+    /// Only items in this context or the ones within it (repeatable, without the @)
+    #[arg(short = 'x', long, add = ArgValueCompleter::new(completions::complete_context_name))]
+    context: Vec<String>,
+
+    /// Report title
+    #[arg(short, long)]
+    title: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq)]
+pub enum Grouping {
+    Project,
+    Context,
+}
+
+impl ReportArgs {
+    fn options(
+        self,
+        format: ReportFormat,
+        grammar: &NameGrammar,
+    ) -> (Option<String>, ReportOptions) {
+        let options = ReportOptions {
+            format,
+            group_by: match self.by {
+                Grouping::Project => ReportGrouping::Project,
+                Grouping::Context => ReportGrouping::Context,
+            },
+            title: self.title,
+            include_done: self.completed,
+            projects: self.project,
+            contexts: self.context,
+            grammar: grammar.clone(),
+        };
+        (self.output, options)
+    }
 }
 
 #[derive(ValueEnum, Clone, PartialEq)]
@@ -51,6 +430,134 @@ enum Filter {
     Future,
 }
 
+/// The terms given to `list`, all of which an item must match.
+#[derive(Default)]
+struct ListQuery {
+    filter: Option<Filter>,
+    projects: Vec<String>,
+    contexts: Vec<String>,
+    actionable: bool,
+    /// `blocked` or `unblocked`.
+    blocked: Option<bool>,
+    grammar: NameGrammar,
+}
+
+impl ListQuery {
+    fn parse(terms: &[String], grammar: &NameGrammar) -> Result<Self, String> {
+        let mut query = ListQuery {
+            grammar: grammar.clone(),
+            ..ListQuery::default()
+        };
+        for term in terms {
+            if let Some(project) = term.strip_prefix('+') {
+                query.projects.push(project.to_string());
+            } else if let Some(context) = term.strip_prefix('@') {
+                query.contexts.push(context.to_string());
+            } else if term == "actionable" {
+                query.actionable = true;
+            } else if term == "blocked" || term == "unblocked" {
+                query.blocked = Some(term == "blocked");
+            } else if let Some(filter) = parse_filter(term) {
+                query.filter = Some(filter);
+            } else {
+                return Err(format!("Unknown list term '{}'", term));
+            }
+        }
+        Ok(query)
+    }
+
+    /// Whether `item` matches, `open` being the uuids of the open items it may wait for.
+    fn matches(&self, item: &TodoItem, now: NaiveDateTime, open: &HashSet<Uuid>) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|f| filter_matches(f, item, now))
+            && self.projects.iter().all(|name| {
+                item.projects
+                    .iter()
+                    .any(|p| self.grammar.is_within(&p.name, name))
+            })
+            && self.contexts.iter().all(|name| {
+                item.contexts
+                    .iter()
+                    .any(|c| self.grammar.is_within(&c.name, name))
+            })
+            && (!self.actionable || item.threshold_at().is_none_or(|t| t <= now))
+            && self
+                .blocked
+                .is_none_or(|blocked| todo_deps::is_blocked(item, open) == blocked)
+    }
+}
+
+fn sort_rows(rows: &mut [Row], order: SortOrder) {
+    match order {
+        SortOrder::File => {}
+        // Items without a priority or due date go last.
+        SortOrder::Priority => {
+            rows.sort_by_key(|row| row.item.priority.priority.unwrap_or(u8::MAX))
+        }
+        SortOrder::Due => rows.sort_by_key(|row| (row.item.due.is_none(), row.item.due_at())),
+    }
+}
+
+/// Selects open items for `edit` and `modify`: a single index or uuid, or the ones matching
+/// list terms.
+fn select_open_items(
+    items: &[TodoItem],
+    terms: &[String],
+    grammar: &NameGrammar,
+) -> Result<Vec<usize>, String> {
+    if let [id] = terms
+        && parse_filter(id).is_none()
+        && let Ok(index) = find_open_item(items, id)
+    {
+        return Ok(vec![index]);
+    }
+    let query = ListQuery::parse(terms, grammar)?;
+    let now = Local::now().naive_local();
+    let open = todo_deps::open_uuids(items);
+    Ok((0..items.len())
+        .filter(|&i| !items[i].done && query.matches(&items[i], now, &open))
+        .collect())
+}
+
+/// Asks a yes/no question on the terminal; anything but `y` or `yes` is a no.
+fn confirm(question: &str) -> bool {
+    use std::io::Write;
+    print!("{} [y/N] ", question);
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).is_ok()
+        && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// An item as shown by `list --raw`: its todo.txt line, with `due:` and `t:` dates in
+/// `date_format` when one is configured.
+fn display_item(item: &TodoItem, date_format: Option<&str>) -> String {
+    let line = item.to_string();
+    let Some(date_format) = date_format else {
+        return line;
+    };
+    line.split(' ')
+        .map(|word| {
+            for tag in ["due:", "t:"] {
+                if let Some(value) = word.strip_prefix(tag)
+                    && let Ok((date, time)) = parse_date_time(value)
+                {
+                    let time = time.map(|t| t.format("T%H:%M").to_string());
+                    return format!(
+                        "{}{}{}",
+                        tag,
+                        date.format(date_format),
+                        time.unwrap_or_default()
+                    );
+                }
+            }
+            word.to_string()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn parse_filter(s: &str) -> Option<Filter> {
     match s {
         "today" => Some(Filter::Today),
@@ -62,51 +569,479 @@ fn parse_filter(s: &str) -> Option<Filter> {
     }
 }
 
+/// Whether an item's due date passes a date filter. `overdue` goes by the time of day too,
+/// so an item due at 14:00 is overdue from then on.
+fn filter_matches(filter: &Filter, item: &TodoItem, now: NaiveDateTime) -> bool {
+    match filter {
+        Filter::Overdue => item.due_at().is_some_and(|due| due < now),
+        _ => date_range(filter, now.date())
+            .is_none_or(|(start, end)| item.due.is_some_and(|d| d >= start && d <= end)),
+    }
+}
+
+fn date_range(filter: &Filter, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    match filter {
+        Filter::Today => Some((today, today)),
+        Filter::Week => Some((today, today + Duration::days(6))),
+        Filter::All => None,
+        Filter::Overdue => Some((NaiveDate::MIN, today - Duration::days(1))),
+        Filter::Future => Some((today + Duration::days(1), NaiveDate::MAX)),
+    }
+}
+
+/// Resolves an item identifier -- a 1-based index into the open items, or a uuid of an open
+/// item -- to its index in the library.
+fn find_open_item(items: &[TodoItem], id: &str) -> Result<usize, String> {
+    if let Ok(index) = id.parse::<usize>() {
+        let uncompleted: Vec<usize> = (0..items.len()).filter(|&i| !items[i].done).collect();
+        if index > 0 && index <= uncompleted.len() {
+            Ok(uncompleted[index - 1])
+        } else {
+            Err(format!("Invalid absolute index: {}", index))
+        }
+    } else if let Ok(uuid) = id.parse::<uuid::Uuid>() {
+        items
+            .iter()
+            .position(|item| item.uuid == Some(uuid) && !item.done)
+            .ok_or_else(|| format!("UUID {} not found or already completed", uuid))
+    } else {
+        Err(format!("Invalid arg: {}", id))
+    }
+}
+
+/// The done.txt that belongs with `file_name`: the one configured for its list, then the
+/// config file's `done_file_name`, otherwise `done.txt` in the same directory if there is one.
+fn default_done_file(config: &CliConfig, list: Option<&str>, file_name: &str) -> Option<String> {
+    if let Some(list) = list.and_then(|name| config.list(name)) {
+        return list.done_file_name.clone();
+    }
+    if config.done_file_name.is_some() {
+        return config.done_file_name.clone();
+    }
+    let path = std::path::Path::new(file_name).with_file_name("done.txt");
+    (path.exists() && path != std::path::Path::new(file_name))
+        .then(|| path.to_string_lossy().to_string())
+}
+
+/// The projects file that belongs with `file_name`: `projects_file_name` from the config
+/// file, otherwise `projects.toml` in the same directory.
+fn default_projects_file(config: &CliConfig, file_name: &str) -> String {
+    config.projects_file_name.clone().unwrap_or_else(|| {
+        std::path::Path::new(file_name)
+            .with_file_name("projects.toml")
+            .to_string_lossy()
+            .to_string()
+    })
+}
+
+/// Prints the `projects` or `contexts` summary over the todo and done files.
+fn list_tags(
+    lib: &mut TodoLibrary,
+    kind: Grouping,
+    args: TagListArgs,
+    config: &CliConfig,
+    list: Option<&str>,
+) {
+    let done = args
+        .done
+        .or_else(|| default_done_file(config, list, &lib.file_name));
+    if args.lint {
+        lint_names(lib, kind, done);
+        return;
+    }
+    let items = load_with_done(lib, done);
+    let mut metadata = TagMetadataFile::new(default_projects_file(config, &lib.file_name));
+    if let Err(e) = metadata.load() {
+        eprintln!("Error loading file '{}': {}", metadata.file_name, e);
+        std::process::exit(1);
+    }
+
+    let (sigil, heading, mut summaries) = match kind {
+        Grouping::Project => ('+', "Project", project_summaries(&items, &metadata)),
+        Grouping::Context => ('@', "Context", context_summaries(&items, &metadata)),
+    };
+    if !args.all {
+        summaries.retain(|s| !s.archived);
+    }
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&summaries).expect("summaries serialize to JSON")
+        );
+        return;
+    }
+    if summaries.is_empty() {
+        println!("No {}s in use.", heading.to_lowercase());
+        return;
+    }
+
+    let labels: Vec<String> = summaries
+        .iter()
+        .map(|s| format!("{}{}{}", "  ".repeat(s.depth), sigil, s.name))
+        .collect();
+    let width = labels
+        .iter()
+        .map(|l| l.len())
+        .max()
+        .unwrap_or(0)
+        .max(heading.len());
+    let date = |d: Option<NaiveDate>| d.map_or("-".to_string(), |d| d.to_string());
+    println!(
+        "{:<width$} {:>5} {:>5}  {:<10}  {:<13}",
+        heading, "Open", "Done", "Next due", "Last activity"
+    );
+    for (label, summary) in labels.iter().zip(&summaries) {
+        let mut line = format!(
+            "{:<width$} {:>5} {:>5}  {:<10}  {:<13}",
+            label,
+            summary.open,
+            summary.done,
+            date(summary.next_due),
+            date(summary.last_activity)
+        );
+        if let Some(description) = &summary.description {
+            line.push_str(&format!("  {}", description));
+        }
+        if summary.archived {
+            line.push_str("  (archived)");
+        }
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Prints the suspicious project or context words in the todo and done files, like `+proj,`
+/// or `@Home` next to `@home`.
+fn lint_names(lib: &TodoLibrary, kind: Grouping, done_file: Option<String>) {
+    let (sigil, plural) = match kind {
+        Grouping::Project => ("+", "project"),
+        Grouping::Context => ("@", "context"),
+    };
+    let mut found = false;
+    for file in std::iter::once(lib.file_name.clone()).chain(done_file) {
+        let content = std::fs::read_to_string(&file).unwrap_or_else(|e| {
+            eprintln!("Error reading file '{}': {}", file, e);
+            std::process::exit(1);
+        });
+        for lint in lib.grammar.lint(&content) {
+            if lint.word.starts_with(sigil) {
+                println!("{}: {}", file, lint);
+                found = true;
+            }
+        }
+    }
+    if !found {
+        println!("No suspicious {} names.", plural);
+    }
+}
+
+/// Prints the problems `todo_lint` finds in the todo and done files, after repairing the
+/// fixable ones with `fix`, and exits with status 1 if any are left.
+fn doctor(lib: &TodoLibrary, done_file: Option<String>, known_tags: &[String], fix: bool) {
+    let files: Vec<String> = std::iter::once(lib.file_name.clone())
+        .chain(done_file)
+        .collect();
+    let contents: Vec<String> = files
+        .iter()
+        .map(|file| {
+            std::fs::read_to_string(file).unwrap_or_else(|e| {
+                eprintln!("Error reading file '{}': {}", file, e);
+                std::process::exit(1);
+            })
+        })
+        .collect();
+    let uuids: Vec<HashSet<Uuid>> = contents
+        .iter()
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| TodoItem::parse_with(line, &lib.grammar).ok()?.uuid)
+                .collect()
+        })
+        .collect();
+
+    let (mut problems, mut fixable) = (0, 0);
+    for (index, (file, content)) in files.iter().zip(&contents).enumerate() {
+        let options = LintOptions {
+            grammar: lib.grammar.clone(),
+            // A subtask's parent may have been archived to the done file, or the other way.
+            other_uuids: uuids
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != index)
+                .flat_map(|(_, uuids)| uuids.iter().copied())
+                .collect(),
+            extra_tags: known_tags.to_vec(),
+            ..LintOptions::new(Local::now().date_naive())
+        };
+        let mut content = content.clone();
+        if fix {
+            let (fixed, count) = todo_lint::fix(&content, &options);
+            if count > 0 {
+                if let Err(e) = std::fs::write(file, &fixed) {
+                    eprintln!("Error saving file '{}': {}", file, e);
+                    std::process::exit(1);
+                }
+                println!("Fixed {} problem(s) in '{}'", count, file);
+            }
+            content = fixed;
+        }
+        for issue in todo_lint::lint(&content, &options) {
+            println!("{}: {}", file, issue);
+            problems += 1;
+            fixable += usize::from(issue.is_fixable());
+        }
+    }
+    if problems == 0 {
+        println!("No problems found.");
+        return;
+    }
+    if fixable > 0 {
+        println!("{} problem(s), {} fixable with --fix", problems, fixable);
+    } else {
+        println!("{} problem(s)", problems);
+    }
+    std::process::exit(1);
+}
+
+/// Runs a `project` or `context` action on `lib`, and on `done_file` when the action asks
+/// for it, then saves what changed.
+fn rename_tags(
+    lib: &mut TodoLibrary,
+    kind: Grouping,
+    action: TagAction,
+    done_file: Option<String>,
+) {
+    let sigil = match kind {
+        Grouping::Project => '+',
+        Grouping::Context => '@',
+    };
+    let (from, to, done) = match action {
+        TagAction::Rename { from, to, done } => (vec![from], to, done),
+        TagAction::Merge { from, into, done } => (from, into, done),
+    };
+    let from: Vec<&str> = from
+        .iter()
+        .map(|name| name.trim_start_matches(sigil))
+        .collect();
+    let to = to.trim_start_matches(sigil);
+
+    let mut done_lib = None;
+    if done {
+        let Some(done_file) = done_file else {
+            eprintln!("No done file found; set done_file_name in the config file");
+            std::process::exit(1);
+        };
+        let mut library = TodoLibrary::new(done_file);
+        library.grammar = lib.grammar.clone();
+        done_lib = Some(library);
+    }
+    let mut libraries: Vec<&mut TodoLibrary> =
+        std::iter::once(lib).chain(done_lib.as_mut()).collect();
+
+    // Both files are loaded and renamed before either is saved, so a done file that can't
+    // be read or renamed leaves the todo file as it was.
+    let mut changes = vec![];
+    for library in libraries.iter_mut() {
+        if let Err(e) = library.load() {
+            eprintln!("Error loading file '{}': {}", library.file_name, e);
+            std::process::exit(1);
+        }
+        let changed = match kind {
+            Grouping::Project => library.merge_projects(&from, to),
+            Grouping::Context => library.merge_contexts(&from, to),
+        }
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+        changes.push(changed);
+    }
+    for (library, changed) in libraries.iter().zip(changes) {
+        if changed > 0
+            && let Err(e) = library.save()
+        {
+            eprintln!("Error saving file: {}", e);
+            std::process::exit(1);
+        }
+        println!("Changed {} item(s) in '{}'", changed, library.file_name);
+    }
+}
+
+/// Loads `lib` and, if given, a done file, and returns the items of both.
+fn load_with_done(lib: &mut TodoLibrary, done_file: Option<String>) -> Vec<TodoItem> {
+    if let Err(e) = lib.load() {
+        eprintln!("Error loading file '{}': {}", lib.file_name, e);
+        std::process::exit(1);
+    }
+    let mut items = lib.list_items().to_vec();
+    if let Some(done_file) = done_file {
+        let mut done_lib = TodoLibrary::new(done_file.clone());
+        done_lib.grammar = lib.grammar.clone();
+        if let Err(e) = done_lib.load() {
+            eprintln!("Error loading file '{}': {}", done_file, e);
+            std::process::exit(1);
+        }
+        items.extend_from_slice(done_lib.list_items());
+    }
+    items
+}
+
+/// Reads an input file, where `-` means standard input.
+fn read_input(file: &str) -> String {
+    let result = if file == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(file)
+    };
+    result.unwrap_or_else(|e| {
+        eprintln!("Error reading file '{}': {}", file, e);
+        std::process::exit(1);
+    })
+}
+
+/// Writes exported content to `output`, or to standard output when there is none.
+fn write_output(output: Option<&str>, content: &str) {
+    match output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, content) {
+                eprintln!("Error writing file '{}': {}", path, e);
+                std::process::exit(1);
+            }
+        }
+        None => print!("{}", content),
+    }
+}
+
 fn main() {
-    let cli = Cli::parse();
-    let file_name = cli
-        .file
-        .unwrap_or_else(|| std::env::var("TODOTXT").unwrap_or_else(|_| "todo.txt".to_string()));
+    CompleteEnv::with_factory(Cli::command)
+        .var(completions::COMPLETE_VAR)
+        .complete();
+
+    let config = CliConfig::load().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let builtins: Vec<String> = Cli::command()
+        .get_subcommands()
+        .flat_map(|c| {
+            std::iter::once(c.get_name().to_string()).chain(c.get_all_aliases().map(str::to_string))
+        })
+        .chain(["help".to_string()])
+        .collect();
+    let builtins: Vec<&str> = builtins.iter().map(String::as_str).collect();
+    let cli = Cli::parse_from(config.expand_alias(std::env::args().collect(), &builtins));
+    let file_name = if let Some(ref name) = cli.list {
+        match config.list(name) {
+            Some(list) => list.file_name.clone(),
+            None => {
+                eprintln!("Unknown list '{}'", name);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        cli.file
+            .or_else(|| std::env::var("TODOTXT").ok())
+            .or_else(|| config.file_name.clone())
+            .unwrap_or_else(|| "todo.txt".to_string())
+    };
 
     let mut lib = TodoLibrary::new(file_name.clone());
+    lib.grammar = config.names.clone();
 
     match cli.command {
-        Commands::List { completed, filter } => {
+        Commands::List {
+            completed,
+            terms,
+            sort,
+            group,
+            raw,
+            color,
+            all_lists,
+        } => {
+            let now = Local::now().naive_local();
+            let terms = if terms.is_empty() {
+                let default = config.default_filter.as_deref().unwrap_or_default();
+                default.split_whitespace().map(str::to_string).collect()
+            } else {
+                terms
+            };
+            let query = ListQuery::parse(&terms, &lib.grammar).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            });
+            let matches = |item: &TodoItem, open: &HashSet<Uuid>| {
+                item.done == completed && query.matches(item, now, open)
+            };
+            let order = sort.or(config.sort).unwrap_or_default();
+            let date_format = config.date_format.as_deref();
+            let theme = config.theme.unwrap_or_default();
+            let renderer =
+                Renderer::new(color.enabled(theme), theme, now, date_format, &config.names);
+            let print_rows = |mut rows: Vec<Row>| {
+                sort_rows(&mut rows, order);
+                if raw {
+                    for (i, row) in rows.iter().enumerate() {
+                        println!("{}. {}", i + 1, display_item(row.item, date_format));
+                    }
+                } else {
+                    print!("{}", renderer.render(&rows, group));
+                }
+            };
+
+            if all_lists {
+                let mut workspace = config.workspace();
+                if let Err(e) = workspace.load() {
+                    eprintln!("Error loading lists: {}", e);
+                    std::process::exit(1);
+                }
+                // Items may wait for items in other lists.
+                let all_items: Vec<TodoItem> = workspace
+                    .list_names()
+                    .iter()
+                    .flat_map(|name| workspace.list(name).unwrap().library.items.clone())
+                    .collect();
+                let open = todo_deps::open_uuids(&all_items);
+                let found = workspace.query(|item| matches(item, &open));
+                for list in workspace.list_names() {
+                    println!("Items in '{}':", list);
+                    let ids = render::open_ids(&workspace.list(list).unwrap().library.items);
+                    print_rows(
+                        found
+                            .iter()
+                            .filter(|e| e.list == list)
+                            .map(|e| Row {
+                                id: ids[e.index],
+                                item: e.item,
+                            })
+                            .collect(),
+                    );
+                }
+                if found.is_empty() {
+                    println!("No items found.");
+                }
+                return;
+            }
+
             if let Err(e) = lib.load() {
                 eprintln!("Error loading file '{}': {}", file_name, e);
                 std::process::exit(1);
             }
 
-            let today = Local::now().date_naive();
-            let date_filter = match filter {
-                Some(Filter::Today) => Some((today, today)),
-                Some(Filter::Week) => Some((today, today + Duration::days(6))),
-                Some(Filter::All) => None,
-                Some(Filter::Overdue) => Some((chrono::NaiveDate::MIN, today - Duration::days(1))),
-                Some(Filter::Future) => Some((today + Duration::days(1), chrono::NaiveDate::MAX)),
-                None => None,
-            };
-
-            let filtered_items: Vec<_> = lib
+            let ids = render::open_ids(lib.list_items());
+            let open = todo_deps::open_uuids(lib.list_items());
+            let rows: Vec<Row> = lib
                 .list_items()
                 .iter()
-                .filter(|item| {
-                    item.done == completed
-                        && match date_filter {
-                            None => true,
-                            Some((start, end)) => {
-                                item.due.map_or(false, |d| d >= start && d <= end)
-                            }
-                        }
-                })
+                .zip(ids)
+                .filter(|(item, _)| matches(item, &open))
+                .map(|(item, id)| Row { id, item })
                 .collect();
+            let empty = rows.is_empty();
 
             println!("Items in '{}':", file_name);
-            for (i, item) in filtered_items.iter().enumerate() {
-                println!("{}. {}", i + 1, item);
-            }
+            print_rows(rows);
 
-            if filtered_items.is_empty() {
+            if empty {
                 println!("No items found.");
             }
         }
@@ -130,29 +1065,16 @@ fn main() {
                 std::process::exit(1);
             }
 
-            let today = Local::now().date_naive();
+            let now = Local::now().naive_local();
             let items = lib.list_items();
             let mut indices_to_complete = Vec::new();
 
             let arg1_ref = &arg1;
             if let Some(filter_name) = parse_filter(arg1_ref) {
-                let date_filter = match filter_name {
-                    Filter::Today => Some((today, today)),
-                    Filter::Week => Some((today, today + Duration::days(6))),
-                    Filter::All => None,
-                    Filter::Overdue => Some((chrono::NaiveDate::MIN, today - Duration::days(1))),
-                    Filter::Future => Some((today + Duration::days(1), chrono::NaiveDate::MAX)),
-                };
-
                 let filtered_indices: Vec<usize> = items
                     .iter()
                     .enumerate()
-                    .filter(|(_, item)| {
-                        !item.done
-                            && date_filter.map_or(true, |(start, end)| {
-                                item.due.map_or(false, |d| d >= start && d <= end)
-                            })
-                    })
+                    .filter(|(_, item)| !item.done && filter_matches(&filter_name, item, now))
                     .map(|(i, _)| i)
                     .collect();
 
@@ -187,37 +1109,20 @@ fn main() {
                 }
             } else {
                 // arg1 is identifier
-                if let Ok(index) = arg1_ref.parse::<usize>() {
-                    let uncompleted: Vec<usize> =
-                        (0..items.len()).filter(|&i| !items[i].done).collect();
-                    if index > 0 && index <= uncompleted.len() {
-                        indices_to_complete.push(uncompleted[index - 1]);
-                    } else {
-                        eprintln!("Invalid absolute index: {}", index);
+                match find_open_item(items, arg1_ref) {
+                    Ok(i) => indices_to_complete.push(i),
+                    Err(e) => {
+                        eprintln!("{}", e);
                         std::process::exit(1);
                     }
-                } else if let Ok(uuid) = arg1_ref.parse::<uuid::Uuid>() {
-                    let mut found = false;
-                    for i in 0..items.len() {
-                        if items[i].uuid == Some(uuid) && !items[i].done {
-                            indices_to_complete.push(i);
-                            found = true;
-                            break;
-                        }
-                    }
-                    if !found {
-                        eprintln!("UUID {} not found or already completed", uuid);
-                        std::process::exit(1);
-                    }
-                } else {
-                    eprintln!("Invalid arg: {}", arg1);
-                    std::process::exit(1);
                 }
             }
 
+            let was_blocked = lib.blocked_items();
             for &i in &indices_to_complete {
                 lib.complete_item(i).unwrap();
             }
+            let still_blocked = lib.blocked_items();
 
             if !indices_to_complete.is_empty() {
                 if let Err(e) = lib.save() {
@@ -229,9 +1134,403 @@ fn main() {
                     indices_to_complete.len(),
                     file_name
                 );
+                for i in was_blocked {
+                    if !still_blocked.contains(&i) && !lib.items[i].done {
+                        println!("Unblocked: {}", lib.items[i].description);
+                    }
+                }
             } else {
                 println!("No items matched the criteria");
             }
         }
+        Commands::Depend { id, on, remove } => {
+            if let Err(e) = lib.load() {
+                eprintln!("Error loading file '{}': {}", file_name, e);
+                std::process::exit(1);
+            }
+            let (index, on) = find_open_item(&lib.items, &id)
+                .and_then(|index| Ok((index, find_open_item(&lib.items, &on)?)))
+                .unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                });
+            let changed = if remove {
+                lib.remove_dependency(index, on)
+            } else {
+                lib.add_dependency(index, on).map(|()| true)
+            }
+            .unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            });
+            if !changed {
+                println!(
+                    "'{}' doesn't wait for '{}'",
+                    lib.items[index].description, lib.items[on].description
+                );
+                return;
+            }
+            if let Err(e) = lib.save() {
+                eprintln!("Error saving file: {}", e);
+                std::process::exit(1);
+            }
+            let verb = if remove {
+                "no longer waits"
+            } else {
+                "now waits"
+            };
+            println!(
+                "'{}' {} for '{}'",
+                lib.items[index].description, verb, lib.items[on].description
+            );
+        }
+        Commands::Project { action } => {
+            let done = default_done_file(&config, cli.list.as_deref(), &file_name);
+            rename_tags(&mut lib, Grouping::Project, action, done);
+        }
+        Commands::Context { action } => {
+            let done = default_done_file(&config, cli.list.as_deref(), &file_name);
+            rename_tags(&mut lib, Grouping::Context, action, done);
+        }
+        Commands::Projects(args) => list_tags(
+            &mut lib,
+            Grouping::Project,
+            args,
+            &config,
+            cli.list.as_deref(),
+        ),
+        Commands::Contexts(args) => list_tags(
+            &mut lib,
+            Grouping::Context,
+            args,
+            &config,
+            cli.list.as_deref(),
+        ),
+        Commands::Doctor { fix, done } => {
+            let done = done.or_else(|| default_done_file(&config, cli.list.as_deref(), &file_name));
+            doctor(&lib, done, &config.known_tags, fix);
+        }
+        Commands::Lists => {
+            if config.lists.is_empty() {
+                println!("No lists configured.");
+            }
+            for list in &config.lists {
+                let marker = if cli.list.as_deref() == Some(list.name.as_str()) {
+                    "*"
+                } else {
+                    " "
+                };
+                match &list.done_file_name {
+                    Some(done) => println!(
+                        "{} {}: {} (done: {})",
+                        marker, list.name, list.file_name, done
+                    ),
+                    None => println!("{} {}: {}", marker, list.name, list.file_name),
+                }
+            }
+        }
+        Commands::Move { id, to } => {
+            let Some(target) = config.list(&to) else {
+                eprintln!("Unknown list '{}'", to);
+                std::process::exit(1);
+            };
+            let canonical = |f: &str| std::fs::canonicalize(f).unwrap_or_else(|_| f.into());
+            if canonical(&target.file_name) == canonical(&file_name) {
+                eprintln!("Source and target are the same list '{}'", to);
+                std::process::exit(1);
+            }
+
+            // The source list is unnamed, so it can't clash with the target's name.
+            let mut workspace = TodoWorkspace::new();
+            workspace
+                .add_list(String::new(), file_name.clone(), None)
+                .and_then(|_| workspace.add_list(to.clone(), target.file_name.clone(), None))
+                .expect("list names are distinct");
+            if let Err(e) = workspace.list_mut("").unwrap().load() {
+                eprintln!("Error loading file '{}': {}", file_name, e);
+                std::process::exit(1);
+            }
+            // Like add, moving into a list whose file doesn't exist yet creates it, but a
+            // file that can't be read must not be overwritten.
+            if let Err(e) = workspace.list_mut(&to).unwrap().load()
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                eprintln!("Error loading file '{}': {}", target.file_name, e);
+                std::process::exit(1);
+            }
+
+            let source = &workspace.list("").unwrap().library.items;
+            let index = find_open_item(source, &id).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            });
+            if let Err(e) = workspace.move_item("", index, &to) {
+                eprintln!("Error moving item: {}", e);
+                std::process::exit(1);
+            }
+            // The target first: if it can't be written, the source still has the item.
+            for name in [to.as_str(), ""] {
+                if let Err(e) = workspace.list(name).unwrap().save() {
+                    eprintln!("Error saving file: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            println!("Moved item from '{}' to '{}'", file_name, to);
+        }
+        Commands::Export { format } => {
+            if let Err(e) = lib.load() {
+                eprintln!("Error loading file '{}': {}", file_name, e);
+                std::process::exit(1);
+            }
+
+            match format {
+                ExportFormat::Ics {
+                    output,
+                    events,
+                    completed,
+                } => {
+                    let options = IcalExportOptions {
+                        component: if events {
+                            IcalComponent::Event
+                        } else {
+                            IcalComponent::Todo
+                        },
+                        include_done: completed,
+                    };
+                    let ics = todotxt::todo_ical::export_ical(lib.list_items(), &options);
+                    write_output(output.as_deref(), &ics);
+                }
+                ExportFormat::Taskwarrior { output } => {
+                    let json = todotxt::todo_taskwarrior::to_taskwarrior_json(lib.list_items());
+                    write_output(output.as_deref(), &(json + "\n"));
+                }
+                ExportFormat::Md(args) => {
+                    let (output, options) = args.options(ReportFormat::Markdown, &lib.grammar);
+                    let report = todotxt::todo_report::render_report(lib.list_items(), &options);
+                    write_output(output.as_deref(), &report);
+                }
+                ExportFormat::Org(args) => {
+                    let (output, options) = args.options(ReportFormat::Org, &lib.grammar);
+                    let report = todotxt::todo_report::render_report(lib.list_items(), &options);
+                    write_output(output.as_deref(), &report);
+                }
+            }
+        }
+        Commands::Import { format } => {
+            // As with add, importing into a file that doesn't exist yet creates it, but a file
+            // that can't be read must not be overwritten.
+            if let Err(e) = lib.load()
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                eprintln!("Error loading file '{}': {}", file_name, e);
+                std::process::exit(1);
+            }
+
+            let summary = match format {
+                ImportFormat::Ics { file } => {
+                    lib.import_ical(&read_input(&file)).unwrap_or_else(|e| {
+                        eprintln!("Error parsing '{}': {}", file, e);
+                        std::process::exit(1);
+                    })
+                }
+                ImportFormat::Taskwarrior { file } => lib
+                    .import_taskwarrior(&read_input(&file))
+                    .unwrap_or_else(|e| {
+                        eprintln!("Error parsing '{}': {}", file, e);
+                        std::process::exit(1);
+                    }),
+            };
+
+            if summary.imported > 0
+                && let Err(e) = lib.save()
+            {
+                eprintln!("Error saving file: {}", e);
+                std::process::exit(1);
+            }
+            for line in &summary.rejected {
+                eprintln!("Not imported, it would not load back: {}", line);
+            }
+            println!(
+                "Imported {} item(s) into '{}', skipped {} already present",
+                summary.imported, file_name, summary.skipped
+            );
+        }
+        Commands::Timer { action } => {
+            let log_file =
+                timer::default_log_file(config.time_log_file_name.as_deref(), &file_name);
+            let done = default_done_file(&config, cli.list.as_deref(), &file_name);
+            if let Err(e) = timer::run(action, &mut lib, log_file, done) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Serve { port, token } => serve::serve(&file_name, &lib.grammar, port, token),
+        Commands::Report {
+            json,
+            weekly,
+            periods,
+            done,
+        } => {
+            let done = done.or_else(|| default_done_file(&config, cli.list.as_deref(), &file_name));
+            let items = load_with_done(&mut lib, done);
+
+            let options = StatsOptions {
+                period: if weekly {
+                    StatsPeriod::Week
+                } else {
+                    StatsPeriod::Day
+                },
+                periods,
+                ..StatsOptions::new(Local::now().naive_local())
+            };
+            let stats = todotxt::todo_stats::compute_stats(&items, &options);
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&stats).expect("stats serialize to JSON")
+                );
+            } else {
+                print!("{}", stats);
+            }
+        }
+        Commands::Chart { kind } => {
+            let (burndown, args) = match kind {
+                ChartKind::Burndown(args) => (true, args),
+                ChartKind::Flow(args) => (false, args),
+            };
+            let done = args
+                .done
+                .or_else(|| default_done_file(&config, cli.list.as_deref(), &file_name));
+            let mut items = load_with_done(&mut lib, done);
+            let project = args.project.map(|p| p.trim_start_matches('+').to_string());
+            if let Some(ref project) = project {
+                items.retain(|item| {
+                    item.projects
+                        .iter()
+                        .any(|p| lib.grammar.is_within(&p.name, project))
+                });
+            }
+
+            let today = Local::now().date_naive();
+            let from = today - Duration::days(args.days.saturating_sub(1) as i64);
+            let points = todotxt::todo_stats::cumulative_flow(&items, from, today);
+            let title = match (&project, burndown) {
+                (Some(project), true) => format!("Burndown of +{}", project),
+                (None, true) => "Burndown".to_string(),
+                (Some(project), false) => format!("Cumulative flow of +{}", project),
+                (None, false) => "Cumulative flow".to_string(),
+            };
+            match args.svg {
+                Some(path) => {
+                    let svg = if burndown {
+                        chart::burndown_svg(&title, &points)
+                    } else {
+                        chart::flow_svg(&title, &points)
+                    };
+                    write_output(Some(&path), &svg);
+                }
+                None if burndown => print!("{}", chart::render_burndown(&title, &points)),
+                None => print!("{}", chart::render_flow(&title, &points)),
+            }
+        }
+        Commands::Completions { shell } => {
+            if let Err(e) = completions::print_registration(shell) {
+                eprintln!("Error writing completion script: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Edit { terms } => {
+            if let Err(e) = lib.load() {
+                eprintln!("Error loading file '{}': {}", file_name, e);
+                std::process::exit(1);
+            }
+            let selected = select_open_items(lib.list_items(), &terms, &lib.grammar)
+                .unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                });
+            if selected.is_empty() {
+                println!("No items matched the criteria");
+                return;
+            }
+
+            let summary = edit::edit_in_editor(&mut lib, &selected).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            });
+            if summary.is_empty() {
+                println!("No changes");
+                return;
+            }
+            if let Err(e) = lib.save() {
+                eprintln!("Error saving file: {}", e);
+                std::process::exit(1);
+            }
+            println!(
+                "Updated {}, deleted {}, added {} item(s) in '{}'",
+                summary.updated, summary.deleted, summary.added, file_name
+            );
+        }
+        Commands::Modify {
+            terms,
+            changes,
+            dry_run,
+            yes,
+        } => {
+            if let Err(e) = lib.load() {
+                eprintln!("Error loading file '{}': {}", file_name, e);
+                std::process::exit(1);
+            }
+            let changes = modify::Changes::new(changes, &lib.grammar).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            });
+            let selected = select_open_items(lib.list_items(), &terms, &lib.grammar)
+                .unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                });
+
+            let modified: Vec<(usize, TodoItem)> = selected
+                .iter()
+                .map(|&i| changes.apply(&lib.items[i]).map(|item| (i, item)))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_or_else(|e| {
+                    eprintln!("{}; nothing changed", e);
+                    std::process::exit(1);
+                })
+                .into_iter()
+                .filter(|(i, item)| *item != lib.items[*i])
+                .collect();
+            if modified.is_empty() {
+                println!("No items would change");
+                return;
+            }
+            for (i, item) in &modified {
+                println!("-{}\n+{}", lib.items[*i], item);
+            }
+            if dry_run {
+                println!("{} item(s) would be changed", modified.len());
+                return;
+            }
+            let confirm_above = config.confirm_above.unwrap_or(10);
+            if !yes
+                && modified.len() > confirm_above
+                && !confirm(&format!("Modify {} items?", modified.len()))
+            {
+                println!("Nothing changed");
+                return;
+            }
+
+            let count = modified.len();
+            for (i, item) in modified {
+                lib.items[i] = item;
+            }
+            if let Err(e) = lib.save() {
+                eprintln!("Error saving file: {}", e);
+                std::process::exit(1);
+            }
+            println!("Modified {} item(s) in '{}'", count, file_name);
+        }
     }
 }
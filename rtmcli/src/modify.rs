@@ -0,0 +1,354 @@
+//! `rtmcli modify`: the same changes applied to every matching item.
+
+use chrono::{Days, Months, NaiveDate};
+use clap::Args;
+use todotxt::todo_names::NameGrammar;
+use todotxt::todo_recurrence::TodoRecurrence;
+use todotxt::{TodoContext, TodoItem, TodoPriority, TodoProject};
+
+/// The changes to make, as given on the command line.
+#[derive(Args, Debug, Default)]
+pub struct ChangeArgs {
+    /// Add a +project or @context (repeatable)
+    #[arg(short, long, value_name = "TAG")]
+    add: Vec<String>,
+
+    /// Remove a +project or @context (repeatable)
+    #[arg(short, long, value_name = "TAG")]
+    remove: Vec<String>,
+
+    /// Set the priority (A-Z), or `none` to clear it
+    #[arg(short, long)]
+    priority: Option<String>,
+
+    /// Set the due date (YYYY-MM-DD), shift it (+3d, -1w, +1m, +1y), or `none` to clear it
+    #[arg(long, allow_hyphen_values = true)]
+    due: Option<String>,
+
+    /// Set, shift or clear the threshold date, like --due
+    #[arg(short = 't', long, allow_hyphen_values = true)]
+    threshold: Option<String>,
+
+    /// Set the recurrence (e.g. 1w, +1m), or `none` to clear it
+    #[arg(long)]
+    rec: Option<String>,
+}
+
+/// A change to a date field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DateChange {
+    Set(NaiveDate),
+    Clear,
+    /// Shift by days, or by months when the unit is `m` or `y`. Items without the date are
+    /// left alone.
+    ShiftDays(i64),
+    ShiftMonths(i32),
+}
+
+/// Validated changes, ready to apply to any number of items.
+#[derive(Debug, Default)]
+pub struct Changes {
+    add_projects: Vec<TodoProject>,
+    add_contexts: Vec<TodoContext>,
+    remove_projects: Vec<TodoProject>,
+    remove_contexts: Vec<TodoContext>,
+    priority: Option<TodoPriority>,
+    due: Option<DateChange>,
+    threshold: Option<DateChange>,
+    recurrence: Option<Option<TodoRecurrence>>,
+    /// How the tags typed in descriptions are read, to find those to remove.
+    grammar: NameGrammar,
+}
+
+impl Changes {
+    /// Validates `args`, reading tags with the names `grammar` allows.
+    pub fn new(args: ChangeArgs, grammar: &NameGrammar) -> Result<Self, String> {
+        let mut changes = Changes {
+            grammar: grammar.clone(),
+            ..Default::default()
+        };
+        let project =
+            |tag: &str| TodoProject::parse_with(tag, grammar).map_err(|e| invalid_tag(tag, e));
+        let context =
+            |tag: &str| TodoContext::parse_with(tag, grammar).map_err(|e| invalid_tag(tag, e));
+        for tag in &args.add {
+            match tag.chars().next() {
+                Some('+') => changes.add_projects.push(project(tag)?),
+                Some('@') => changes.add_contexts.push(context(tag)?),
+                _ => return Err(format!("Expected +project or @context, got '{}'", tag)),
+            }
+        }
+        for tag in &args.remove {
+            match tag.chars().next() {
+                Some('+') => changes.remove_projects.push(project(tag)?),
+                Some('@') => changes.remove_contexts.push(context(tag)?),
+                _ => return Err(format!("Expected +project or @context, got '{}'", tag)),
+            }
+        }
+        changes.priority = match args.priority.as_deref() {
+            None => None,
+            Some("none") => Some(TodoPriority { priority: None }),
+            Some(p) => Some(
+                TodoPriority::from_tag_value(&p.to_uppercase())
+                    .map_err(|_| format!("Invalid priority '{}'", p))?,
+            ),
+        };
+        changes.due = args.due.as_deref().map(parse_date_change).transpose()?;
+        changes.threshold = args
+            .threshold
+            .as_deref()
+            .map(parse_date_change)
+            .transpose()?;
+        changes.recurrence = match args.rec.as_deref() {
+            None => None,
+            Some("none") => Some(None),
+            Some(r) => Some(Some(
+                r.parse()
+                    .map_err(|e| format!("Invalid recurrence '{}': {}", r, e))?,
+            )),
+        };
+        if changes.is_empty() {
+            return Err("No changes given".to_string());
+        }
+        Ok(changes)
+    }
+}
+
+fn invalid_tag(tag: &str, e: impl std::fmt::Display) -> String {
+    format!("Invalid tag '{}': {}", tag, e)
+}
+
+fn parse_date_change(s: &str) -> Result<DateChange, String> {
+    if s == "none" {
+        return Ok(DateChange::Clear);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(DateChange::Set(date));
+    }
+    let invalid = || format!("Invalid date or offset '{}'", s);
+    let (sign, rest) = match s.as_bytes().first() {
+        Some(b'-') => (-1, &s[1..]),
+        Some(b'+') => (1, &s[1..]),
+        _ => (1, s),
+    };
+    let unit = rest.chars().last().ok_or_else(invalid)?;
+    let count: i64 = rest[..rest.len() - unit.len_utf8()]
+        .parse()
+        .map_err(|_| invalid())?;
+    let out_of_range = || format!("Offset '{}' is out of range", s);
+    let scaled = |factor: i64| count.checked_mul(sign * factor).ok_or_else(out_of_range);
+    let months = |factor| i32::try_from(scaled(factor)?).map_err(|_| out_of_range());
+    match unit {
+        'd' => Ok(DateChange::ShiftDays(scaled(1)?)),
+        'w' => Ok(DateChange::ShiftDays(scaled(7)?)),
+        'm' => Ok(DateChange::ShiftMonths(months(1)?)),
+        'y' => Ok(DateChange::ShiftMonths(months(12)?)),
+        _ => Err(invalid()),
+    }
+}
+
+/// The date after `change`, or an error if a shift would leave the range of dates.
+fn apply_date(
+    date: Option<NaiveDate>,
+    change: Option<DateChange>,
+) -> Result<Option<NaiveDate>, String> {
+    let shift = |f: &dyn Fn(NaiveDate) -> Option<NaiveDate>| {
+        date.map(|d| f(d).ok_or_else(|| format!("Shifting {} goes out of range", d)))
+            .transpose()
+    };
+    match change {
+        None => Ok(date),
+        Some(DateChange::Set(d)) => Ok(Some(d)),
+        Some(DateChange::Clear) => Ok(None),
+        Some(DateChange::ShiftDays(days)) => shift(&|d| {
+            let delta = Days::new(days.unsigned_abs());
+            if days >= 0 {
+                d.checked_add_days(delta)
+            } else {
+                d.checked_sub_days(delta)
+            }
+        }),
+        Some(DateChange::ShiftMonths(months)) => shift(&|d| {
+            let delta = Months::new(months.unsigned_abs());
+            if months >= 0 {
+                d.checked_add_months(delta)
+            } else {
+                d.checked_sub_months(delta)
+            }
+        }),
+    }
+}
+
+impl Changes {
+    fn is_empty(&self) -> bool {
+        self.add_projects.is_empty()
+            && self.add_contexts.is_empty()
+            && self.remove_projects.is_empty()
+            && self.remove_contexts.is_empty()
+            && self.priority.is_none()
+            && self.due.is_none()
+            && self.threshold.is_none()
+            && self.recurrence.is_none()
+    }
+
+    /// The item with the changes made. Removals happen before additions, and a tag the
+    /// item already has isn't added twice. Fails if a date would be shifted out of range.
+    pub fn apply(&self, item: &TodoItem) -> Result<TodoItem, String> {
+        let mut item = item.clone();
+        for tag in self.remove_projects.iter().map(|p| p.to_string()) {
+            item.retag_with(&tag, None, &self.grammar);
+        }
+        for tag in self.remove_contexts.iter().map(|c| c.to_string()) {
+            item.retag_with(&tag, None, &self.grammar);
+        }
+        item.projects.retain(|p| !self.remove_projects.contains(p));
+        item.contexts.retain(|c| !self.remove_contexts.contains(c));
+        for project in &self.add_projects {
+            if !item.projects.contains(project) {
+                item.projects.push(project.clone());
+            }
+        }
+        for context in &self.add_contexts {
+            if !item.contexts.contains(context) {
+                item.contexts.push(context.clone());
+            }
+        }
+        if let Some(ref priority) = self.priority {
+            item.priority = priority.clone();
+        }
+        item.due = apply_date(item.due, self.due)?;
+        item.threshold = apply_date(item.threshold, self.threshold)?;
+        // Shifted dates keep their time of day; cleared ones lose it.
+        item.due_time = item.due.and(item.due_time);
+        item.threshold_time = item.threshold.and(item.threshold_time);
+        if let Some(ref recurrence) = self.recurrence {
+            item.recurrence = recurrence.clone();
+        }
+        Ok(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(args: ChangeArgs) -> Changes {
+        Changes::new(args, &NameGrammar::default()).unwrap()
+    }
+
+    #[test]
+    fn test_tags_and_priority() {
+        let item: TodoItem = "(B) Write report +work @office +draft".parse().unwrap();
+        let changes = changes(ChangeArgs {
+            add: vec!["+urgent".to_string(), "+work".to_string()],
+            remove: vec!["@office".to_string(), "+draft".to_string()],
+            priority: Some("a".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            changes.apply(&item).unwrap().to_string(),
+            "(A) Write report +work +urgent"
+        );
+    }
+
+    #[test]
+    fn test_tags_follow_the_grammar() {
+        let args = || ChangeArgs {
+            add: vec!["+c++".to_string()],
+            ..Default::default()
+        };
+        assert!(
+            Changes::new(args(), &NameGrammar::default())
+                .unwrap_err()
+                .starts_with("Invalid tag '+c++'")
+        );
+        let grammar = NameGrammar {
+            extra_chars: "+".to_string(),
+            ..Default::default()
+        };
+        let changes = Changes::new(args(), &grammar).unwrap();
+        let item: TodoItem = "Learn templates".parse().unwrap();
+        assert_eq!(
+            changes.apply(&item).unwrap().to_string(),
+            "Learn templates +c++"
+        );
+    }
+
+    #[test]
+    fn test_dates_and_recurrence() {
+        let item: TodoItem = "Pay rent due:2026-01-31 t:2026-01-25 rec:1m"
+            .parse()
+            .unwrap();
+        let changes = changes(ChangeArgs {
+            due: Some("+1m".to_string()),
+            threshold: Some("-1w".to_string()),
+            rec: Some("none".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            changes.apply(&item).unwrap().to_string(),
+            "Pay rent due:2026-02-28 t:2026-01-18"
+        );
+
+        // Shifting a date the item doesn't have leaves it without one.
+        let item: TodoItem = "No dates".parse().unwrap();
+        let changes = Changes::new(
+            ChangeArgs {
+                due: Some("3d".to_string()),
+                threshold: Some("2026-10-20".to_string()),
+                ..Default::default()
+            },
+            &NameGrammar::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            changes.apply(&item).unwrap().to_string(),
+            "No dates t:2026-10-20"
+        );
+    }
+
+    #[test]
+    fn test_invalid_changes() {
+        let error = |args: ChangeArgs| Changes::new(args, &NameGrammar::default()).unwrap_err();
+        assert_eq!(error(ChangeArgs::default()), "No changes given");
+        assert!(
+            error(ChangeArgs {
+                add: vec!["work".to_string()],
+                ..Default::default()
+            })
+            .starts_with("Expected +project")
+        );
+        assert!(
+            error(ChangeArgs {
+                due: Some("soon".to_string()),
+                ..Default::default()
+            })
+            .starts_with("Invalid date")
+        );
+        assert!(
+            error(ChangeArgs {
+                priority: Some("AA".to_string()),
+                ..Default::default()
+            })
+            .starts_with("Invalid priority")
+        );
+        assert!(
+            error(ChangeArgs {
+                due: Some("+9999999999m".to_string()),
+                ..Default::default()
+            })
+            .ends_with("out of range")
+        );
+
+        // An offset that parses can still shift a date past the last one there is.
+        let item: TodoItem = "Pay rent due:2026-01-31".parse().unwrap();
+        let changes = changes(ChangeArgs {
+            due: Some("+99999999999d".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            changes.apply(&item).unwrap_err(),
+            "Shifting 2026-01-31 goes out of range"
+        );
+    }
+}
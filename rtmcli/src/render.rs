@@ -0,0 +1,496 @@
+//! Terminal rendering for `rtmcli list`: aligned ids, priority colors, relative dates and
+//! optional grouping by project or context.
+
+use crate::Grouping;
+use chrono::{NaiveDate, NaiveDateTime};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use todotxt::TodoItem;
+use todotxt::todo_item::is_tag_word;
+use todotxt::todo_names::NameGrammar;
+
+/// Dates this many days away or fewer are shown relative to today, e.g. "in 3d".
+const RELATIVE_DAYS: i64 = 6;
+
+/// Due and threshold times less than this many hours away are shown as e.g. "in 3h".
+const RELATIVE_HOURS: i64 = 24;
+
+/// Palette for `list`, from `theme` in the config file.
+#[derive(Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// Bright colors, for dark terminal backgrounds
+    #[default]
+    Dark,
+    /// Darker colors, for light terminal backgrounds
+    Light,
+    /// No colors at all
+    None,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal and `NO_COLOR` isn't set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn enabled(self, theme: Theme) -> bool {
+        theme != Theme::None
+            && match self {
+                ColorChoice::Always => true,
+                ColorChoice::Never => false,
+                ColorChoice::Auto => {
+                    std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                        && std::io::stdout().is_terminal()
+                }
+            }
+    }
+}
+
+/// SGR codes for each part of a rendered line.
+struct Palette {
+    priorities: [&'static str; 4],
+    project: &'static str,
+    context: &'static str,
+    overdue: &'static str,
+    today: &'static str,
+}
+
+const DARK: Palette = Palette {
+    priorities: ["1;91", "93", "92", "96"],
+    project: "94",
+    context: "95",
+    overdue: "91",
+    today: "1;93",
+};
+
+const LIGHT: Palette = Palette {
+    priorities: ["1;31", "33", "32", "36"],
+    project: "34",
+    context: "35",
+    overdue: "31",
+    today: "1;33",
+};
+
+const DIM: &str = "2";
+const BOLD: &str = "1";
+
+/// An item to render and the id shown for it; done items have none.
+pub struct Row<'a> {
+    pub id: Option<usize>,
+    pub item: &'a TodoItem,
+}
+
+/// The ids `list` shows, by position in `items`: the 1-based index among open items, which
+/// is what `complete`, `move` and `edit` take.
+pub fn open_ids(items: &[TodoItem]) -> Vec<Option<usize>> {
+    let mut next = 0;
+    items
+        .iter()
+        .map(|item| {
+            (!item.done).then(|| {
+                next += 1;
+                next
+            })
+        })
+        .collect()
+}
+
+pub struct Renderer<'a> {
+    /// `None` renders plain text.
+    palette: Option<&'static Palette>,
+    now: NaiveDateTime,
+    date_format: &'a str,
+    /// How the tags typed in descriptions are read, to color them in place.
+    grammar: &'a NameGrammar,
+}
+
+/// A piece of a line: its text and the color to show it in.
+type Span = (String, Option<&'static str>);
+
+impl<'a> Renderer<'a> {
+    pub fn new(
+        color: bool,
+        theme: Theme,
+        now: NaiveDateTime,
+        date_format: Option<&'a str>,
+        grammar: &'a NameGrammar,
+    ) -> Self {
+        let palette = match theme {
+            _ if !color => None,
+            Theme::Dark => Some(&DARK),
+            Theme::Light => Some(&LIGHT),
+            Theme::None => None,
+        };
+        Renderer {
+            palette,
+            now,
+            date_format: date_format.unwrap_or("%Y-%m-%d"),
+            grammar,
+        }
+    }
+
+    /// Renders `rows` in the given order, or under one heading per project or context (in
+    /// name order, then items with none). An item in several projects is shown under each.
+    pub fn render(&self, rows: &[Row], group_by: Option<Grouping>) -> String {
+        let Some(grouping) = group_by else {
+            return self.render_rows(rows.iter().collect::<Vec<_>>().as_slice());
+        };
+
+        let mut groups: BTreeMap<String, Vec<&Row>> = BTreeMap::new();
+        let mut ungrouped = Vec::new();
+        for row in rows {
+            let keys: Vec<String> = match grouping {
+                Grouping::Project => row.item.projects.iter().map(|p| p.to_string()).collect(),
+                Grouping::Context => row.item.contexts.iter().map(|c| c.to_string()).collect(),
+            };
+            if keys.is_empty() {
+                ungrouped.push(row);
+            }
+            for key in keys {
+                groups.entry(key).or_default().push(row);
+            }
+        }
+        let other = match grouping {
+            Grouping::Project => "(no project)",
+            Grouping::Context => "(no context)",
+        };
+
+        let mut out = String::new();
+        let sections = groups
+            .iter()
+            .map(|(heading, rows)| (heading.as_str(), rows))
+            .chain((!ungrouped.is_empty()).then_some((other, &ungrouped)));
+        for (heading, rows) in sections {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&self.paint(heading, Some(BOLD)));
+            out.push('\n');
+            out.push_str(&self.render_rows(rows));
+        }
+        out
+    }
+
+    fn render_rows(&self, rows: &[&Row]) -> String {
+        let id_width = rows
+            .iter()
+            .filter_map(|r| r.id)
+            .max()
+            .map_or(1, |id| id.to_string().len());
+        let has_priority = rows.iter().any(|r| r.item.priority.priority.is_some());
+        let lines: Vec<(Vec<Span>, Vec<Span>, bool)> =
+            rows.iter().map(|row| self.spans(row.item)).collect();
+        let text_width = lines
+            .iter()
+            .map(|(text, _, _)| span_width(text))
+            .max()
+            .unwrap_or(0);
+
+        let mut out = String::new();
+        for (row, (text, dates, dim)) in rows.iter().zip(&lines) {
+            let mut line = match row.id {
+                Some(id) => format!("{:>width$}", id, width = id_width),
+                None => format!("{:>width$}", "x", width = id_width),
+            };
+            line.push(' ');
+            if has_priority {
+                let priority = match row.item.priority.priority {
+                    Some(p) if !row.item.done => format!("({})", (p + b'A') as char),
+                    _ => "   ".to_string(),
+                };
+                let color = row
+                    .item
+                    .priority
+                    .priority
+                    .and_then(|p| self.palette.map(|c| c.priorities[(p as usize).min(3)]));
+                line.push_str(&self.paint_unless(&priority, color, *dim));
+                line.push(' ');
+            }
+            line.push_str(&self.join(text, *dim));
+            if !dates.is_empty() {
+                line.push_str(&" ".repeat(text_width - span_width(text) + 2));
+                line.push_str(&self.join(dates, *dim));
+            }
+            if *dim {
+                line = self.paint(&line, Some(DIM));
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// The description column and the dates column of an item, and whether the line is
+    /// dimmed: done items and items whose threshold date is still ahead.
+    fn spans(&self, item: &TodoItem) -> (Vec<Span>, Vec<Span>, bool) {
+        let palette = self.palette;
+        let projects: Vec<String> = item.projects.iter().map(|p| p.to_string()).collect();
+        let contexts: Vec<String> = item.contexts.iter().map(|c| c.to_string()).collect();
+        let is_tag =
+            |tags: &[String], word: &str| tags.iter().any(|t| is_tag_word(word, t, self.grammar));
+
+        // The description as typed, with its projects and contexts colored in place and
+        // runs of other words kept together.
+        let mut text: Vec<Span> = Vec::new();
+        let mut words: Vec<&str> = Vec::new();
+        for word in item.description.split_whitespace() {
+            let color = if is_tag(&projects, word) {
+                palette.map(|c| c.project)
+            } else if is_tag(&contexts, word) {
+                palette.map(|c| c.context)
+            } else {
+                words.push(word);
+                continue;
+            };
+            if !words.is_empty() {
+                text.push((words.join(" "), None));
+                words.clear();
+            }
+            text.push((word.to_string(), color));
+        }
+        if !words.is_empty() || text.is_empty() {
+            text.push((words.join(" "), None));
+        }
+        text.extend(
+            projects
+                .iter()
+                .filter(|p| !item.is_inline_with(p, self.grammar))
+                .map(|p| (p.clone(), palette.map(|c| c.project))),
+        );
+        text.extend(
+            contexts
+                .iter()
+                .filter(|c| !item.is_inline_with(c, self.grammar))
+                .map(|c| (c.clone(), palette.map(|p| p.context))),
+        );
+        if let Some(ref recurrence) = item.recurrence {
+            text.push((format!("rec:{}", recurrence), None));
+        }
+
+        let mut dates = Vec::new();
+        if item.done {
+            if let Some(completed) = item.completion_date {
+                dates.push((format!("done {}", self.relative(completed)), None));
+            }
+        } else if let Some(due) = item.due {
+            let color = if item.is_overdue(self.now) {
+                palette.map(|c| c.overdue)
+            } else if due == self.now.date() {
+                palette.map(|c| c.today)
+            } else {
+                None
+            };
+            let when = match item.due_time {
+                Some(time) => self.relative_time(due.and_time(time)),
+                None => self.relative(due),
+            };
+            dates.push((format!("due {}", when), color));
+        }
+        let waiting = item.threshold_at().is_some_and(|t| t > self.now);
+        if waiting && !item.done {
+            let threshold = item.threshold.unwrap();
+            let when = match item.threshold_time {
+                Some(time) => self.relative_time(threshold.and_time(time)),
+                None => self.relative(threshold),
+            };
+            dates.push((format!("starts {}", when), None));
+        }
+        (text, dates, item.done || waiting)
+    }
+
+    fn relative(&self, date: NaiveDate) -> String {
+        match (date - self.now.date()).num_days() {
+            0 => "today".to_string(),
+            1 => "tomorrow".to_string(),
+            -1 => "yesterday".to_string(),
+            days if (2..=RELATIVE_DAYS).contains(&days) => format!("in {}d", days),
+            days if (2..=RELATIVE_DAYS).contains(&-days) => format!("{}d ago", -days),
+            _ => date.format(self.date_format).to_string(),
+        }
+    }
+
+    /// A time of day: in minutes or hours when it's close, else its day and the time.
+    fn relative_time(&self, at: NaiveDateTime) -> String {
+        let minutes = (at - self.now).num_minutes();
+        match minutes {
+            0..60 => format!("in {}m", minutes),
+            -59..0 => format!("{}m ago", -minutes),
+            _ if minutes.abs() < RELATIVE_HOURS * 60 => match minutes / 60 {
+                hours if hours > 0 => format!("in {}h", hours),
+                hours => format!("{}h ago", -hours),
+            },
+            _ => format!("{} {}", self.relative(at.date()), at.format("%H:%M")),
+        }
+    }
+
+    /// Joins spans with spaces, coloring each unless the whole line is going to be dimmed.
+    fn join(&self, spans: &[Span], dim: bool) -> String {
+        spans
+            .iter()
+            .map(|(text, color)| self.paint_unless(text, *color, dim))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn paint_unless(&self, text: &str, color: Option<&str>, dim: bool) -> String {
+        if dim {
+            text.to_string()
+        } else {
+            self.paint(text, color)
+        }
+    }
+
+    fn paint(&self, text: &str, color: Option<&str>) -> String {
+        match color {
+            Some(code) if self.palette.is_some() => format!("\x1b[{}m{}\x1b[0m", code, text),
+            _ => text.to_string(),
+        }
+    }
+}
+
+fn span_width(spans: &[Span]) -> usize {
+    let words: usize = spans.iter().map(|(text, _)| text.chars().count()).sum();
+    words + spans.len().saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noon(s: &str) -> NaiveDateTime {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    fn items() -> Vec<TodoItem> {
+        [
+            "(A) Plan release +work @office due:2026-10-17",
+            "x 2026-10-18 Book venue +party",
+            "Write notes +work due:2026-10-21",
+            "Paint fence t:2026-10-25",
+            "Buy milk @store due:2026-11-30",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect()
+    }
+
+    fn rows(items: &[TodoItem]) -> Vec<Row<'_>> {
+        items
+            .iter()
+            .zip(open_ids(items))
+            .map(|(item, id)| Row { id, item })
+            .collect()
+    }
+
+    #[test]
+    fn test_plain_layout() {
+        let items = items();
+        let grammar = NameGrammar::default();
+        let renderer = Renderer::new(
+            false,
+            Theme::Dark,
+            noon("2026-10-18"),
+            Some("%d.%m.%Y"),
+            &grammar,
+        );
+        assert_eq!(
+            renderer.render(&rows(&items), None),
+            "1 (A) Plan release +work @office  due yesterday\n\
+             x     Book venue +party           done today\n\
+             2     Write notes +work           due in 3d\n\
+             3     Paint fence                 starts 25.10.2026\n\
+             4     Buy milk @store             due 30.11.2026\n"
+        );
+    }
+
+    #[test]
+    fn test_grouped_by_project() {
+        let items = items();
+        let grammar = NameGrammar::default();
+        let renderer = Renderer::new(false, Theme::Dark, noon("2026-10-18"), None, &grammar);
+        let out = renderer.render(&rows(&items[..3]), Some(Grouping::Project));
+        assert_eq!(
+            out,
+            "+party\n\
+             x Book venue +party  done today\n\
+             \n\
+             +work\n\
+             1 (A) Plan release +work @office  due yesterday\n\
+             2     Write notes +work           due in 3d\n"
+        );
+    }
+
+    #[test]
+    fn test_colors() {
+        let items = items();
+        let grammar = NameGrammar::default();
+        let renderer = Renderer::new(true, Theme::Light, noon("2026-10-18"), None, &grammar);
+        let out = renderer.render(&rows(&items[..2]), None);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "1 \x1b[1;31m(A)\x1b[0m Plan release \x1b[34m+work\x1b[0m \x1b[35m@office\x1b[0m  \
+             \x1b[31mdue yesterday\x1b[0m"
+        );
+        // Done items are dimmed as a whole, without their other colors.
+        assert_eq!(
+            lines[1],
+            "\x1b[2mx     Book venue +party           done today\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_inline_tags_keep_their_place() {
+        let item: TodoItem = "Call @mom about +garden plans +home".parse().unwrap();
+        let mut extra = item.clone();
+        extra.projects.push("+later".parse().unwrap());
+        let items = [item, extra];
+        let grammar = NameGrammar::default();
+        let renderer = Renderer::new(true, Theme::Light, noon("2026-10-18"), None, &grammar);
+        let out = renderer.render(&rows(&items), None);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "1 Call \x1b[35m@mom\x1b[0m about \x1b[34m+garden\x1b[0m plans \x1b[34m+home\x1b[0m"
+        );
+        assert!(lines[1].ends_with("\x1b[34m+home\x1b[0m \x1b[34m+later\x1b[0m"));
+    }
+
+    #[test]
+    fn test_times_of_day() {
+        let items: Vec<TodoItem> = [
+            "Handover due:2026-10-18T11:20",
+            "Deploy due:2026-10-18T15:30",
+            "Call vendor due:2026-10-20T09:00",
+            "Night shift t:2026-10-18T22:00",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+        let grammar = NameGrammar::default();
+        let renderer = Renderer::new(true, Theme::Dark, noon("2026-10-18"), None, &grammar);
+        let out = renderer.render(&rows(&items), None);
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].ends_with("\x1b[91mdue 40m ago\x1b[0m"));
+        assert!(lines[1].ends_with("\x1b[1;93mdue in 3h\x1b[0m"));
+        assert!(lines[2].ends_with("  due in 2d 09:00"));
+        assert!(lines[3].contains("starts in 10h"));
+    }
+
+    #[test]
+    fn test_color_choice() {
+        assert!(ColorChoice::Always.enabled(Theme::Dark));
+        assert!(!ColorChoice::Always.enabled(Theme::None));
+        assert!(!ColorChoice::Never.enabled(Theme::Light));
+    }
+}
@@ -0,0 +1,478 @@
+//! `rtmcli serve`: a small REST API over a todo file, for editor plugins, dashboards and
+//! launchers that would rather speak HTTP than shell out.
+//!
+//! Requests are handled one at a time. Each one re-reads the file, so edits made by other
+//! tools in the meantime are picked up, and every mutation is written back through
+//! TodoLibrary::save before the response is sent. Only requests whose Host and Origin are
+//! localhost are served, so a web page open in a browser can't use the API.
+
+use crate::{Filter, filter_matches, parse_filter};
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+use todotxt::todo_item::format_date_time;
+use todotxt::todo_names::NameGrammar;
+use todotxt::{TodoItem, TodoLibrary, TodoPatch};
+
+/// An item as returned by the API. `index` is its position in the file, which together
+/// with `uuid` can be used to address it in later requests.
+#[derive(Serialize)]
+struct ItemJson {
+    index: usize,
+    text: String,
+    done: bool,
+    priority: Option<String>,
+    creation_date: Option<String>,
+    completion_date: Option<String>,
+    description: String,
+    projects: Vec<String>,
+    contexts: Vec<String>,
+    /// YYYY-MM-DD, or YYYY-MM-DDTHH:MM for items due at a time of day; `threshold` too.
+    due: Option<String>,
+    threshold: Option<String>,
+    recurrence: Option<String>,
+    uuid: Option<String>,
+    sub: Option<String>,
+    deps: Vec<String>,
+}
+
+impl ItemJson {
+    fn new(index: usize, item: &TodoItem) -> Self {
+        let date = |d: Option<NaiveDate>| d.map(|d| d.format("%Y-%m-%d").to_string());
+        ItemJson {
+            index,
+            text: item.to_string(),
+            done: item.done,
+            priority: item
+                .priority
+                .priority
+                .map(|p| ((p + b'A') as char).to_string()),
+            creation_date: date(item.creation_date),
+            completion_date: date(item.completion_date),
+            description: item.description.clone(),
+            projects: item.projects.iter().map(|p| p.name.clone()).collect(),
+            contexts: item.contexts.iter().map(|c| c.name.clone()).collect(),
+            due: item.due.map(|d| format_date_time(d, item.due_time)),
+            threshold: item
+                .threshold
+                .map(|t| format_date_time(t, item.threshold_time)),
+            recurrence: item.recurrence.as_ref().map(|r| r.to_string()),
+            uuid: item.uuid.map(|u| u.to_string()),
+            sub: item.sub.map(|u| u.to_string()),
+            deps: item.deps.iter().map(|u| u.to_string()).collect(),
+        }
+    }
+}
+
+/// An error response: the status code and a message returned as `{"error": ...}`.
+struct ApiError(u16, String);
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        ApiError(400, message.into())
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        ApiError(404, message.into())
+    }
+}
+
+type ApiResult = Result<(u16, Value), ApiError>;
+
+pub fn serve(file_name: &str, grammar: &NameGrammar, port: u16, token: Option<String>) {
+    let server = Server::http(("127.0.0.1", port)).unwrap_or_else(|e| {
+        eprintln!("Error binding to 127.0.0.1:{}: {}", port, e);
+        std::process::exit(1);
+    });
+    println!("Serving '{}' on http://127.0.0.1:{}", file_name, port);
+
+    for mut request in server.incoming_requests() {
+        let result = if !same_origin(&request) {
+            Err(ApiError(
+                403,
+                "only requests from localhost are accepted".to_string(),
+            ))
+        } else if authorized(&request, token.as_deref()) {
+            let mut body = String::new();
+            match request.as_reader().read_to_string(&mut body) {
+                Ok(_) => handle(file_name, grammar, request.method(), request.url(), &body),
+                Err(e) => Err(ApiError::bad_request(e.to_string())),
+            }
+        } else {
+            Err(ApiError(401, "missing or invalid bearer token".to_string()))
+        };
+
+        let (status, value) = result
+            .unwrap_or_else(|ApiError(status, message)| (status, json!({ "error": message })));
+        let response = Response::from_string(value.to_string())
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes("Content-Type", "application/json").expect("static header"),
+            );
+        if let Err(e) = request.respond(response) {
+            eprintln!("Error sending response: {}", e);
+        }
+    }
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+/// Whether the request comes from this machine rather than a web page elsewhere: its Host
+/// must be a loopback name, which defeats DNS rebinding, and a browser's Origin, if sent,
+/// must be one too, which stops other sites posting to the API.
+fn same_origin(request: &Request) -> bool {
+    header(request, "Host").is_some_and(is_loopback)
+        && header(request, "Origin").is_none_or(|origin| {
+            origin
+                .strip_prefix("http://")
+                .or_else(|| origin.strip_prefix("https://"))
+                .is_some_and(is_loopback)
+        })
+}
+
+/// Whether `host`, with an optional port, names the loopback interface.
+fn is_loopback(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split_once(']').map_or(rest, |(name, _)| name),
+        None => host.split_once(':').map_or(host, |(name, _)| name),
+    };
+    matches!(name, "localhost" | "127.0.0.1" | "::1")
+}
+
+fn authorized(request: &Request, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    header(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+/// Compares without stopping at the first difference, so response times don't give the
+/// token away a byte at a time.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn handle(
+    file_name: &str,
+    grammar: &NameGrammar,
+    method: &Method,
+    url: &str,
+    body: &str,
+) -> ApiResult {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let mut lib = TodoLibrary::new(file_name.to_string());
+    lib.grammar = grammar.clone();
+    if let Err(e) = lib.load() {
+        // A file that doesn't exist yet is just an empty list, as for `rtmcli add`.
+        if e.kind() != std::io::ErrorKind::NotFound {
+            return Err(ApiError(500, e.to_string()));
+        }
+    }
+
+    let (status, value, modified) = match (method, segments.as_slice()) {
+        (Method::Get, ["items"]) => (200, list_items(&lib, query)?, false),
+        (Method::Post, ["items"]) => {
+            let item = parse_text(body, grammar)?;
+            lib.add_item(item);
+            let index = lib.item_count() - 1;
+            (201, item_json(&lib, index), true)
+        }
+        (Method::Get, ["items", id]) => (200, item_json(&lib, find(&lib, id)?), false),
+        (Method::Patch, ["items", id]) => {
+            let index = find(&lib, id)?;
+            lib.items[index] = patch_item(&lib.items[index], body, grammar)?;
+            (200, item_json(&lib, index), true)
+        }
+        (Method::Delete, ["items", id]) => {
+            let index = find(&lib, id)?;
+            let removed = lib.remove_item(index).expect("index was just found");
+            (200, json!(ItemJson::new(index, &removed)), true)
+        }
+        (Method::Post, ["items", id, "complete"]) => {
+            let index = find(&lib, id)?;
+            lib.complete_item(index);
+            (200, item_json(&lib, index), true)
+        }
+        (Method::Post, ["items", id, "uncomplete"]) => {
+            let index = find(&lib, id)?;
+            lib.uncomplete_item(index);
+            (200, item_json(&lib, index), true)
+        }
+        (Method::Get, ["items", id, "subtasks"]) => {
+            let index = find(&lib, id)?;
+            let subtasks: Vec<ItemJson> = match lib.items[index].uuid {
+                Some(uuid) => lib
+                    .items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, item)| item.sub == Some(uuid))
+                    .map(|(i, item)| ItemJson::new(i, item))
+                    .collect(),
+                None => Vec::new(),
+            };
+            (200, json!(subtasks), false)
+        }
+        (Method::Post, ["items", id, "subtasks"]) => {
+            let index = find(&lib, id)?;
+            let child = parse_text(body, grammar)?;
+            let (new_parent, new_child) = lib.items[index].add_subtask(&child);
+            if let Some(parent) = new_parent {
+                lib.items[index] = parent;
+            }
+            lib.add_item(new_child);
+            let child_index = lib.item_count() - 1;
+            (201, item_json(&lib, child_index), true)
+        }
+        _ => {
+            return Err(ApiError::not_found(format!(
+                "no route for {} {}",
+                method, path
+            )));
+        }
+    };
+
+    if modified {
+        lib.save().map_err(|e| ApiError(500, e.to_string()))?;
+    }
+    Ok((status, value))
+}
+
+fn item_json(lib: &TodoLibrary, index: usize) -> Value {
+    json!(ItemJson::new(index, &lib.items[index]))
+}
+
+/// Resolves a path segment -- an index into the file or an item's uuid -- to an index.
+fn find(lib: &TodoLibrary, id: &str) -> Result<usize, ApiError> {
+    if let Ok(index) = id.parse::<usize>() {
+        if index < lib.item_count() {
+            return Ok(index);
+        }
+    } else if let Ok(uuid) = id.parse::<uuid::Uuid>()
+        && let Some(index) = lib.items.iter().position(|item| item.uuid == Some(uuid))
+    {
+        return Ok(index);
+    }
+    Err(ApiError::not_found(format!("no item '{}'", id)))
+}
+
+/// Query parameters: `done=true|false` (default false), `filter=` one of the CLI's date
+/// filters, and `project=` / `context=` names without their sigil, which match their
+/// subprojects and subcontexts too.
+fn list_items(lib: &TodoLibrary, query: &str) -> Result<Value, ApiError> {
+    let mut done = false;
+    let mut date_filter: Option<Filter> = None;
+    let mut project = None;
+    let mut context = None;
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "done" => {
+                done = value
+                    .parse()
+                    .map_err(|_| ApiError::bad_request(format!("invalid done '{}'", value)))?
+            }
+            "filter" => {
+                date_filter =
+                    Some(parse_filter(value).ok_or_else(|| {
+                        ApiError::bad_request(format!("unknown filter '{}'", value))
+                    })?);
+            }
+            "project" => project = Some(value.to_string()),
+            "context" => context = Some(value.to_string()),
+            _ => {
+                return Err(ApiError::bad_request(format!(
+                    "unknown parameter '{}'",
+                    key
+                )));
+            }
+        }
+    }
+
+    let items: Vec<ItemJson> = lib
+        .items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.done == done)
+        .filter(|(_, item)| {
+            date_filter
+                .as_ref()
+                .is_none_or(|f| filter_matches(f, item, Local::now().naive_local()))
+        })
+        .filter(|(_, item)| {
+            project.as_ref().is_none_or(|name| {
+                item.projects
+                    .iter()
+                    .any(|p| lib.grammar.is_within(&p.name, name))
+            })
+        })
+        .filter(|(_, item)| {
+            context.as_ref().is_none_or(|name| {
+                item.contexts
+                    .iter()
+                    .any(|c| lib.grammar.is_within(&c.name, name))
+            })
+        })
+        .map(|(i, item)| ItemJson::new(i, item))
+        .collect();
+    Ok(json!(items))
+}
+
+/// Parses a `{"text": "<todo.txt line>"}` request body.
+fn parse_text(body: &str, grammar: &NameGrammar) -> Result<TodoItem, ApiError> {
+    let value: Value =
+        serde_json::from_str(body).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let text = value
+        .get("text")
+        .and_then(Value::as_str)
+        .ok_or_else(|| ApiError::bad_request("body must be {\"text\": \"...\"}"))?;
+    TodoItem::parse_with(text, grammar)
+        .map_err(|e| ApiError::bad_request(format!("invalid todo: {}", e)))
+}
+
+/// Applies a JSON merge-patch style body to an item: a field that is absent is kept, `null`
+/// clears it, and any other value sets it. Every field is validated before anything is
+/// applied, so a bad request leaves the item untouched.
+fn patch_item(item: &TodoItem, body: &str, grammar: &NameGrammar) -> Result<TodoItem, ApiError> {
+    let patch: TodoPatch =
+        serde_json::from_str(body).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    patch.apply_with(item, grammar).map_err(|errors| {
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        ApiError::bad_request(messages.join("; "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_add_patch_complete_and_delete() {
+        let temp_file = std::env::temp_dir().join("test_serve_handle.txt");
+        let file_name = temp_file.to_str().unwrap();
+        let grammar = NameGrammar::default();
+        fs::write(&temp_file, "(B) Write report +work due:2026-10-20\n").unwrap();
+
+        let (status, value) = handle(
+            file_name,
+            &grammar,
+            &Method::Post,
+            "/items",
+            r#"{"text": "Buy milk @store"}"#,
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(status, 201);
+        assert_eq!(value["index"], 1);
+        assert_eq!(value["contexts"], json!(["store"]));
+
+        let (_, value) = handle(
+            file_name,
+            &grammar,
+            &Method::Patch,
+            "/items/0",
+            r#"{"priority": null, "due": "2026-10-22", "projects": ["home"]}"#,
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(value["text"], "Write report +home due:2026-10-22");
+
+        // An invalid field, or a line that wouldn't load back, rejects the whole patch.
+        let err = handle(
+            file_name,
+            &grammar,
+            &Method::Patch,
+            "/items/0",
+            r#"{"description": "Water plants due:soon"}"#,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.0, 400);
+        let err = handle(
+            file_name,
+            &grammar,
+            &Method::Patch,
+            "/items/0",
+            r#"{"description": "Changed", "due": "soon"}"#,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.0, 400);
+
+        handle(file_name, &grammar, &Method::Post, "/items/0/complete", "")
+            .ok()
+            .unwrap();
+        let (_, value) = handle(file_name, &grammar, &Method::Get, "/items", "")
+            .ok()
+            .unwrap();
+        assert_eq!(value.as_array().unwrap().len(), 1);
+        let (_, value) = handle(file_name, &grammar, &Method::Get, "/items?done=true", "")
+            .ok()
+            .unwrap();
+        // Read back from the file, the +home written after the description is part of it.
+        assert_eq!(value[0]["description"], "Write report +home");
+
+        handle(file_name, &grammar, &Method::Delete, "/items/1", "")
+            .ok()
+            .unwrap();
+        let content = fs::read_to_string(&temp_file).unwrap();
+        assert_eq!(content.lines().count(), 1);
+        assert!(content.starts_with("x "));
+
+        assert_eq!(
+            handle(file_name, &grammar, &Method::Get, "/items/7", "")
+                .err()
+                .unwrap()
+                .0,
+            404
+        );
+        fs::remove_file(&temp_file).unwrap();
+    }
+
+    #[test]
+    fn test_added_items_follow_the_grammar() {
+        let temp_file = std::env::temp_dir().join("test_serve_grammar.txt");
+        let file_name = temp_file.to_str().unwrap();
+        let grammar = NameGrammar {
+            extra_chars: "+".to_string(),
+            ..NameGrammar::default()
+        };
+        let _ = fs::remove_file(&temp_file);
+
+        let (_, value) = handle(
+            file_name,
+            &grammar,
+            &Method::Post,
+            "/items",
+            r#"{"text": "Learn +c++"}"#,
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(value["projects"], json!(["c++"]));
+        fs::remove_file(&temp_file).unwrap();
+    }
+
+    #[test]
+    fn test_only_loopback_hosts_and_exact_tokens() {
+        assert!(is_loopback("127.0.0.1:8765"));
+        assert!(is_loopback("localhost"));
+        assert!(is_loopback("[::1]:8765"));
+        assert!(!is_loopback("evil.example:8765"));
+        assert!(!is_loopback("localhost.evil.example"));
+
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secrets"));
+    }
+}
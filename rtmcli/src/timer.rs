@@ -0,0 +1,290 @@
+//! `rtmcli timer`: start and stop timers on items, and report the time tracked.
+
+use crate::{Grouping, completions, find_open_item, load_with_done};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use clap::Subcommand;
+use clap_complete::engine::ArgValueCompleter;
+use todotxt::todo_timer::{self, TimeLog};
+use todotxt::{TodoItem, TodoLibrary};
+use uuid::Uuid;
+
+#[derive(Subcommand)]
+pub enum TimerAction {
+    /// Start a timer on an item, giving it a uuid if it has none
+    Start {
+        /// Index or uuid of the item
+        #[arg(add = ArgValueCompleter::new(completions::complete_id))]
+        id: String,
+    },
+    /// Stop a timer and add the time to the item's spent: tag
+    Stop {
+        /// Index or uuid of the item; may be left out while only one timer runs
+        #[arg(add = ArgValueCompleter::new(completions::complete_id))]
+        id: Option<String>,
+    },
+    /// Show the running timers
+    Status,
+    /// Show the time tracked per project or context
+    Report {
+        /// First day to count (YYYY-MM-DD); defaults to six days before --to
+        #[arg(long)]
+        from: Option<NaiveDate>,
+
+        /// Last day to count (YYYY-MM-DD); defaults to today
+        #[arg(long)]
+        to: Option<NaiveDate>,
+
+        /// Group the time by project or by context
+        #[arg(long, value_enum, default_value = "project")]
+        by: Grouping,
+
+        /// Done file to include; defaults to the list's done file, or done.txt next to
+        /// the todo file
+        #[arg(short, long)]
+        done: Option<String>,
+    },
+}
+
+/// The time log that belongs with `file_name`: `time_log_file_name` from the config file,
+/// otherwise `timelog.txt` in the same directory.
+pub fn default_log_file(configured: Option<&str>, file_name: &str) -> String {
+    configured.map(str::to_string).unwrap_or_else(|| {
+        std::path::Path::new(file_name)
+            .with_file_name("timelog.txt")
+            .to_string_lossy()
+            .to_string()
+    })
+}
+
+pub fn run(
+    action: TimerAction,
+    lib: &mut TodoLibrary,
+    log_file: String,
+    default_done: Option<String>,
+) -> Result<(), String> {
+    let now = Local::now().naive_local();
+    let mut log = TimeLog::new(log_file);
+    log.load()
+        .map_err(|e| format!("Error loading file '{}': {}", log.file_name, e))?;
+
+    match action {
+        TimerAction::Start { id } => {
+            load(lib)?;
+            let index = find_open_item(&lib.items, &id)?;
+            let item = &mut lib.items[index];
+            let uuid = match item.uuid {
+                Some(uuid) => uuid,
+                None => {
+                    let uuid = Uuid::new_v4();
+                    item.uuid = Some(uuid);
+                    lib.save()
+                        .map_err(|e| format!("Error saving file: {}", e))?;
+                    uuid
+                }
+            };
+            log.start(uuid, now).map_err(|e| e.to_string())?;
+            save(&log)?;
+            println!("Started timer for: {}", lib.items[index].description);
+        }
+        TimerAction::Stop { id } => {
+            load(lib)?;
+            let uuid = match id {
+                Some(id) => {
+                    let index = find_open_item(&lib.items, &id)?;
+                    lib.items[index]
+                        .uuid
+                        .ok_or_else(|| "No timer is running for this item".to_string())?
+                }
+                None => match log.running().map(|e| e.uuid).collect::<Vec<_>>()[..] {
+                    [uuid] => uuid,
+                    [] => return Err("No timer is running".to_string()),
+                    _ => return Err("Several timers are running; give an id".to_string()),
+                },
+            };
+            let session = log.stop(uuid, now).map_err(|e| e.to_string())?;
+            save(&log)?;
+
+            // The item may have been completed or deleted while the timer ran; the time
+            // stays in the log either way.
+            match lib.items.iter().position(|item| item.uuid == Some(uuid)) {
+                Some(index) => {
+                    let item = &lib.items[index];
+                    let spent = todo_timer::spent(item)
+                        .unwrap_or_else(Duration::zero)
+                        .checked_add(&session)
+                        .unwrap_or(Duration::MAX);
+                    lib.items[index] = todo_timer::set_spent(item, spent);
+                    lib.save()
+                        .map_err(|e| format!("Error saving file: {}", e))?;
+                    println!(
+                        "Stopped timer for: {} ({}, {} in total)",
+                        lib.items[index].description,
+                        todo_timer::format_duration(session),
+                        todo_timer::format_duration(spent)
+                    );
+                }
+                None => println!(
+                    "Stopped timer for {} ({})",
+                    uuid,
+                    todo_timer::format_duration(session)
+                ),
+            }
+        }
+        TimerAction::Status => {
+            load(lib)?;
+            let running: Vec<_> = log.running().collect();
+            if running.is_empty() {
+                println!("No timers running");
+            }
+            for entry in running {
+                let item = lib.items.iter().find(|item| item.uuid == Some(entry.uuid));
+                let name = item.map_or(entry.uuid.to_string(), |item| item.description.clone());
+                println!(
+                    "{}: running {}{}",
+                    name,
+                    todo_timer::format_duration(entry.duration(now)),
+                    item.map(progress).unwrap_or_default()
+                );
+            }
+        }
+        TimerAction::Report { from, to, by, done } => {
+            let to = to.unwrap_or(now.date());
+            let from = from.unwrap_or(to - Duration::days(6));
+            if from > to {
+                return Err(format!("--from {} is after --to {}", from, to));
+            }
+            let items = load_with_done(lib, done.or(default_done));
+            print!("{}", report(&log, &items, from, to, by, now));
+        }
+    }
+    Ok(())
+}
+
+fn load(lib: &mut TodoLibrary) -> Result<(), String> {
+    lib.load()
+        .map_err(|e| format!("Error loading file '{}': {}", lib.file_name, e))
+}
+
+fn save(log: &TimeLog) -> Result<(), String> {
+    log.save()
+        .map_err(|e| format!("Error saving file '{}': {}", log.file_name, e))
+}
+
+/// The time already spent on an item and its estimate, e.g. `, spent 1h of est 2h`.
+fn progress(item: &TodoItem) -> String {
+    match (todo_timer::spent(item), todo_timer::estimate(item)) {
+        (Some(spent), Some(est)) => format!(
+            ", spent {} of est {}",
+            todo_timer::format_duration(spent),
+            todo_timer::format_duration(est)
+        ),
+        (Some(spent), None) => format!(", spent {}", todo_timer::format_duration(spent)),
+        (None, Some(est)) => format!(", est {}", todo_timer::format_duration(est)),
+        (None, None) => String::new(),
+    }
+}
+
+fn report(
+    log: &TimeLog,
+    items: &[TodoItem],
+    from: NaiveDate,
+    to: NaiveDate,
+    by: Grouping,
+    now: NaiveDateTime,
+) -> String {
+    let totals = log.totals(from, to, now);
+    let by_tag = match by {
+        Grouping::Project => todo_timer::time_by_tag(items, &totals, |item| {
+            item.projects
+                .iter()
+                .map(|p| format!("+{}", p.name))
+                .collect()
+        }),
+        Grouping::Context => todo_timer::time_by_tag(items, &totals, |item| {
+            item.contexts
+                .iter()
+                .map(|c| format!("@{}", c.name))
+                .collect()
+        }),
+    };
+
+    let mut out = format!("Time tracked from {} to {}:\n", from, to);
+    if by_tag.is_empty() {
+        out.push_str("No time tracked.\n");
+        return out;
+    }
+    let none = match by {
+        Grouping::Project => "(no project)",
+        Grouping::Context => "(no context)",
+    };
+    // Untagged time goes last.
+    let rows: Vec<(String, String)> = by_tag
+        .iter()
+        .skip(usize::from(by_tag.contains_key(&None)))
+        .chain(by_tag.get_key_value(&None))
+        .map(|(tag, &time)| {
+            (
+                tag.clone().unwrap_or_else(|| none.to_string()),
+                todo_timer::format_duration(time),
+            )
+        })
+        .collect();
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, time) in rows {
+        out.push_str(&format!("  {:<width$}  {:>7}\n", name, time));
+    }
+    let total = totals.values().fold(Duration::zero(), |sum, &t| sum + t);
+    out.push_str(&format!(
+        "  {:<width$}  {:>7}\n",
+        "Total",
+        todo_timer::format_duration(total)
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use todotxt::todo_timer::TimeEntry;
+
+    #[test]
+    fn test_report_by_project() {
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        let items: Vec<TodoItem> = [
+            "Invoices +billing uuid:67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "Call Bob uuid:0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+        let mut log = TimeLog::new(String::new());
+        for (item, start, end) in [
+            (&items[0], "2026-10-17 09:00", "2026-10-17 10:30"),
+            (&items[1], "2026-10-18 11:00", "2026-10-18 11:20"),
+            // Before the range.
+            (&items[1], "2026-10-01 11:00", "2026-10-01 12:00"),
+        ] {
+            log.entries.push(TimeEntry {
+                uuid: item.uuid.unwrap(),
+                start: at(start),
+                end: Some(at(end)),
+            });
+        }
+        let day = |s: &str| s.parse::<NaiveDate>().unwrap();
+        let out = report(
+            &log,
+            &items,
+            day("2026-10-12"),
+            day("2026-10-18"),
+            Grouping::Project,
+            at("2026-10-18 12:00"),
+        );
+        assert_eq!(
+            out,
+            "Time tracked from 2026-10-12 to 2026-10-18:\n\
+             \x20 +billing        1h30m\n\
+             \x20 (no project)      20m\n\
+             \x20 Total           1h50m\n"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_borrows_for_generic_args)]
+
 use std::fs;
 use std::process::Command;
 
@@ -8,6 +10,29 @@ fn today() -> String {
         .to_string()
 }
 
+/// A directory of its own for one test, named after the test and this process so parallel
+/// and earlier runs can't interfere, and removed when dropped, even if the test fails.
+struct TestDir(std::path::PathBuf);
+
+impl TestDir {
+    fn new(test: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("rtmcli-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TestDir(path)
+    }
+
+    fn join(&self, name: &str) -> std::path::PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
 #[test]
 fn test_cli_add_single_task() {
     let temp_dir = std::env::temp_dir();
@@ -94,7 +119,9 @@ fn test_cli_list_tasks() {
 
     // List tasks
     let output = Command::new("cargo")
-        .args(&["run", "--bin", "rtmcli", "--", "-f", file_path, "list"])
+        .args(&[
+            "run", "--bin", "rtmcli", "--", "-f", file_path, "list", "--raw",
+        ])
         .output()
         .expect("Failed to run list");
 
@@ -204,6 +231,7 @@ fn test_cli_list_completed_tasks() {
             file_path,
             "list",
             "--completed",
+            "--raw",
         ])
         .output()
         .expect("Failed to list completed");
@@ -216,3 +244,707 @@ fn test_cli_list_completed_tasks() {
 
     fs::remove_file(&temp_file).unwrap();
 }
+
+#[test]
+fn test_cli_list_selection_and_move() {
+    let dir = TestDir::new("list_selection_and_move");
+    let work_file = dir.join("work.txt");
+    let home_file = dir.join("home.txt");
+    let config_file = dir.join("config.toml");
+
+    fs::write(
+        &config_file,
+        format!(
+            "[[lists]]\nname = \"work\"\nfile_name = {:?}\n\n[[lists]]\nname = \"home\"\nfile_name = {:?}\n\n[[lists]]\nname = \"gone\"\nfile_name = {:?}\n",
+            work_file.to_str().unwrap(),
+            home_file.to_str().unwrap(),
+            dir.join("missing/gone.txt").to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    // Add to the "work" list by name
+    let output = Command::new("cargo")
+        .args([
+            "run", "--bin", "rtmcli", "--", "--list", "work", "add", "Fix sink",
+        ])
+        .env("RTM_CONFIG", &config_file)
+        .output()
+        .expect("Failed to run add command");
+    assert!(output.status.success());
+    let today = today();
+    assert_eq!(
+        fs::read_to_string(&work_file).unwrap().trim(),
+        format!("{} Fix sink", today)
+    );
+
+    // Move it to "home"
+    let output = Command::new("cargo")
+        .args([
+            "run", "--bin", "rtmcli", "--", "--list", "work", "move", "1", "home",
+        ])
+        .env("RTM_CONFIG", &config_file)
+        .output()
+        .expect("Failed to run move command");
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&work_file).unwrap().trim(), "");
+    assert_eq!(
+        fs::read_to_string(&home_file).unwrap().trim(),
+        format!("{} Fix sink", today)
+    );
+
+    // Moving into the same list, or into a file that can't be read, changes nothing
+    let output = Command::new("cargo")
+        .args([
+            "run", "--bin", "rtmcli", "--", "--list", "home", "move", "1", "home",
+        ])
+        .env("RTM_CONFIG", &config_file)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("same list"));
+    fs::write(&work_file, b"\xff\xfe not text").unwrap();
+    let output = Command::new("cargo")
+        .args([
+            "run", "--bin", "rtmcli", "--", "--list", "home", "move", "1", "work",
+        ])
+        .env("RTM_CONFIG", &config_file)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(fs::read(&work_file).unwrap(), b"\xff\xfe not text");
+    assert!(fs::read_to_string(&home_file).unwrap().contains("Fix sink"));
+
+    // A target that can't be written leaves the item in the source
+    let output = Command::new("cargo")
+        .args([
+            "run", "--bin", "rtmcli", "--", "--list", "home", "move", "1", "gone",
+        ])
+        .env("RTM_CONFIG", &config_file)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(fs::read_to_string(&home_file).unwrap().contains("Fix sink"));
+
+    // Unknown list names are rejected
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "rtmcli", "--", "--list", "nope", "list"])
+        .env("RTM_CONFIG", &config_file)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_cli_report_includes_done_file() {
+    let temp_dir = TestDir::new("report_includes_done_file");
+    let todo_file = temp_dir.join("todo.txt");
+    fs::write(&todo_file, "(A) Plan release +work\nBuy milk\n").unwrap();
+    fs::write(
+        temp_dir.join("done.txt"),
+        format!("x {} Book venue +work\n", today()),
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--bin",
+            "rtmcli",
+            "--",
+            "-f",
+            todo_file.to_str().unwrap(),
+            "report",
+            "--json",
+        ])
+        .output()
+        .expect("Failed to run report command");
+    assert!(output.status.success());
+
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["open"], 2);
+    assert_eq!(stats["done"], 1);
+    assert_eq!(stats["throughput"][13]["completed"], 1);
+    assert_eq!(stats["projects"][0]["name"], "work");
+}
+
+#[test]
+fn test_cli_config_defaults_and_aliases() {
+    let dir = TestDir::new("config_defaults_and_aliases");
+    let todo_file = dir.join("todo.txt");
+    let config_file = dir.join("config.toml");
+
+    fs::write(
+        &todo_file,
+        "Write notes +work due:2026-10-20\n\
+         (A) Plan release +work due:2026-10-19\n\
+         Later +work t:2099-01-01\n\
+         Buy milk @store\n",
+    )
+    .unwrap();
+    fs::write(
+        &config_file,
+        format!(
+            "file_name = {:?}\nsort = \"priority\"\ndate_format = \"%d.%m.%Y\"\n\n[aliases]\nw = \"list +work actionable\"\n",
+            todo_file.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    // No -f: the config's file_name is used, and `w` expands to its alias.
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "rtmcli", "--", "w", "--raw"])
+        .env("RTM_CONFIG", &config_file)
+        .env_remove("TODOTXT")
+        .output()
+        .expect("Failed to run aliased command");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().skip(1).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "1. (A) Plan release +work due:19.10.2026",
+            "2. Write notes +work due:20.10.2026",
+        ]
+    );
+
+    // A --sort flag overrides the config.
+    let output = Command::new("cargo")
+        .args([
+            "run", "--bin", "rtmcli", "--", "list", "--sort", "file", "--raw", "+work",
+        ])
+        .env("RTM_CONFIG", &config_file)
+        .env_remove("TODOTXT")
+        .output()
+        .expect("Failed to run list command");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("1. Write notes"));
+    assert!(stdout.contains("3. Later +work t:01.01.2099"));
+}
+
+#[test]
+fn test_cli_list_rendered_layout() {
+    let dir = TestDir::new("list_rendered_layout");
+    let temp_file = dir.join("todo.txt");
+    let file_path = temp_file.to_str().unwrap();
+    fs::write(
+        &temp_file,
+        format!(
+            "(B) Call plumber @phone due:{}\nx Done already\nWater plants +home\n",
+            today()
+        ),
+    )
+    .unwrap();
+
+    // Output isn't a terminal here, so there are no colors even without --color never.
+    let output = Command::new("cargo")
+        .args([
+            "run", "--bin", "rtmcli", "--", "-f", file_path, "list", "--group", "context",
+        ])
+        .output()
+        .expect("Failed to run list");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout.lines().skip(1).collect::<Vec<_>>(),
+        vec![
+            "@phone",
+            "1 (B) Call plumber @phone  due today",
+            "",
+            "(no context)",
+            "2 Water plants +home",
+        ]
+    );
+}
+
+#[test]
+fn test_cli_edit_with_editor() {
+    let dir = TestDir::new("edit_with_editor");
+    let temp_file = dir.join("todo.txt");
+    let file_path = temp_file.to_str().unwrap();
+    fs::write(
+        &temp_file,
+        "2026-10-01 Buy milk @store\n2026-10-01 Call mom\n",
+    )
+    .unwrap();
+
+    // A non-interactive "editor" that changes the selected line in place.
+    let output = Command::new("cargo")
+        .args([
+            "run", "--bin", "rtmcli", "--", "-f", file_path, "edit", "@store",
+        ])
+        .env_remove("VISUAL")
+        .env("EDITOR", "sed -i s/milk/bread/")
+        .output()
+        .expect("Failed to run edit command");
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(&temp_file).unwrap(),
+        "2026-10-01 Buy bread @store\n2026-10-01 Call mom"
+    );
+
+    // An invalid line is reported and nothing is saved.
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "rtmcli", "--", "-f", file_path, "edit", "2"])
+        .env_remove("VISUAL")
+        .env("EDITOR", "sed -i s/Call/due:someday/")
+        .output()
+        .expect("Failed to run edit command");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Line 3: \"2026-10-01 due:someday mom\": invalid date"));
+    let kept = stderr.split('\'').nth(1).unwrap();
+    fs::remove_file(kept).unwrap();
+    assert!(
+        fs::read_to_string(&temp_file)
+            .unwrap()
+            .ends_with("Call mom")
+    );
+}
+
+#[test]
+fn test_cli_modify_dry_run_and_confirm() {
+    let dir = TestDir::new("modify_dry_run_and_confirm");
+    let temp_file = dir.join("todo.txt");
+    let config_file = dir.join("config.toml");
+    let file_path = temp_file.to_str().unwrap();
+    let original = "Write report +work due:2026-10-20\nBuy milk @store\nPlan sprint +work";
+    fs::write(&temp_file, original).unwrap();
+    fs::write(&config_file, "confirm_above = 1\n").unwrap();
+
+    let modify = |extra: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--bin", "rtmcli", "--", "-f", file_path, "modify"])
+            .args(["+work", "--add", "@office", "--due", "+1d"])
+            .args(extra)
+            .env("RTM_CONFIG", &config_file)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .expect("Failed to run modify command")
+    };
+
+    let output = modify(&["--dry-run"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        "-Write report +work due:2026-10-20\n\
+         +Write report +work @office due:2026-10-21\n\
+         -Plan sprint +work\n\
+         +Plan sprint +work @office\n\
+         2 item(s) would be changed\n"
+    );
+    assert_eq!(fs::read_to_string(&temp_file).unwrap(), original);
+
+    // More items than `confirm_above`, and no answer on stdin.
+    let output = modify(&[]);
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .ends_with("Nothing changed\n")
+    );
+    assert_eq!(fs::read_to_string(&temp_file).unwrap(), original);
+
+    let output = modify(&["--yes"]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(&temp_file).unwrap(),
+        "Write report +work @office due:2026-10-21\nBuy milk @store\nPlan sprint +work @office"
+    );
+}
+
+#[test]
+fn test_cli_timer_start_stop() {
+    let dir = TestDir::new("timer_start_stop");
+    let temp_file = dir.join("todo.txt");
+    let config_file = dir.join("config.toml");
+    let log_file = dir.join("timelog.txt");
+    let file_path = temp_file.to_str().unwrap();
+    fs::write(&temp_file, "Write report +work est:2h").unwrap();
+    fs::write(&config_file, "").unwrap();
+
+    let timer = |args: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--bin", "rtmcli", "--", "-f", file_path, "timer"])
+            .args(args)
+            .env("RTM_CONFIG", &config_file)
+            .output()
+            .expect("Failed to run timer command")
+    };
+
+    let output = timer(&["start", "1"]);
+    assert!(output.status.success());
+    let content = fs::read_to_string(&temp_file).unwrap();
+    let uuid = content.split("uuid:").nth(1).expect("start assigns a uuid");
+    assert!(fs::read_to_string(&log_file).unwrap().starts_with(uuid));
+
+    assert!(!timer(&["start", "1"]).status.success());
+    let status = String::from_utf8(timer(&["status"]).stdout).unwrap();
+    assert!(status.contains("Write report"), "{}", status);
+    assert!(status.contains("running 0m, est 2h"), "{}", status);
+
+    let output = timer(&["stop"]);
+    assert!(output.status.success());
+    assert!(fs::read_to_string(&temp_file).unwrap().contains("spent:0m"));
+    let status = String::from_utf8(timer(&["status"]).stdout).unwrap();
+    assert_eq!(status, "No timers running\n");
+    assert!(!timer(&["stop"]).status.success());
+
+    fs::write(
+        &log_file,
+        format!("{} 2026-10-01T09:00:00 2026-10-01T10:30:00\n", uuid),
+    )
+    .unwrap();
+    let output = timer(&["report", "--from", "2026-10-01", "--to", "2026-10-07"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Time tracked from 2026-10-01 to 2026-10-07:\n  +work    1h30m\n  Total    1h30m\n"
+    );
+}
+
+#[test]
+fn test_cli_depend_and_blocked() {
+    let dir = TestDir::new("depend_and_blocked");
+    let temp_file = dir.join("todo.txt");
+    let file_path = temp_file.to_str().unwrap();
+    fs::write(&temp_file, "Design schema\nBuild API\nWrite docs").unwrap();
+
+    let rtmcli = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "rtmcli", "--", "-f", file_path])
+            .args(args)
+            .output()
+            .expect("Failed to run rtmcli");
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(
+        rtmcli(&["depend", "2", "1"]),
+        "'Build API' now waits for 'Design schema'\n"
+    );
+    let content = fs::read_to_string(&temp_file).unwrap();
+    let uuid = content
+        .lines()
+        .next()
+        .unwrap()
+        .split("uuid:")
+        .nth(1)
+        .unwrap();
+    assert!(content.contains(&format!("Build API dep:{}", uuid)));
+
+    let blocked = rtmcli(&["list", "--raw", "blocked"]);
+    assert_eq!(
+        blocked,
+        format!("Items in '{}':\n1. Build API dep:{}\n", file_path, uuid)
+    );
+    let unblocked = rtmcli(&["list", "--raw", "unblocked"]);
+    assert!(unblocked.contains("Design schema") && unblocked.contains("Write docs"));
+    assert!(!unblocked.contains("Build API"));
+
+    let output = Command::new("cargo")
+        .args([
+            "run", "--bin", "rtmcli", "--", "-f", file_path, "depend", "1", "2",
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("cycle"));
+
+    let completed = rtmcli(&["complete", "1"]);
+    assert!(
+        completed.ends_with("Unblocked: Build API\n"),
+        "{}",
+        completed
+    );
+    // The dep: tag stays, so un-completing the item would block it again.
+    assert!(fs::read_to_string(&temp_file).unwrap().contains("dep:"));
+    assert!(!rtmcli(&["list", "--raw", "blocked"]).contains("Build API"));
+}
+
+#[test]
+fn test_cli_list_includes_subprojects() {
+    let dir = TestDir::new("list_includes_subprojects");
+    let temp_file = dir.join("todo.txt");
+    let file_path = temp_file.to_str().unwrap();
+    fs::write(
+        &temp_file,
+        "Migrate db +work.clientA.migration\nTidy desk +workshop\nFix shelf @home:garage",
+    )
+    .unwrap();
+
+    let list = |term: &str| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "rtmcli", "--", "-f", file_path])
+            .args(["list", "--raw", term])
+            .output()
+            .expect("Failed to run list command");
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let work = list("+work");
+    assert!(work.contains("Migrate db") && !work.contains("Tidy desk"));
+    assert!(list("+work.clientA").contains("Migrate db"));
+    assert!(list("@home").contains("Fix shelf"));
+}
+
+#[test]
+fn test_cli_project_rename_and_merge() {
+    let dir = TestDir::new("project_rename_and_merge");
+    let temp_file = dir.join("todo.txt");
+    let done_file = dir.join("done.txt");
+    let file_path = temp_file.to_str().unwrap();
+    fs::write(
+        &temp_file,
+        "Fix oldproj bug +oldproj +oldproj.api\nPlan +other @phone",
+    )
+    .unwrap();
+    fs::write(&done_file, "x 2026-10-01 2026-09-30 Ship +oldproj").unwrap();
+
+    let rtmcli = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "rtmcli", "--", "-f", file_path])
+            .args(args)
+            .env("RTM_CONFIG", dir.join("config.toml"))
+            .output()
+            .expect("Failed to run rtmcli");
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    rtmcli(&["project", "rename", "+oldproj", "newproj", "--done"]);
+    assert_eq!(
+        fs::read_to_string(&temp_file).unwrap(),
+        "Fix oldproj bug +newproj +newproj.api\nPlan +other @phone"
+    );
+    assert_eq!(
+        fs::read_to_string(&done_file).unwrap(),
+        "x 2026-10-01 2026-09-30 Ship +newproj"
+    );
+
+    let out = rtmcli(&[
+        "project",
+        "merge",
+        "other",
+        "newproj.api",
+        "--into",
+        "newproj",
+    ]);
+    assert_eq!(out, format!("Changed 2 item(s) in '{}'\n", file_path));
+    assert_eq!(
+        fs::read_to_string(&temp_file).unwrap(),
+        "Fix oldproj bug +newproj\nPlan +newproj @phone"
+    );
+    rtmcli(&["context", "rename", "@phone", "calls"]);
+    assert!(fs::read_to_string(&temp_file).unwrap().ends_with("@calls"));
+}
+
+#[test]
+fn test_cli_project_rename_leaves_todo_file_when_done_file_fails() {
+    let dir = TestDir::new("project_rename_done_fails");
+    let temp_file = dir.join("todo.txt");
+    let file_path = temp_file.to_str().unwrap();
+    fs::write(&temp_file, "Fix bug +oldproj").unwrap();
+    // A directory where the done file should be, so it can't be read.
+    fs::create_dir(dir.join("done.txt")).unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--bin", "rtmcli", "--", "-f", file_path])
+        .args(["project", "rename", "oldproj", "newproj", "--done"])
+        .env("RTM_CONFIG", dir.join("config.toml"))
+        .output()
+        .expect("Failed to run rtmcli");
+    assert!(!output.status.success());
+    assert_eq!(fs::read_to_string(&temp_file).unwrap(), "Fix bug +oldproj");
+}
+
+#[test]
+fn test_cli_projects_summary_and_metadata() {
+    let dir = TestDir::new("projects_summary_and_metadata");
+    let temp_file = dir.join("todo.txt");
+    let file_path = temp_file.to_str().unwrap();
+    fs::write(
+        &temp_file,
+        "2026-10-01 Plan +work due:2026-10-25\n2026-10-02 Migrate +work.api @office due:2026-10-20\nGarden +home",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("done.txt"),
+        "x 2026-10-15 2026-10-03 Invoice +work.api",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("projects.toml"),
+        "[projects.work]\ndescription = \"Day job\"\n\n[projects.home]\narchived = true\n",
+    )
+    .unwrap();
+
+    let rtmcli = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "rtmcli", "--", "-f", file_path])
+            .args(args)
+            .env("RTM_CONFIG", dir.join("config.toml"))
+            .output()
+            .expect("Failed to run rtmcli");
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let out = rtmcli(&["projects"]);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", out);
+    assert!(lines[1].starts_with("+work "));
+    assert!(lines[1].contains("2026-10-20  2026-10-15"));
+    assert!(lines[1].ends_with("Day job"));
+    assert!(lines[2].starts_with("  +work.api "));
+
+    let out = rtmcli(&["projects", "--all"]);
+    assert!(
+        out.lines()
+            .any(|l| l.starts_with("+home") && l.ends_with("(archived)"))
+    );
+
+    let out = rtmcli(&["contexts", "--json"]);
+    assert!(out.contains("\"name\": \"office\""));
+}
+
+#[test]
+fn test_cli_name_grammar_and_lint() {
+    let dir = TestDir::new("name_grammar_and_lint");
+    let temp_file = dir.join("todo.txt");
+    let file_path = temp_file.to_str().unwrap();
+    fs::write(
+        &temp_file,
+        "Call @mom, about +Garden\nLearn +c++ +garden.beds",
+    )
+    .unwrap();
+
+    let rtmcli = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "rtmcli", "--", "-f", file_path])
+            .args(args)
+            .env("RTM_CONFIG", dir.join("config.toml"))
+            .output()
+            .expect("Failed to run rtmcli");
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let out = rtmcli(&["contexts", "--lint"]);
+    assert_eq!(
+        out,
+        format!("{}: line 1: '@mom,' is read as @mom\n", file_path)
+    );
+    assert_eq!(rtmcli(&["list", "+garden", "--raw"]).lines().count(), 2);
+
+    fs::write(
+        dir.join("config.toml"),
+        "[names]\ncase_insensitive = true\nextra_chars = \"-_.+\"\n",
+    )
+    .unwrap();
+    let out = rtmcli(&["list", "+garden", "--raw"]);
+    assert_eq!(out.lines().count(), 3, "{}", out);
+    assert!(out.contains("+c++"));
+    assert_eq!(
+        rtmcli(&["projects", "--lint"]),
+        "No suspicious project names.\n"
+    );
+}
+
+#[test]
+fn test_cli_inline_tags_keep_their_place() {
+    let dir = TestDir::new("inline_tags_keep_their_place");
+    let temp_file = dir.join("todo.txt");
+    let file_path = temp_file.to_str().unwrap();
+
+    let rtmcli = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "rtmcli", "--", "-f", file_path])
+            .args(args)
+            .env("RTM_CONFIG", dir.join("config.toml"))
+            .output()
+            .expect("Failed to run rtmcli");
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    rtmcli(&["add", "Call @mom about +garden plans due:2026-10-20"]);
+    assert_eq!(
+        fs::read_to_string(&temp_file).unwrap(),
+        format!("{} Call @mom about +garden plans due:2026-10-20", today())
+    );
+    rtmcli(&["project", "rename", "garden", "yard"]);
+    rtmcli(&[
+        "modify", "1", "--remove", "@mom", "--add", "+later", "--yes",
+    ]);
+    assert_eq!(
+        fs::read_to_string(&temp_file).unwrap(),
+        format!("{} Call about +yard plans +later due:2026-10-20", today())
+    );
+}
+
+#[test]
+fn test_cli_doctor() {
+    let dir = TestDir::new("doctor");
+    let temp_file = dir.join("todo.txt");
+    let done_file = dir.join("done.txt");
+    let file_path = temp_file.to_str().unwrap();
+    fs::write(
+        &temp_file,
+        "(A) Plan pri:A  \n\nChild sub:00000000-0000-0000-0000-00000000000a color:red\n(A) Clash pri:B",
+    )
+    .unwrap();
+    fs::write(
+        &done_file,
+        "x 2026-10-01 2026-10-05 Parent uuid:00000000-0000-0000-0000-00000000000a",
+    )
+    .unwrap();
+
+    // Exits with status 1 while problems remain.
+    let rtmcli = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--bin", "rtmcli", "--", "-f", file_path])
+            .args(args)
+            .env("RTM_CONFIG", dir.join("config.toml"))
+            .output()
+            .expect("Failed to run rtmcli");
+        (
+            output.status.success(),
+            String::from_utf8(output.stdout).unwrap(),
+        )
+    };
+
+    let done_path = done_file.to_str().unwrap();
+    let (success, out) = rtmcli(&["doctor"]);
+    assert!(!success);
+    assert_eq!(
+        out,
+        format!(
+            "{f}: line 1: trailing whitespace\n\
+             {f}: line 1: both (A) and pri:A\n\
+             {f}: line 2: blank line\n\
+             {f}: line 3: unknown tag 'color:red'\n\
+             {f}: line 4: conflicting priorities (A) and pri:B\n\
+             {d}: line 1: completed on 2026-10-01 before it was created on 2026-10-05\n\
+             6 problem(s), 3 fixable with --fix\n",
+            f = file_path,
+            d = done_path
+        )
+    );
+
+    let (success, out) = rtmcli(&["doctor", "--fix"]);
+    assert!(!success);
+    assert!(out.starts_with(&format!("Fixed 3 problem(s) in '{}'\n", file_path)));
+    assert!(out.ends_with("3 problem(s)\n"), "{}", out);
+    assert_eq!(
+        fs::read_to_string(&temp_file).unwrap(),
+        "(A) Plan\nChild sub:00000000-0000-0000-0000-00000000000a color:red\n(A) Clash pri:B"
+    );
+
+    fs::write(&temp_file, "(A) Plan").unwrap();
+    fs::write(&done_file, "x 2026-10-05 2026-10-01 Parent").unwrap();
+    assert_eq!(
+        rtmcli(&["doctor"]),
+        (true, "No problems found.\n".to_string())
+    );
+}
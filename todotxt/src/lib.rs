@@ -1,12 +1,27 @@
 pub mod todo_context;
+pub mod todo_deps;
+pub mod todo_hierarchy;
+pub mod todo_ical;
 pub mod todo_item;
 pub mod todo_library;
+pub mod todo_lint;
+pub mod todo_names;
+pub mod todo_patch;
 pub mod todo_priority;
 pub mod todo_project;
 pub mod todo_recurrence;
+pub mod todo_reminder;
+pub mod todo_report;
+pub mod todo_stats;
+pub mod todo_tags;
+pub mod todo_taskwarrior;
+pub mod todo_timer;
+pub mod todo_workspace;
 
 pub use todo_context::TodoContext;
 pub use todo_item::TodoItem;
-pub use todo_library::TodoLibrary;
+pub use todo_library::{TodoImportSummary, TodoLibrary, TodoRenameError};
+pub use todo_patch::{FieldPatch, TodoPatch, TodoPatchError};
 pub use todo_priority::TodoPriority;
 pub use todo_project::TodoProject;
+pub use todo_workspace::{TodoList, TodoWorkspace};
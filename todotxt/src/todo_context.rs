@@ -1,3 +1,5 @@
+use crate::todo_hierarchy;
+use crate::todo_names::NameGrammar;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
@@ -7,20 +9,38 @@ pub struct TodoContext {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
-#[error("context must start with '@' and be non-empty")]
+#[error("context must start with '@' followed by a valid name")]
 pub struct TodoContextParseError;
 
 impl FromStr for TodoContext {
     type Err = TodoContextParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with('@') && s.len() > 1 {
-            Ok(TodoContext {
-                name: s[1..].to_string(),
+        Self::parse_with(s, &NameGrammar::default())
+    }
+}
+
+impl TodoContext {
+    /// Parses `@name` with the names `grammar` allows, dropping trailing punctuation; see
+    /// [`NameGrammar`].
+    pub fn parse_with(s: &str, grammar: &NameGrammar) -> Result<Self, TodoContextParseError> {
+        s.strip_prefix('@')
+            .and_then(|name| grammar.parse(name))
+            .map(|name| TodoContext {
+                name: name.to_string(),
             })
-        } else {
-            Err(TodoContextParseError)
-        }
+            .ok_or(TodoContextParseError)
+    }
+
+    /// Whether this is `ancestor` or within it, as `home:garage` is within `home`; see
+    /// [`todo_hierarchy`].
+    pub fn is_within(&self, ancestor: &str) -> bool {
+        todo_hierarchy::is_within(&self.name, ancestor)
+    }
+
+    /// This with `from` renamed to `to`, if it is `from` or within it.
+    pub fn renamed(&self, from: &str, to: &str) -> Option<Self> {
+        todo_hierarchy::renamed(&self.name, from, to).map(|name| TodoContext { name })
     }
 }
 
@@ -102,4 +122,26 @@ mod tests {
         let c2 = c1.clone();
         assert_eq!(c1, c2);
     }
+
+    #[test]
+    fn hierarchy() {
+        let child = TodoContext::from_str("@home:garage").unwrap();
+        assert!(child.is_within("home"));
+        assert!(!child.is_within("homes"));
+        assert_eq!(child.renamed("home", "new").unwrap().name, "new:garage");
+    }
+
+    #[test]
+    fn trailing_punctuation_and_invalid_names() {
+        assert_eq!(TodoContext::from_str("@garden,").unwrap().name, "garden");
+        assert!(TodoContext::from_str("@c++").is_err());
+        let grammar = NameGrammar {
+            extra_chars: "+".to_string(),
+            ..NameGrammar::default()
+        };
+        assert_eq!(
+            TodoContext::parse_with("@c++", &grammar).unwrap().name,
+            "c++"
+        );
+    }
 }
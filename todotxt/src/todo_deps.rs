@@ -0,0 +1,164 @@
+//! Dependencies between items: `dep:<uuid>` says an item can't start until the item with
+//! that uuid is done. An item is blocked while any item it depends on is open; a `dep:`
+//! naming no known item, say one archived to done.txt, doesn't block.
+
+use crate::todo_item::TodoItem;
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum DependencyError {
+    #[error("no item at index {0}")]
+    NoItem(usize),
+    #[error("an item can't depend on itself")]
+    SelfDependency,
+    #[error("that would make a dependency cycle")]
+    Cycle,
+}
+
+/// The uuids of the open items, which are what blocks other items.
+pub fn open_uuids(items: &[TodoItem]) -> HashSet<Uuid> {
+    items
+        .iter()
+        .filter(|item| !item.done)
+        .filter_map(|item| item.uuid)
+        .collect()
+}
+
+/// Whether `item` is open and waits for one of the `open` items (see [`open_uuids`]).
+pub fn is_blocked(item: &TodoItem, open: &HashSet<Uuid>) -> bool {
+    !item.done && item.deps.iter().any(|dep| open.contains(dep))
+}
+
+/// The indices of the blocked items.
+pub fn blocked(items: &[TodoItem]) -> Vec<usize> {
+    let open = open_uuids(items);
+    (0..items.len())
+        .filter(|&i| is_blocked(&items[i], &open))
+        .collect()
+}
+
+/// The open items that `item` waits for.
+pub fn blockers<'a>(item: &TodoItem, items: &'a [TodoItem]) -> Vec<&'a TodoItem> {
+    items
+        .iter()
+        .filter(|other| !other.done && other.uuid.is_some_and(|u| item.deps.contains(&u)))
+        .collect()
+}
+
+fn graph(items: &[TodoItem]) -> BTreeMap<Uuid, Vec<Uuid>> {
+    let mut graph: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+    for item in items {
+        if let Some(uuid) = item.uuid {
+            graph.entry(uuid).or_default().extend(&item.deps);
+        }
+    }
+    graph
+}
+
+/// Whether `item` depends on the item with uuid `on`, directly or through other items.
+pub fn depends_on(items: &[TodoItem], item: &TodoItem, on: Uuid) -> bool {
+    let graph = graph(items);
+    let mut seen: HashSet<Uuid> = item.deps.iter().copied().collect();
+    let mut stack = item.deps.clone();
+    while let Some(uuid) = stack.pop() {
+        if uuid == on {
+            return true;
+        }
+        for &dep in graph.get(&uuid).into_iter().flatten() {
+            if seen.insert(dep) {
+                stack.push(dep);
+            }
+        }
+    }
+    false
+}
+
+/// The dependency cycles among the items, each as the uuids along it. Items in a cycle
+/// would block each other forever, so these are mistakes to point out.
+pub fn cycles(items: &[TodoItem]) -> Vec<Vec<Uuid>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        OnPath,
+        Done,
+    }
+
+    fn visit(
+        uuid: Uuid,
+        graph: &BTreeMap<Uuid, Vec<Uuid>>,
+        state: &mut BTreeMap<Uuid, State>,
+        path: &mut Vec<Uuid>,
+        cycles: &mut Vec<Vec<Uuid>>,
+    ) {
+        state.insert(uuid, State::OnPath);
+        path.push(uuid);
+        for &dep in graph.get(&uuid).into_iter().flatten() {
+            match state.get(&dep) {
+                Some(State::OnPath) => {
+                    let start = path.iter().position(|&u| u == dep).unwrap();
+                    cycles.push(path[start..].to_vec());
+                }
+                Some(State::Done) => {}
+                None => visit(dep, graph, state, path, cycles),
+            }
+        }
+        path.pop();
+        state.insert(uuid, State::Done);
+    }
+
+    let graph = graph(items);
+    let mut state = BTreeMap::new();
+    let mut cycles = Vec::new();
+    for &uuid in graph.keys() {
+        if !state.contains_key(&uuid) {
+            visit(uuid, &graph, &mut state, &mut Vec::new(), &mut cycles);
+        }
+    }
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "00000000-0000-0000-0000-00000000000a";
+    const B: &str = "00000000-0000-0000-0000-00000000000b";
+    const C: &str = "00000000-0000-0000-0000-00000000000c";
+
+    fn items(lines: &[&str]) -> Vec<TodoItem> {
+        lines.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_blocked_by_open_items_only() {
+        let items = items(&[
+            &format!("Design uuid:{}", A),
+            &format!("x 2026-10-18 Specs uuid:{}", B),
+            &format!("Build dep:{}", A),
+            &format!("Test dep:{}", B),
+            "Deploy dep:00000000-0000-0000-0000-0000000000ff",
+        ]);
+        assert_eq!(blocked(&items), vec![2]);
+        assert_eq!(blockers(&items[2], &items)[0].description, "Design");
+        assert!(blockers(&items[3], &items).is_empty());
+    }
+
+    #[test]
+    fn test_cycles() {
+        let items = items(&[
+            &format!("One uuid:{} dep:{}", A, B),
+            &format!("Two uuid:{} dep:{}", B, C),
+            &format!("Three uuid:{}", C),
+        ]);
+        let (a, c) = (Uuid::parse_str(A).unwrap(), Uuid::parse_str(C).unwrap());
+        assert!(cycles(&items).is_empty());
+        assert!(depends_on(&items, &items[0], c));
+        assert!(!depends_on(&items, &items[2], a));
+
+        let mut looped = items.clone();
+        looped[2].deps.push(a);
+        let found = cycles(&looped);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].len(), 3);
+    }
+}
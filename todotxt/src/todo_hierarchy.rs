@@ -0,0 +1,82 @@
+//! Hierarchical project and context names. A `.` or `:` in a name separates levels, so
+//! `+work.clientA.migration` is within `+work.clientA`, which is within `+work`, and
+//! `@home:garage` is within `@home`. Parents don't need items of their own to exist.
+
+/// The characters that separate the levels of a name.
+pub const SEPARATORS: [char; 2] = ['.', ':'];
+
+/// Whether `name` is `ancestor` or one of its descendants. `work.clientA` is within `work`,
+/// `workshop` is not.
+pub fn is_within(name: &str, ancestor: &str) -> bool {
+    match name.strip_prefix(ancestor) {
+        Some(rest) => rest.is_empty() || rest.starts_with(SEPARATORS),
+        None => false,
+    }
+}
+
+/// The names from the top level down to `name` itself: `work`, `work.clientA`,
+/// `work.clientA.migration`.
+pub fn ancestors(name: &str) -> impl Iterator<Item = &str> {
+    name.match_indices(SEPARATORS)
+        .map(|(i, _)| &name[..i])
+        .filter(|prefix| !prefix.is_empty())
+        .chain(std::iter::once(name))
+}
+
+/// How deep `name` is: 0 for a top-level name, 1 for its children and so on.
+pub fn depth(name: &str) -> usize {
+    ancestors(name).count() - 1
+}
+
+/// `name` with `from` replaced by `to` if it is within `from`, so renaming a parent renames
+/// its children: `work.clientA` becomes `job.clientA` when `work` is renamed to `job`.
+pub fn renamed(name: &str, from: &str, to: &str) -> Option<String> {
+    is_within(name, from).then(|| format!("{}{}", to, &name[from.len()..]))
+}
+
+/// A sort key that keeps children right after their parent, which plain string order
+/// doesn't: `work-x` sorts between `work` and `work.a`.
+pub fn sort_key(name: &str) -> Vec<&str> {
+    name.split(SEPARATORS).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_within() {
+        assert!(is_within("work.clientA.migration", "work"));
+        assert!(is_within("work.clientA", "work.clientA"));
+        assert!(is_within("home:garage", "home"));
+        assert!(!is_within("workshop", "work"));
+        assert!(!is_within("work", "work.clientA"));
+    }
+
+    #[test]
+    fn test_ancestors_and_depth() {
+        assert_eq!(
+            ancestors("work.clientA:migration").collect::<Vec<_>>(),
+            vec!["work", "work.clientA", "work.clientA:migration"]
+        );
+        assert_eq!(depth("home"), 0);
+        assert_eq!(depth("home:garage"), 1);
+    }
+
+    #[test]
+    fn test_renamed() {
+        assert_eq!(
+            renamed("work.clientA", "work", "job").as_deref(),
+            Some("job.clientA")
+        );
+        assert_eq!(renamed("work", "work", "job").as_deref(), Some("job"));
+        assert_eq!(renamed("workshop", "work", "job"), None);
+    }
+
+    #[test]
+    fn test_sort_key_keeps_children_with_parent() {
+        let mut names = vec!["work.a", "work-x", "work"];
+        names.sort_by_key(|n| sort_key(n));
+        assert_eq!(names, vec!["work", "work.a", "work-x"]);
+    }
+}
@@ -0,0 +1,735 @@
+use crate::todo_context::TodoContext;
+use crate::todo_item::TodoItem;
+use crate::todo_priority::TodoPriority;
+use crate::todo_project::TodoProject;
+use crate::todo_recurrence::{TodoRecurrence, TodoRecurrenceUnit};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use uuid::Uuid;
+
+/// Which iCalendar component each exported item becomes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum IcalComponent {
+    /// A VTODO, carrying DUE, DTSTART, STATUS and PRIORITY. Calendar apps that show tasks
+    /// display these in their task list.
+    #[default]
+    Todo,
+    /// An all-day VEVENT on the due date (or the threshold date, for items with no due
+    /// date), for calendars that don't display tasks.
+    Event,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IcalExportOptions {
+    pub component: IcalComponent,
+    /// Also export completed items. As VTODOs they carry STATUS:COMPLETED.
+    pub include_done: bool,
+}
+
+/// Renders every item with a `due:` or `t:` date as an iCalendar (RFC 5545) document.
+///
+/// The item's `uuid:` is used as the UID so a subscribed calendar updates an entry in place
+/// when the item changes; see [`TodoItem::stable_uuid`] for items without one.
+pub fn export_ical(items: &[TodoItem], options: &IcalExportOptions) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//brcha//rtm//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for item in items {
+        if item.due.is_none() && item.threshold.is_none() {
+            continue;
+        }
+        if item.done && !options.include_done {
+            continue;
+        }
+        match options.component {
+            IcalComponent::Todo => push_vtodo(&mut lines, item, &stamp),
+            IcalComponent::Event => push_vevent(&mut lines, item, &stamp),
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<String>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+fn push_vtodo(lines: &mut Vec<String>, item: &TodoItem, stamp: &str) {
+    lines.push("BEGIN:VTODO".to_string());
+    push_common(lines, item, stamp);
+    // DTSTART and DUE must be of the same value type, so with a due date the start has a
+    // time exactly when the due date does.
+    let timed = match item.due {
+        Some(_) => item.due_time.is_some(),
+        None => item.threshold_time.is_some(),
+    };
+    if let Some(t) = item.threshold {
+        if timed {
+            let time = item.threshold_time.unwrap_or(NaiveTime::MIN);
+            lines.push(format!("DTSTART:{}", ical_date_time(t, time)));
+        } else {
+            lines.push(format!("DTSTART;VALUE=DATE:{}", ical_date(t)));
+        }
+    }
+    match (item.due, item.due_time) {
+        (Some(d), Some(time)) => lines.push(format!("DUE:{}", ical_date_time(d, time))),
+        (Some(d), None) => lines.push(format!("DUE;VALUE=DATE:{}", ical_date(d))),
+        (None, _) => {}
+    }
+    if let Some(p) = item.priority.priority {
+        lines.push(format!("PRIORITY:{}", ical_priority(p)));
+    }
+    if item.done {
+        lines.push("STATUS:COMPLETED".to_string());
+        if let Some(c) = item.completion_date {
+            lines.push(format!("COMPLETED:{}T000000Z", ical_date(c)));
+        }
+    } else {
+        lines.push("STATUS:NEEDS-ACTION".to_string());
+    }
+    push_rrule(lines, item);
+    lines.push("END:VTODO".to_string());
+}
+
+fn push_vevent(lines: &mut Vec<String>, item: &TodoItem, stamp: &str) {
+    // Only called for items with at least one of the two dates.
+    let Some(day) = item.due.or(item.threshold) else {
+        return;
+    };
+    lines.push("BEGIN:VEVENT".to_string());
+    push_common(lines, item, stamp);
+    lines.push(format!("DTSTART;VALUE=DATE:{}", ical_date(day)));
+    lines.push(format!(
+        "DTEND;VALUE=DATE:{}",
+        ical_date(day + Duration::days(1))
+    ));
+    lines.push("TRANSP:TRANSPARENT".to_string());
+    push_rrule(lines, item);
+    lines.push("END:VEVENT".to_string());
+}
+
+fn push_common(lines: &mut Vec<String>, item: &TodoItem, stamp: &str) {
+    lines.push(format!("UID:{}", item.stable_uuid()));
+    lines.push(format!("DTSTAMP:{}", stamp));
+    if let Some(c) = item.creation_date {
+        lines.push(format!("CREATED:{}T000000Z", ical_date(c)));
+    }
+    // The description as typed, tags and all, so they come back where they were; CATEGORIES
+    // lists them too, for calendars that group by category.
+    lines.push(format!("SUMMARY:{}", escape_text(&item.description)));
+    let categories: Vec<String> = item
+        .projects
+        .iter()
+        .map(|p| p.to_string())
+        .chain(item.contexts.iter().map(|c| c.to_string()))
+        .map(|c| escape_text(&c))
+        .collect();
+    if !categories.is_empty() {
+        lines.push(format!("CATEGORIES:{}", categories.join(",")));
+    }
+    if let Some(parent) = item.sub {
+        lines.push(format!("RELATED-TO;RELTYPE=PARENT:{}", parent));
+    }
+}
+
+fn push_rrule(lines: &mut Vec<String>, item: &TodoItem) {
+    if let Some(rule) = item.recurrence.as_ref().and_then(recurrence_to_rrule) {
+        lines.push(format!("RRULE:{}", rule));
+    }
+}
+
+/// Maps a `rec:` value to an RRULE value. Returns `None` for recurrences iCalendar can't
+/// express, which is only "every N business days" for N > 1. Non-strict recurrences (which
+/// todo.txt counts from the completion date, not the due date) are exported as if strict,
+/// since RRULE has no notion of completion.
+pub fn recurrence_to_rrule(recurrence: &TodoRecurrence) -> Option<String> {
+    let freq = match recurrence.unit {
+        TodoRecurrenceUnit::Daily => "DAILY",
+        TodoRecurrenceUnit::BusinessDay if recurrence.count == 1 => {
+            return Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR".to_string());
+        }
+        TodoRecurrenceUnit::BusinessDay => return None,
+        TodoRecurrenceUnit::Weekly => "WEEKLY",
+        TodoRecurrenceUnit::Monthly => "MONTHLY",
+        TodoRecurrenceUnit::Yearly => "YEARLY",
+    };
+    if recurrence.count > 1 {
+        Some(format!("FREQ={};INTERVAL={}", freq, recurrence.count))
+    } else {
+        Some(format!("FREQ={}", freq))
+    }
+}
+
+/// todo.txt priorities A through I map onto iCalendar's 1 (highest) to 9 (lowest); anything
+/// below I is clamped to 9.
+fn ical_priority(p: u8) -> u8 {
+    (p + 1).min(9)
+}
+
+fn ical_date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+fn ical_date_time(date: NaiveDate, time: NaiveTime) -> String {
+    format!("{}T{}", ical_date(date), time.format("%H%M%S"))
+}
+
+fn escape_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line to at most 75 octets per physical line, as RFC 5545 requires,
+/// without splitting a UTF-8 sequence.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            // The leading space of the continuation line counts towards its length.
+            width = 1;
+        }
+        folded.push(ch);
+        width += ch.len_utf8();
+    }
+    folded
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum IcalParseError {
+    #[error("VTODO is missing its END:VTODO line")]
+    Unterminated,
+    #[error("invalid date value '{0}'")]
+    Date(String),
+}
+
+/// Parses every VTODO in an iCalendar document into a TodoItem. Other components (VEVENT,
+/// VALARM inside a VTODO, ...) are ignored.
+///
+/// A UID that is itself a UUID becomes the item's `uuid:`; any other UID is mapped to a
+/// name-based UUID of it, so importing the same file twice still recognises its items.
+pub fn parse_vtodos(ics: &str) -> Result<Vec<TodoItem>, IcalParseError> {
+    let mut items = Vec::new();
+    let mut current: Option<TodoItem> = None;
+    // Depth of components nested inside the current VTODO, whose properties (e.g. a
+    // VALARM's DESCRIPTION) must not be mistaken for the VTODO's own.
+    let mut nested = 0;
+    // When the current VTODO was last modified, standing in for a missing COMPLETED.
+    let mut stamp = None;
+
+    for line in unfold_lines(ics) {
+        let Some((name, params, value)) = split_content_line(&line) else {
+            continue;
+        };
+        match (name.as_str(), value) {
+            ("BEGIN", "VTODO") if current.is_none() => {
+                stamp = None;
+                current = Some(TodoItem {
+                    done: false,
+                    priority: TodoPriority { priority: None },
+                    completion_date: None,
+                    creation_date: None,
+                    description: String::new(),
+                    projects: vec![],
+                    contexts: vec![],
+                    due: None,
+                    due_time: None,
+                    recurrence: None,
+                    threshold: None,
+                    threshold_time: None,
+                    uuid: None,
+                    sub: None,
+                    deps: Vec::new(),
+                });
+            }
+            ("END", "VTODO") if nested == 0 => {
+                if let Some(mut item) = current.take() {
+                    if item.done && item.completion_date.is_none() {
+                        item.completion_date = stamp;
+                    }
+                    items.push(item);
+                }
+            }
+            ("BEGIN", _) if current.is_some() => nested += 1,
+            ("END", _) if current.is_some() => nested -= 1,
+            _ => {
+                if nested == 0
+                    && let Some(ref mut item) = current
+                {
+                    if name == "DTSTAMP" {
+                        stamp = Some(parse_ical_date(value)?);
+                    }
+                    apply_property(item, &name, &params, value)?;
+                }
+            }
+        }
+    }
+
+    if current.is_some() {
+        return Err(IcalParseError::Unterminated);
+    }
+    Ok(items)
+}
+
+fn apply_property(
+    item: &mut TodoItem,
+    name: &str,
+    params: &str,
+    value: &str,
+) -> Result<(), IcalParseError> {
+    match name {
+        "UID" => {
+            item.uuid = Some(
+                Uuid::parse_str(value)
+                    .unwrap_or_else(|_| Uuid::new_v5(&Uuid::NAMESPACE_OID, value.as_bytes())),
+            );
+        }
+        "SUMMARY" => {
+            item.description = unescape_text(value)
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            // Tags typed in the summary are read in place, as from a todo.txt line.
+            let words: Vec<String> = item
+                .description
+                .split_whitespace()
+                .map(str::to_string)
+                .collect();
+            for word in words {
+                if let Ok(project) = word.parse::<TodoProject>() {
+                    add_project(item, project);
+                } else if let Ok(context) = word.parse::<TodoContext>() {
+                    add_context(item, context);
+                }
+            }
+        }
+        "PRIORITY" => {
+            // 0 means "undefined"; 1 (highest) to 9 (lowest) become A to I.
+            if let Ok(p @ 1..=9) = value.parse::<u8>() {
+                item.priority = TodoPriority {
+                    priority: Some(p - 1),
+                };
+            }
+        }
+        "DUE" => (item.due, item.due_time) = parse_ical_date_time(value)?,
+        "DTSTART" => (item.threshold, item.threshold_time) = parse_ical_date_time(value)?,
+        "CREATED" => item.creation_date = Some(parse_ical_date(value)?),
+        "COMPLETED" => {
+            item.done = true;
+            item.completion_date = Some(parse_ical_date(value)?);
+        }
+        "STATUS" if value.eq_ignore_ascii_case("COMPLETED") => item.done = true,
+        "CATEGORIES" => {
+            for category in split_list(value) {
+                let name: String = category.split_whitespace().collect::<Vec<_>>().join("_");
+                if let Some(project) = name.strip_prefix('+') {
+                    if !project.is_empty() {
+                        add_project(
+                            item,
+                            TodoProject {
+                                name: project.to_string(),
+                            },
+                        );
+                    }
+                } else {
+                    let context = name.strip_prefix('@').unwrap_or(&name);
+                    if !context.is_empty() {
+                        add_context(
+                            item,
+                            TodoContext {
+                                name: context.to_string(),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+        "RELATED-TO" => {
+            let reltype = params
+                .split(';')
+                .find_map(|p| p.strip_prefix("RELTYPE="))
+                .unwrap_or("PARENT");
+            if reltype.eq_ignore_ascii_case("PARENT")
+                && let Ok(parent) = Uuid::parse_str(value)
+            {
+                item.sub = Some(parent);
+            }
+        }
+        "RRULE" => item.recurrence = rrule_to_recurrence(value),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Adds `project` unless the summary or CATEGORIES already gave it.
+fn add_project(item: &mut TodoItem, project: TodoProject) {
+    if !item.projects.contains(&project) {
+        item.projects.push(project);
+    }
+}
+
+fn add_context(item: &mut TodoItem, context: TodoContext) {
+    if !item.contexts.contains(&context) {
+        item.contexts.push(context);
+    }
+}
+
+/// The inverse of [`recurrence_to_rrule`]. Returns `None` for rules `rec:` can't express,
+/// e.g. ones with a COUNT or UNTIL limit or a BYxxx part other than the weekday form
+/// exported for business-day recurrence. Imported recurrences are strict, since an RRULE is
+/// anchored to its start date rather than to when the task was completed.
+pub fn rrule_to_recurrence(rule: &str) -> Option<TodoRecurrence> {
+    let mut freq = None;
+    let mut interval: u16 = 1;
+    let mut weekdays = false;
+    for part in rule.split(';') {
+        let (key, value) = part.split_once('=')?;
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => freq = Some(value.to_ascii_uppercase()),
+            "INTERVAL" => interval = value.parse().ok()?,
+            "BYDAY" if value.eq_ignore_ascii_case("MO,TU,WE,TH,FR") => weekdays = true,
+            "WKST" => {}
+            _ => return None,
+        }
+    }
+    let unit = match (freq?.as_str(), weekdays) {
+        ("DAILY", false) => TodoRecurrenceUnit::Daily,
+        ("DAILY", true) | ("WEEKLY", true) if interval == 1 => TodoRecurrenceUnit::BusinessDay,
+        ("WEEKLY", false) => TodoRecurrenceUnit::Weekly,
+        ("MONTHLY", false) => TodoRecurrenceUnit::Monthly,
+        ("YEARLY", false) => TodoRecurrenceUnit::Yearly,
+        _ => return None,
+    };
+    Some(TodoRecurrence {
+        strict: true,
+        count: interval,
+        unit,
+    })
+}
+
+/// Joins folded continuation lines (those starting with a space or tab) back onto the line
+/// they continue, accepting both CRLF and bare LF line endings.
+fn unfold_lines(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(continuation) = raw.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(continuation);
+        } else if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+/// Splits `NAME;PARAM=x;PARAM=y:value` into an upper-cased name, the raw parameter string
+/// and the value.
+fn split_content_line(line: &str) -> Option<(String, String, &str)> {
+    let colon = line.find(':')?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((
+        name.to_ascii_uppercase(),
+        params.to_ascii_uppercase(),
+        value,
+    ))
+}
+
+/// Accepts a DATE (`20261020`) or DATE-TIME (`20261020T140000`, optionally with a trailing
+/// `Z`) value and keeps the date part.
+fn parse_ical_date(value: &str) -> Result<NaiveDate, IcalParseError> {
+    let date = value.get(..8).unwrap_or(value);
+    NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| IcalParseError::Date(value.to_string()))
+}
+
+/// A DATE, or a DATE-TIME with its time of day. A UTC time (`...Z`) is converted to local
+/// time; a floating one, or one with a TZID, is taken as it is written.
+fn parse_ical_date_time(
+    value: &str,
+) -> Result<(Option<NaiveDate>, Option<NaiveTime>), IcalParseError> {
+    let Some((_, time)) = value.split_once('T') else {
+        return Ok((Some(parse_ical_date(value)?), None));
+    };
+    let invalid = || IcalParseError::Date(value.to_string());
+    let at = NaiveDateTime::parse_from_str(&value[..15.min(value.len())], "%Y%m%dT%H%M%S")
+        .map_err(|_| invalid())?;
+    let at = if time.ends_with('Z') {
+        Utc.from_utc_datetime(&at)
+            .with_timezone(&Local)
+            .naive_local()
+    } else {
+        at
+    };
+    Ok((Some(at.date()), Some(at.time())))
+}
+
+/// Splits a comma-separated TEXT list, honouring `\,` escapes within an entry.
+fn split_list(value: &str) -> Vec<String> {
+    let mut entries = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    entries
+                        .last_mut()
+                        .unwrap()
+                        .push_str(&unescape_text(&format!("\\{}", next)));
+                }
+            }
+            ',' => entries.push(String::new()),
+            _ => entries.last_mut().unwrap().push(ch),
+        }
+    }
+    entries
+}
+
+fn unescape_text(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            match chars.next() {
+                Some('n') | Some('N') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines_of(ics: &str) -> Vec<&str> {
+        ics.split("\r\n").collect()
+    }
+
+    #[test]
+    fn test_export_skips_undated_and_done_items() {
+        let items: Vec<TodoItem> = vec![
+            "No dates".parse().unwrap(),
+            "x 2026-10-01 2026-09-01 Done due:2026-10-01"
+                .parse()
+                .unwrap(),
+            "Dated due:2026-10-20".parse().unwrap(),
+        ];
+        let ics = export_ical(&items, &IcalExportOptions::default());
+        assert_eq!(ics.matches("BEGIN:VTODO").count(), 1);
+        assert!(ics.contains("SUMMARY:Dated\r\n"));
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_export_vtodo_fields() {
+        let item: TodoItem = "(B) 2026-10-01 Pay rent, quickly +home @desk due:2026-10-20 t:2026-10-15 rec:+m uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"
+            .parse()
+            .unwrap();
+        let ics = export_ical(&[item], &IcalExportOptions::default());
+        let lines = lines_of(&ics);
+        assert!(lines.contains(&"UID:67e55044-10b1-426f-9247-bb680e5fe0c8"));
+        assert!(lines.contains(&"SUMMARY:Pay rent\\, quickly +home @desk"));
+        assert!(lines.contains(&"CATEGORIES:+home,@desk"));
+        assert!(lines.contains(&"DUE;VALUE=DATE:20261020"));
+        assert!(lines.contains(&"DTSTART;VALUE=DATE:20261015"));
+        assert!(lines.contains(&"PRIORITY:2"));
+        assert!(lines.contains(&"RRULE:FREQ=MONTHLY"));
+        assert!(lines.contains(&"STATUS:NEEDS-ACTION"));
+        assert!(lines.contains(&"CREATED:20261001T000000Z"));
+    }
+
+    #[test]
+    fn test_export_completed_vtodo() {
+        let item: TodoItem = "x 2026-10-02 2026-10-01 Filed taxes due:2026-10-03"
+            .parse()
+            .unwrap();
+        let options = IcalExportOptions {
+            include_done: true,
+            ..Default::default()
+        };
+        let ics = export_ical(&[item], &options);
+        let lines = lines_of(&ics);
+        assert!(lines.contains(&"STATUS:COMPLETED"));
+        assert!(lines.contains(&"COMPLETED:20261002T000000Z"));
+    }
+
+    #[test]
+    fn test_export_vevent_uses_threshold_when_no_due() {
+        let item: TodoItem = "Start reading t:2026-10-15".parse().unwrap();
+        let options = IcalExportOptions {
+            component: IcalComponent::Event,
+            ..Default::default()
+        };
+        let ics = export_ical(&[item], &options);
+        let lines = lines_of(&ics);
+        assert!(lines.contains(&"BEGIN:VEVENT"));
+        assert!(lines.contains(&"DTSTART;VALUE=DATE:20261015"));
+        assert!(lines.contains(&"DTEND;VALUE=DATE:20261016"));
+        assert!(!ics.contains("VTODO"));
+    }
+
+    #[test]
+    fn test_uid_is_stable_without_uuid() {
+        let a: TodoItem = "2026-10-01 Task due:2026-10-20".parse().unwrap();
+        let b: TodoItem = "2026-10-01 Task due:2026-11-20".parse().unwrap();
+        let c: TodoItem = "2026-10-01 Other task due:2026-10-20".parse().unwrap();
+        assert_eq!(a.stable_uuid(), b.stable_uuid());
+        assert_ne!(a.stable_uuid(), c.stable_uuid());
+    }
+
+    #[test]
+    fn test_recurrence_to_rrule() {
+        let rule = |s: &str| recurrence_to_rrule(&s.parse().unwrap());
+        assert_eq!(rule("d"), Some("FREQ=DAILY".to_string()));
+        assert_eq!(rule("+2w"), Some("FREQ=WEEKLY;INTERVAL=2".to_string()));
+        assert_eq!(rule("3y"), Some("FREQ=YEARLY;INTERVAL=3".to_string()));
+        assert_eq!(
+            rule("b"),
+            Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR".to_string())
+        );
+        assert_eq!(rule("2b"), None);
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let long = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = fold_line(&long);
+        for physical in folded.split("\r\n") {
+            assert!(physical.len() <= 75);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), long);
+    }
+
+    #[test]
+    fn test_parse_vtodo_fields() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nBEGIN:VTODO\r\nUID:67e55044-10b1-426f-9247-bb680e5fe0c8\r\nSUMMARY:Pay rent\\, quickly\r\nPRIORITY:2\r\nDUE;VALUE=DATE:20261020\r\nDTSTART;TZID=Europe/Belgrade:20261015T090000\r\nCREATED:20261001T101010Z\r\nCATEGORIES:+home,@desk,Errand\r\nRELATED-TO:0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d\r\nRRULE:FREQ=MONTHLY;INTERVAL=2\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        let items = parse_vtodos(ics).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            items[0].to_string(),
+            "(B) 2026-10-01 Pay rent, quickly +home @desk @Errand due:2026-10-20 rec:+2m t:2026-10-15T09:00 uuid:67e55044-10b1-426f-9247-bb680e5fe0c8 sub:0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d"
+        );
+    }
+
+    #[test]
+    fn test_parse_completed_vtodo_and_ignores_valarm() {
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VTODO\nUID:task-1@example.com\nSUMMARY:Filed\n  taxes\nSTATUS:COMPLETED\nCOMPLETED:20261002T120000Z\nBEGIN:VALARM\nSUMMARY:Reminder\nEND:VALARM\nEND:VTODO\nBEGIN:VEVENT\nSUMMARY:Not a task\nEND:VEVENT\nEND:VCALENDAR\n";
+        let items = parse_vtodos(ics).unwrap();
+        assert_eq!(items.len(), 1);
+        assert!(items[0].done);
+        assert_eq!(items[0].description, "Filed taxes");
+        assert_eq!(
+            items[0].completion_date,
+            NaiveDate::from_ymd_opt(2026, 10, 2)
+        );
+        assert_eq!(
+            items[0].uuid,
+            Some(Uuid::new_v5(&Uuid::NAMESPACE_OID, b"task-1@example.com"))
+        );
+
+        // Without COMPLETED, the item was done when it was last modified.
+        let ics =
+            "BEGIN:VTODO\nSUMMARY:Old\nSTATUS:COMPLETED\nDTSTAMP:20261005T080000Z\nEND:VTODO\n";
+        let items = parse_vtodos(ics).unwrap();
+        assert_eq!(
+            items[0].completion_date,
+            NaiveDate::from_ymd_opt(2026, 10, 5)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_vtodos("BEGIN:VTODO\nSUMMARY:x\n"),
+            Err(IcalParseError::Unterminated)
+        );
+        assert_eq!(
+            parse_vtodos("BEGIN:VTODO\nDUE:2026-10-20\nEND:VTODO\n"),
+            Err(IcalParseError::Date("2026-10-20".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_rrule_to_recurrence() {
+        let rec = |s: &str| rrule_to_recurrence(s).map(|r| r.to_string());
+        assert_eq!(rec("FREQ=DAILY"), Some("+d".to_string()));
+        assert_eq!(rec("FREQ=WEEKLY;INTERVAL=3"), Some("+3w".to_string()));
+        assert_eq!(
+            rec("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR"),
+            Some("+b".to_string())
+        );
+        assert_eq!(rec("FREQ=MONTHLY;COUNT=3"), None);
+        assert_eq!(rec("FREQ=MONTHLY;BYMONTHDAY=15"), None);
+        assert_eq!(rec("FREQ=HOURLY"), None);
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let item: TodoItem = "(C) 2026-10-01 Water plants +garden @home due:2026-10-20 rec:+w uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"
+            .parse()
+            .unwrap();
+        let ics = export_ical(std::slice::from_ref(&item), &IcalExportOptions::default());
+        assert_eq!(parse_vtodos(&ics).unwrap(), vec![item]);
+
+        // Tags typed mid-sentence come back where they were.
+        let item: TodoItem =
+            "Call @mom about +garden due:2026-10-20 uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"
+                .parse()
+                .unwrap();
+        let ics = export_ical(std::slice::from_ref(&item), &IcalExportOptions::default());
+        let imported = parse_vtodos(&ics).unwrap();
+        assert_eq!(
+            imported[0].to_string(),
+            "Call @mom about +garden due:2026-10-20 uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"
+        );
+        assert_eq!(imported, vec![item]);
+
+        // So does the time of a due date.
+        let item: TodoItem =
+            "Call Bob due:2026-10-20T17:30 uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"
+                .parse()
+                .unwrap();
+        let ics = export_ical(std::slice::from_ref(&item), &IcalExportOptions::default());
+        assert!(lines_of(&ics).contains(&"DUE:20261020T173000"));
+        assert_eq!(parse_vtodos(&ics).unwrap(), vec![item]);
+    }
+
+    #[test]
+    fn test_dtstart_has_the_value_type_of_due() {
+        let item: TodoItem = "Pay rent due:2026-10-20T17:30 t:2026-10-15"
+            .parse()
+            .unwrap();
+        let ics = export_ical(&[item], &IcalExportOptions::default());
+        let lines = lines_of(&ics);
+        assert!(lines.contains(&"DUE:20261020T173000"));
+        assert!(lines.contains(&"DTSTART:20261015T000000"));
+
+        let item: TodoItem = "Pay rent due:2026-10-20 t:2026-10-15T09:00"
+            .parse()
+            .unwrap();
+        let ics = export_ical(&[item], &IcalExportOptions::default());
+        let lines = lines_of(&ics);
+        assert!(lines.contains(&"DUE;VALUE=DATE:20261020"));
+        assert!(lines.contains(&"DTSTART;VALUE=DATE:20261015"));
+
+        let item: TodoItem = "Pay rent t:2026-10-15T09:00".parse().unwrap();
+        let ics = export_ical(&[item], &IcalExportOptions::default());
+        assert!(lines_of(&ics).contains(&"DTSTART:20261015T090000"));
+    }
+}
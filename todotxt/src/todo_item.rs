@@ -1,8 +1,9 @@
 use crate::todo_context::TodoContext;
+use crate::todo_names::NameGrammar;
 use crate::todo_priority::TodoPriority;
 use crate::todo_project::TodoProject;
 use crate::todo_recurrence::TodoRecurrence;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
@@ -18,14 +19,22 @@ pub struct TodoItem {
     pub priority: TodoPriority,
     pub completion_date: Option<NaiveDate>,
     pub creation_date: Option<NaiveDate>, // must exist if completion date is set
+    /// The text as typed, including any `+project` and `@context` words in it, which are
+    /// also in `projects` and `contexts`; see [`TodoItem::is_inline`].
     pub description: String,
     pub projects: Vec<TodoProject>,
     pub contexts: Vec<TodoContext>,
     pub due: Option<NaiveDate>,
+    /// Time of day of `due`, written `due:2026-10-20T14:00`. Read from `duetime:14:00` too.
+    pub due_time: Option<NaiveTime>,
     pub recurrence: Option<TodoRecurrence>,
     pub threshold: Option<NaiveDate>,
+    /// Time of day of `threshold`, written `t:2026-10-20T09:00`.
+    pub threshold_time: Option<NaiveTime>,
     pub uuid: Option<Uuid>,
     pub sub: Option<Uuid>,
+    /// Items this one waits for, by uuid, one `dep:` tag each.
+    pub deps: Vec<Uuid>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
@@ -50,6 +59,14 @@ impl FromStr for TodoItem {
     type Err = TodoItemParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TodoItem::parse_with(s, &NameGrammar::default())
+    }
+}
+
+impl TodoItem {
+    /// Parses a todo.txt line, reading project and context names with `grammar`. A `+` or
+    /// `@` word that isn't a valid name stays in the description.
+    pub fn parse_with(s: &str, grammar: &NameGrammar) -> Result<Self, TodoItemParseError> {
         let parts: Vec<&str> = s.split_whitespace().collect();
         let mut index = 0;
 
@@ -77,24 +94,24 @@ impl FromStr for TodoItem {
         let mut creation_date = None;
 
         if done {
-            if let Some(date_str) = parts.get(index) {
-                if let Ok(date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-                    completion_date = Some(date);
-                    index += 1;
-                }
+            if let Some(date_str) = parts.get(index)
+                && let Ok(date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+            {
+                completion_date = Some(date);
+                index += 1;
             }
-            if let Some(date_str) = parts.get(index) {
-                if let Ok(date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-                    creation_date = Some(date);
-                    index += 1;
-                }
+            if let Some(date_str) = parts.get(index)
+                && let Ok(date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+            {
+                creation_date = Some(date);
+                index += 1;
             }
         } else {
-            if let Some(date_str) = parts.get(index) {
-                if let Ok(date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-                    creation_date = Some(date);
-                    index += 1;
-                }
+            if let Some(date_str) = parts.get(index)
+                && let Ok(date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+            {
+                creation_date = Some(date);
+                index += 1;
             }
         }
 
@@ -103,28 +120,43 @@ impl FromStr for TodoItem {
         let mut projects = vec![];
         let mut contexts = vec![];
         let mut due = None;
+        let mut due_time = None;
+        // The `duetime:` word, kept as text if there turns out to be no due date.
+        let mut compat_due_time: Option<(&str, NaiveTime)> = None;
         let mut recurrence = None;
         let mut threshold = None;
+        let mut threshold_time = None;
         let mut uuid: Option<Uuid> = None;
         let mut sub: Option<Uuid> = None;
+        let mut deps: Vec<Uuid> = Vec::new();
         let mut clean_description_parts = vec![];
 
         for word in description_vec {
-            if word.starts_with('+') && word.len() > 1 {
-                let project = TodoProject::from_str(word)?;
+            // Projects and contexts stay where they were typed, as well as being collected.
+            if let Ok(project) = TodoProject::parse_with(word, grammar) {
                 projects.push(project);
-            } else if word.starts_with('@') && word.len() > 1 {
-                let context = TodoContext::from_str(word)?;
+                clean_description_parts.push(word.to_string());
+            } else if let Ok(context) = TodoContext::parse_with(word, grammar) {
                 contexts.push(context);
+                clean_description_parts.push(word.to_string());
             } else if word.starts_with("due:") && word.len() > 5 {
-                let date_str = &word[4..];
-                due = Some(chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")?);
+                let (date, time) = parse_date_time(&word[4..])?;
+                due = Some(date);
+                due_time = time;
+            } else if word.starts_with("duetime:") && word.len() > 8 {
+                // Lines written before times of day were understood may use `duetime:` for
+                // anything; only a time is taken as one, so such lines still load.
+                match NaiveTime::parse_from_str(&word[8..], TIME_FORMAT) {
+                    Ok(time) => compat_due_time = Some((word, time)),
+                    Err(_) => clean_description_parts.push(word.to_string()),
+                }
             } else if word.starts_with("rec:") && word.len() > 4 {
                 let rec_str = &word[4..];
                 recurrence = Some(TodoRecurrence::from_str(rec_str)?);
             } else if word.starts_with("t:") && word.len() > 2 {
-                let thresh_str = &word[2..];
-                threshold = Some(chrono::NaiveDate::parse_from_str(thresh_str, "%Y-%m-%d")?);
+                let (date, time) = parse_date_time(&word[2..])?;
+                threshold = Some(date);
+                threshold_time = time;
             } else if word.starts_with("uuid:") && word.len() > 5 {
                 let uuid_str = &word[5..];
                 let parsed_uuid = Uuid::parse_str(uuid_str)?;
@@ -134,6 +166,13 @@ impl FromStr for TodoItem {
                 let sub_str = &word[4..];
                 let parsed_sub = Uuid::parse_str(sub_str)?;
                 sub = Some(parsed_sub);
+            } else if word.starts_with("dep:") && word.len() > 4 {
+                // Like pri:, a value that isn't a uuid stays text rather than losing the line.
+                match Uuid::parse_str(&word[4..]) {
+                    Ok(dep) if !deps.contains(&dep) => deps.push(dep),
+                    Ok(_) => {}
+                    Err(_) => clean_description_parts.push(word.to_string()),
+                }
             } else if word.starts_with("pri:") && word.len() > 4 {
                 let prio_str = &word[4..];
                 // Unlike the other tags, an unparseable pri: value is not a hard error: it
@@ -155,6 +194,15 @@ impl FromStr for TodoItem {
             }
         }
 
+        if let Some((word, time)) = compat_due_time {
+            if due.is_some() {
+                // A time given in the due: tag itself wins.
+                due_time = due_time.or(Some(time));
+            } else {
+                clean_description_parts.push(word.to_string());
+            }
+        }
+
         let description = clean_description_parts.join(" ");
 
         Ok(TodoItem {
@@ -166,10 +214,13 @@ impl FromStr for TodoItem {
             projects,
             contexts,
             due,
+            due_time,
             recurrence,
             threshold,
+            threshold_time,
             uuid,
             sub,
+            deps,
         })
     }
 }
@@ -215,16 +266,21 @@ impl Display for TodoItem {
             parts.push(format!("pri:{}", (p + b'A') as char));
         }
 
-        for p in &self.projects {
-            parts.push(p.to_string());
+        // Those typed in the description are already written.
+        for tag in self.projects.iter().map(|p| p.to_string()) {
+            if !self.is_inline(&tag) {
+                parts.push(tag);
+            }
         }
 
-        for c in &self.contexts {
-            parts.push(c.to_string());
+        for tag in self.contexts.iter().map(|c| c.to_string()) {
+            if !self.is_inline(&tag) {
+                parts.push(tag);
+            }
         }
 
         if let Some(d) = self.due {
-            parts.push(format!("due:{}", d.format("%Y-%m-%d")));
+            parts.push(format!("due:{}", format_date_time(d, self.due_time)));
         }
 
         if let Some(ref r) = self.recurrence {
@@ -232,7 +288,7 @@ impl Display for TodoItem {
         }
 
         if let Some(t) = self.threshold {
-            parts.push(format!("t:{}", t.format("%Y-%m-%d")));
+            parts.push(format!("t:{}", format_date_time(t, self.threshold_time)));
         }
 
         if let Some(u) = self.uuid {
@@ -243,11 +299,129 @@ impl Display for TodoItem {
             parts.push(format!("sub:{}", s));
         }
 
+        for d in &self.deps {
+            parts.push(format!("dep:{}", d));
+        }
+
         write!(f, "{}", parts.join(" "))
     }
 }
 
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Whether `word`, from a description, is `tag` (`+garden` or `@mom`) typed in place,
+/// perhaps followed by punctuation `grammar` drops, as in `+garden,`. `+garden_` names
+/// another project.
+pub fn is_tag_word(word: &str, tag: &str, grammar: &NameGrammar) -> bool {
+    if word == tag {
+        return true;
+    }
+    let (sigil, name) = tag.split_at(tag.chars().next().map_or(0, char::len_utf8));
+    word.strip_prefix(sigil)
+        .and_then(|rest| grammar.parse(rest))
+        .is_some_and(|parsed| parsed == name)
+}
+
+/// Parses the value of a `due:` or `t:` tag: a date, optionally followed by `T` and a time
+/// of day, as in `2026-10-20T14:00`.
+pub fn parse_date_time(s: &str) -> Result<(NaiveDate, Option<NaiveTime>), chrono::ParseError> {
+    match s.split_once('T') {
+        Some((date, time)) => Ok((
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")?,
+            Some(NaiveTime::parse_from_str(time, TIME_FORMAT)?),
+        )),
+        None => Ok((NaiveDate::parse_from_str(s, "%Y-%m-%d")?, None)),
+    }
+}
+
+/// The inverse of [`parse_date_time`].
+pub fn format_date_time(date: NaiveDate, time: Option<NaiveTime>) -> String {
+    match time {
+        Some(time) => format!("{}T{}", date.format("%Y-%m-%d"), time.format(TIME_FORMAT)),
+        None => date.format("%Y-%m-%d").to_string(),
+    }
+}
+
 impl TodoItem {
+    /// When the item is due: at its due time, or at the end of the day for a due date
+    /// without one. Items compare by this when sorted by due date, so on the same day timed
+    /// items come first.
+    pub fn due_at(&self) -> Option<NaiveDateTime> {
+        let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
+        self.due
+            .map(|d| d.and_time(self.due_time.unwrap_or(end_of_day)))
+    }
+
+    /// When the item becomes actionable: at its threshold time, or at the start of the day.
+    pub fn threshold_at(&self) -> Option<NaiveDateTime> {
+        self.threshold
+            .map(|t| t.and_time(self.threshold_time.unwrap_or(NaiveTime::MIN)))
+    }
+
+    /// Whether the item is open and its due time has passed; a date-only due date passes at
+    /// the end of the day.
+    pub fn is_overdue(&self, now: NaiveDateTime) -> bool {
+        !self.done && self.due_at().is_some_and(|due| due < now)
+    }
+
+    /// Whether `tag` (`+garden` or `@mom`) was typed in the description, rather than only
+    /// being listed after it.
+    pub fn is_inline(&self, tag: &str) -> bool {
+        self.is_inline_with(tag, &NameGrammar::default())
+    }
+
+    /// [`TodoItem::is_inline`], for an item read with `grammar`.
+    pub fn is_inline_with(&self, tag: &str, grammar: &NameGrammar) -> bool {
+        self.description
+            .split_whitespace()
+            .any(|word| is_tag_word(word, tag, grammar))
+    }
+
+    /// The description without the projects and contexts typed in it, for places that show
+    /// those separately.
+    pub fn plain_description(&self) -> String {
+        self.plain_description_with(&NameGrammar::default())
+    }
+
+    /// [`TodoItem::plain_description`], for an item read with `grammar`.
+    pub fn plain_description_with(&self, grammar: &NameGrammar) -> String {
+        let tags: Vec<String> = self
+            .projects
+            .iter()
+            .map(|p| p.to_string())
+            .chain(self.contexts.iter().map(|c| c.to_string()))
+            .collect();
+        self.description
+            .split_whitespace()
+            .filter(|word| !tags.iter().any(|tag| is_tag_word(word, tag, grammar)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Replaces `from` (`+garden`) where it was typed in the description with `to`, keeping
+    /// any punctuation after it, or drops it if `to` is `None`. Whoever renames or removes
+    /// a project or context calls this, so the description doesn't bring the old one back.
+    pub fn retag(&mut self, from: &str, to: Option<&str>) {
+        self.retag_with(from, to, &NameGrammar::default());
+    }
+
+    /// [`TodoItem::retag`], for an item read with `grammar`.
+    pub fn retag_with(&mut self, from: &str, to: Option<&str>, grammar: &NameGrammar) {
+        // Renaming to a tag already typed merges the two, as for `projects`.
+        let to = to.filter(|to| !self.is_inline_with(to, grammar));
+        self.description = self
+            .description
+            .split_whitespace()
+            .filter_map(|word| {
+                if !is_tag_word(word, from, grammar) {
+                    return Some(word.to_string());
+                }
+                to.map(|to| format!("{}{}", to, &word[from.len()..]))
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+
     pub fn add_subtask(&self, child: &TodoItem) -> (Option<TodoItem>, TodoItem) {
         let new_uuid = if let Some(existing_uuid) = self.uuid {
             existing_uuid
@@ -266,6 +440,22 @@ impl TodoItem {
         (new_parent, new_child)
     }
 
+    /// The item's `uuid:`, or for an item without one, a name-based (v5) UUID of its creation
+    /// date and plain description. Exporters use this as the identity of the item in other
+    /// tools, and it stays stable for as long as that text does, wherever its tags are.
+    pub fn stable_uuid(&self) -> Uuid {
+        self.uuid.unwrap_or_else(|| {
+            let name = format!(
+                "{} {}",
+                self.creation_date
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+                self.plain_description()
+            );
+            Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes())
+        })
+    }
+
     pub fn set_done(&self, done: bool) -> TodoItem {
         TodoItem {
             done,
@@ -302,10 +492,11 @@ impl TodoItem {
     }
 
     pub fn set_projects(&self, projects: Vec<TodoProject>) -> TodoItem {
-        TodoItem {
-            projects,
-            ..self.clone()
+        let mut item = self.clone();
+        for removed in self.projects.iter().filter(|p| !projects.contains(p)) {
+            item.retag(&removed.to_string(), None);
         }
+        TodoItem { projects, ..item }
     }
 
     pub fn add_project(&self, project: TodoProject) -> TodoItem {
@@ -319,22 +510,21 @@ impl TodoItem {
 
     pub fn remove_project(&self, project: &TodoProject) -> Option<TodoItem> {
         if let Some(pos) = self.projects.iter().position(|p| p == project) {
-            let mut new_projects = self.projects.clone();
-            new_projects.remove(pos);
-            Some(TodoItem {
-                projects: new_projects,
-                ..self.clone()
-            })
+            let mut item = self.clone();
+            item.projects.remove(pos);
+            item.retag(&project.to_string(), None);
+            Some(item)
         } else {
             None
         }
     }
 
     pub fn set_contexts(&self, contexts: Vec<TodoContext>) -> TodoItem {
-        TodoItem {
-            contexts,
-            ..self.clone()
+        let mut item = self.clone();
+        for removed in self.contexts.iter().filter(|c| !contexts.contains(c)) {
+            item.retag(&removed.to_string(), None);
         }
+        TodoItem { contexts, ..item }
     }
 
     pub fn add_context(&self, context: TodoContext) -> TodoItem {
@@ -348,12 +538,10 @@ impl TodoItem {
 
     pub fn remove_context(&self, context: &TodoContext) -> Option<TodoItem> {
         if let Some(pos) = self.contexts.iter().position(|c| c == context) {
-            let mut new_contexts = self.contexts.clone();
-            new_contexts.remove(pos);
-            Some(TodoItem {
-                contexts: new_contexts,
-                ..self.clone()
-            })
+            let mut item = self.clone();
+            item.contexts.remove(pos);
+            item.retag(&context.to_string(), None);
+            Some(item)
         } else {
             None
         }
@@ -362,6 +550,8 @@ impl TodoItem {
     pub fn set_due(&self, due: Option<NaiveDate>) -> TodoItem {
         TodoItem {
             due,
+            // A new date keeps the time of day; clearing the date clears it too.
+            due_time: due.and(self.due_time),
             ..self.clone()
         }
     }
@@ -376,6 +566,7 @@ impl TodoItem {
     pub fn set_threshold(&self, threshold: Option<NaiveDate>) -> TodoItem {
         TodoItem {
             threshold,
+            threshold_time: threshold.and(self.threshold_time),
             ..self.clone()
         }
     }
@@ -428,7 +619,8 @@ mod tests {
                 name: "home".to_string()
             }]
         );
-        assert_eq!(item.description, "Buy milk");
+        assert_eq!(item.description, "Buy milk +Personal @home");
+        assert_eq!(item.plain_description(), "Buy milk");
         assert_eq!(item.threshold, None);
         assert_eq!(item.uuid, None);
         assert_eq!(item.sub, None);
@@ -465,7 +657,7 @@ mod tests {
             item.creation_date,
             Some(NaiveDate::from_ymd_opt(2024, 8, 31).unwrap())
         );
-        assert_eq!(item.description, "Task");
+        assert_eq!(item.description, "Task +p @c");
     }
 
     #[test]
@@ -526,7 +718,7 @@ mod tests {
                 .unwrap();
         assert!(item.done);
         assert_eq!(item.priority.priority, Some(2));
-        assert_eq!(item.description, "Инфостан");
+        assert_eq!(item.description, "Инфостан +рачуни @кућа");
     }
 
     #[test]
@@ -540,10 +732,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         assert_eq!(item.to_string(), "Buy milk");
         assert_eq!(item.threshold, None);
@@ -558,7 +753,7 @@ mod tests {
             .unwrap();
         assert!(!item.done);
         assert_eq!(item.priority, TodoPriority { priority: None });
-        assert_eq!(item.description, "Buy groceries");
+        assert_eq!(item.description, "Buy groceries +Personal @home");
         assert_eq!(
             item.projects,
             vec![TodoProject {
@@ -597,10 +792,13 @@ mod tests {
                 name: "home".to_string(),
             }],
             due: Some(NaiveDate::from_ymd_opt(2023, 5, 30).unwrap()),
+            due_time: None,
             recurrence: Some("1m".parse().unwrap()),
             threshold: Some(NaiveDate::from_ymd_opt(2023, 5, 25).unwrap()),
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         assert_eq!(
             item.to_string(),
@@ -614,9 +812,10 @@ mod tests {
             "x 2024-10-07 2024-08-31 Стронгхолд pri:A +одржавање @фејнман @здравље due:2024-10-05 rec:5w"
                 .parse()
                 .unwrap();
+        // The tags stay in the description, which the pri: tag follows.
         assert_eq!(
             item.to_string(),
-            "x 2024-10-07 2024-08-31 Стронгхолд pri:A +одржавање @фејнман @здравље due:2024-10-05 rec:5w"
+            "x 2024-10-07 2024-08-31 Стронгхолд +одржавање @фејнман @здравље pri:A due:2024-10-05 rec:5w"
         );
     }
 
@@ -642,10 +841,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         assert_eq!(item.to_string(), "(A) Call mom");
     }
@@ -684,10 +886,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         assert_eq!(item.to_string(), "x Review code");
     }
@@ -706,10 +911,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let once = corrupting_shape.to_string();
         let reparsed: TodoItem = once.parse().unwrap();
@@ -730,10 +938,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         assert_eq!(item.to_string(), "x pri:A");
     }
@@ -752,6 +963,100 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_and_display_deps() {
+        let line = "Build API dep:12345678-1234-1234-1234-123456789abc dep:87654321-4321-4321-4321-abc123456789";
+        let item: TodoItem = line.parse().unwrap();
+        assert_eq!(item.description, "Build API");
+        assert_eq!(item.deps.len(), 2);
+        assert_eq!(item.to_string(), line);
+        let item: TodoItem = "Build dep:nope".parse().unwrap();
+        assert_eq!(item.description, "Build dep:nope");
+        assert!(item.deps.is_empty());
+    }
+
+    #[test]
+    fn inline_tags_keep_their_place() {
+        let line = "(A) Call @mom about +garden plans due:2026-10-20";
+        let item: TodoItem = line.parse().unwrap();
+        assert_eq!(item.to_string(), line);
+        assert_eq!(item.plain_description(), "Call about plans");
+        assert!(item.is_inline("+garden"));
+
+        // Tags only in the lists are written after the description.
+        let item = item.add_project(TodoProject {
+            name: "home".to_string(),
+        });
+        assert_eq!(
+            item.to_string(),
+            "(A) Call @mom about +garden plans +home due:2026-10-20"
+        );
+        let item = item.set_projects(vec![TodoProject {
+            name: "home".to_string(),
+        }]);
+        assert_eq!(
+            item.to_string(),
+            "(A) Call @mom about plans +home due:2026-10-20"
+        );
+
+        let mut item: TodoItem = "Ask @mom, then @dad".parse().unwrap();
+        item.retag("@mom", Some("@parents"));
+        assert_eq!(item.description, "Ask @parents, then @dad");
+        item.retag("@dad", Some("@parents"));
+        assert_eq!(item.description, "Ask @parents, then");
+    }
+
+    #[test]
+    fn parse_names_with_grammar() {
+        let item: TodoItem = "Call @mom, learn +c++ for +garden.".parse().unwrap();
+        assert_eq!(item.contexts[0].name, "mom");
+        assert_eq!(item.projects.len(), 1);
+        assert_eq!(item.projects[0].name, "garden");
+        assert_eq!(item.description, "Call @mom, learn +c++ for +garden.");
+        assert_eq!(item.plain_description(), "Call learn +c++ for");
+
+        let grammar = NameGrammar {
+            extra_chars: "+".to_string(),
+            ..NameGrammar::default()
+        };
+        let item = TodoItem::parse_with("Learn +c++", &grammar).unwrap();
+        assert_eq!(item.projects[0].name, "c++");
+    }
+
+    #[test]
+    fn longer_names_are_not_the_tag() {
+        // `#`, `_` and `-` belong to the name, so these are other projects.
+        let mut item: TodoItem = "Learn +c# in +garden_ for +work-".parse().unwrap();
+        for tag in ["+c", "+garden", "+work"] {
+            assert!(!item.is_inline(tag));
+        }
+        let item_with = |tag: &str| {
+            item.add_project(TodoProject {
+                name: tag[1..].to_string(),
+            })
+        };
+        assert_eq!(
+            item_with("+c").to_string(),
+            "Learn +c# in +garden_ for +work- +c"
+        );
+        item.retag("+garden", Some("+yard"));
+        item.retag("+work", None);
+        assert_eq!(item.description, "Learn +c# in +garden_ for +work-");
+        item.projects.push(TodoProject {
+            name: "c".to_string(),
+        });
+        assert_eq!(item.plain_description(), "Learn in for");
+
+        // Only what the grammar drops may follow the tag.
+        let grammar = NameGrammar {
+            strip_trailing: "#".to_string(),
+            ..NameGrammar::default()
+        };
+        let item = TodoItem::parse_with("Learn +c#", &grammar).unwrap();
+        assert!(item.is_inline_with("+c", &grammar));
+        assert!(!item.is_inline_with("+c", &NameGrammar::default()));
+    }
+
     #[test]
     fn display_with_uuid_and_sub() {
         let item = TodoItem {
@@ -763,10 +1068,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: Some(Uuid::parse_str("12345678-1234-1234-1234-123456789abc").unwrap()),
             sub: Some(Uuid::parse_str("87654321-4321-4321-4321-abc123456789").unwrap()),
+            deps: Vec::new(),
         };
         assert_eq!(
             item.to_string(),
@@ -785,10 +1093,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let child = TodoItem {
             done: false,
@@ -799,10 +1110,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let (updated_parent, new_child) = parent.add_subtask(&child);
         assert!(updated_parent.is_some());
@@ -825,10 +1139,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: Some(existing_uuid),
             sub: None,
+            deps: Vec::new(),
         };
         let child = TodoItem {
             done: false,
@@ -839,10 +1156,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: Some(Uuid::new_v4()), // existing sub, should be overwritten
+            deps: Vec::new(),
         };
         let (updated_parent, new_child) = parent.add_subtask(&child);
         assert!(updated_parent.is_none());
@@ -861,14 +1181,17 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let new_item = item.set_done(true);
-        assert_eq!(new_item.done, true);
-        assert_eq!(item.done, false); // original unchanged
+        assert!(new_item.done);
+        assert!(!item.done); // original unchanged
     }
 
     #[test]
@@ -882,10 +1205,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let new_item = item.set_priority(TodoPriority { priority: Some(1) });
         assert_eq!(new_item.priority.priority, Some(1));
@@ -903,10 +1229,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let new_item = item.set_description("New".to_string());
         assert_eq!(new_item.description, "New");
@@ -924,10 +1253,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let proj = TodoProject {
             name: "Work".to_string(),
@@ -952,10 +1284,13 @@ mod tests {
             projects: vec![proj.clone()],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let new_item = item.remove_project(&proj).unwrap();
         assert_eq!(new_item.projects.len(), 0);
@@ -973,10 +1308,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let ctx = TodoContext {
             name: "Home".to_string(),
@@ -1001,10 +1339,13 @@ mod tests {
             projects: vec![],
             contexts: vec![ctx.clone()],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let new_item = item.remove_context(&ctx).unwrap();
         assert_eq!(new_item.contexts.len(), 0);
@@ -1022,10 +1363,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let date = NaiveDate::from_ymd_opt(2023, 5, 30).unwrap();
         let new_item = item.set_due(Some(date));
@@ -1044,10 +1388,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let rec = TodoRecurrence::from_str("1m").unwrap();
         let new_item = item.set_recurrence(Some(rec.clone()));
@@ -1066,10 +1413,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let date = NaiveDate::from_ymd_opt(2023, 5, 25).unwrap();
         let new_item = item.set_threshold(Some(date));
@@ -1088,10 +1438,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let uuid = Uuid::new_v4();
         let new_item = TodoItem {
@@ -1113,14 +1466,18 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let sub = Uuid::new_v4();
         let new_item = TodoItem {
             sub: Some(sub),
+            deps: Vec::new(),
             ..item.clone()
         };
         assert_eq!(new_item.sub, Some(sub));
@@ -1138,10 +1495,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let date = NaiveDate::from_ymd_opt(2023, 5, 26).unwrap();
         let new_item = item.set_completion_date(Some(date));
@@ -1160,10 +1520,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let date = NaiveDate::from_ymd_opt(2023, 5, 20).unwrap();
         let new_item = item.set_creation_date(Some(date));
@@ -1182,10 +1545,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let contexts = vec![TodoContext {
             name: "home".to_string(),
@@ -1206,10 +1572,13 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         let item2 = item1.clone();
         assert_eq!(item1, item2);
@@ -1230,14 +1599,17 @@ mod tests {
                 name: "home".to_string(),
             }],
             due: Some(NaiveDate::from_ymd_opt(2023, 5, 30).unwrap()),
+            due_time: None,
             recurrence: Some(TodoRecurrence {
                 strict: false,
                 count: 1,
                 unit: TodoRecurrenceUnit::Daily,
             }),
             threshold: Some(NaiveDate::from_ymd_opt(2023, 5, 25).unwrap()),
+            threshold_time: None,
             uuid: Some(Uuid::new_v4()),
             sub: None,
+            deps: Vec::new(),
         };
         let item2 = item1.clone();
         assert_eq!(item1, item2);
@@ -1293,11 +1665,61 @@ mod tests {
             projects: vec![],
             contexts: vec![],
             due: None,
+            due_time: None,
             recurrence: None,
             threshold: None,
+            threshold_time: None,
             uuid: None,
             sub: None,
+            deps: Vec::new(),
         };
         assert_eq!(item.to_string(), "");
     }
+
+    #[test]
+    fn test_due_and_threshold_times_round_trip() {
+        let line = "On-call handover due:2026-10-20T14:00 t:2026-10-20T09:30";
+        let item: TodoItem = line.parse().unwrap();
+        assert_eq!(item.due_time, NaiveTime::from_hms_opt(14, 0, 0));
+        assert_eq!(item.threshold_time, NaiveTime::from_hms_opt(9, 30, 0));
+        assert_eq!(item.to_string(), line);
+
+        let date_only = "Pay rent due:2026-10-20 t:2026-10-15";
+        assert_eq!(
+            date_only.parse::<TodoItem>().unwrap().to_string(),
+            date_only
+        );
+        assert!("Bad due:2026-10-20T25:00".parse::<TodoItem>().is_err());
+    }
+
+    #[test]
+    fn test_duetime_compat_tag() {
+        let item: TodoItem = "Standup duetime:09:15 due:2026-10-20".parse().unwrap();
+        assert_eq!(item.due_time, NaiveTime::from_hms_opt(9, 15, 0));
+        assert_eq!(item.to_string(), "Standup due:2026-10-20T09:15");
+
+        // Without a due date it's only text.
+        let item: TodoItem = "Standup duetime:09:15".parse().unwrap();
+        assert_eq!(item.due_time, None);
+        assert_eq!(item.to_string(), "Standup duetime:09:15");
+        let item: TodoItem = "Check the duetime:later column".parse().unwrap();
+        assert_eq!(item.description, "Check the duetime:later column");
+    }
+
+    #[test]
+    fn test_due_at_and_overdue() {
+        let timed: TodoItem = "A due:2026-10-20T14:00".parse().unwrap();
+        let date_only: TodoItem = "B due:2026-10-20".parse().unwrap();
+        assert!(timed.due_at() < date_only.due_at());
+
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        assert!(!timed.is_overdue(at("2026-10-20 13:59")));
+        assert!(timed.is_overdue(at("2026-10-20 14:01")));
+        assert!(!date_only.is_overdue(at("2026-10-20 23:00")));
+        assert!(date_only.is_overdue(at("2026-10-21 00:00")));
+
+        let waiting: TodoItem = "C t:2026-10-20T09:30".parse().unwrap();
+        assert_eq!(waiting.threshold_at(), Some(at("2026-10-20 09:30")));
+        assert_eq!(waiting.set_threshold(None).threshold_time, None);
+    }
 }
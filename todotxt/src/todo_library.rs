@@ -1,11 +1,39 @@
+use crate::todo_context::TodoContext;
+use crate::todo_deps::{self, DependencyError};
+use crate::todo_ical::{IcalParseError, parse_vtodos};
 use crate::todo_item::TodoItem;
+use crate::todo_names::NameGrammar;
+use crate::todo_project::TodoProject;
 use crate::todo_recurrence::TodoRecurrenceUnit;
+use crate::todo_taskwarrior::{TaskwarriorError, from_taskwarrior_json};
 use chrono::{Duration, Local};
+use uuid::Uuid;
+
+/// The outcome of importing items from another format into a library.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TodoImportSummary {
+    pub imported: usize,
+    /// Items skipped because an item with the same `uuid:` was already present.
+    pub skipped: usize,
+    /// The lines of items left out because they wouldn't load back as the same item, like a
+    /// summary with a `due:` word that isn't a date.
+    pub rejected: Vec<String>,
+}
+
+/// A project or context name that the library's [`NameGrammar`] wouldn't read back as one:
+/// empty, or with whitespace or other characters it doesn't allow.
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[error(
+    "invalid name '{0}': must be non-empty and use only letters, digits and the allowed characters"
+)]
+pub struct TodoRenameError(pub String);
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct TodoLibrary {
     pub file_name: String,
     pub items: Vec<TodoItem>,
+    /// How project and context names are read and matched.
+    pub grammar: NameGrammar,
 }
 
 impl TodoLibrary {
@@ -13,6 +41,7 @@ impl TodoLibrary {
         TodoLibrary {
             file_name,
             items: Vec::new(),
+            grammar: NameGrammar::default(),
         }
     }
 
@@ -20,7 +49,7 @@ impl TodoLibrary {
         let content = std::fs::read_to_string(&self.file_name)?;
         self.items = content
             .lines()
-            .filter_map(|line| line.parse::<TodoItem>().ok())
+            .filter_map(|line| TodoItem::parse_with(line, &self.grammar).ok())
             .collect();
         Ok(())
     }
@@ -79,55 +108,242 @@ impl TodoLibrary {
         self.items[index].done = true;
         // Check for recurrence and create new item
         let has_recurrence = self.items[index].recurrence.is_some();
-        if has_recurrence {
-            if let Some(ref recurrence) = self.items[index].recurrence {
-                if let Some(current_due) = self.items[index].due {
-                    let base_date = if recurrence.strict {
-                        current_due
-                    } else {
-                        Local::now().date_naive()
-                    };
-                    let new_due = match recurrence.unit {
-                        TodoRecurrenceUnit::Daily => {
-                            base_date + Duration::days(recurrence.count as i64)
-                        }
-                        TodoRecurrenceUnit::BusinessDay => {
-                            // Skip weekends or something, but for simplicity, treat as daily
-                            base_date + Duration::days(recurrence.count as i64)
-                        }
-                        TodoRecurrenceUnit::Weekly => {
-                            base_date + Duration::weeks(recurrence.count as i64)
-                        }
-                        TodoRecurrenceUnit::Monthly => {
-                            // Approximate months as 30 days
-                            base_date + Duration::days(recurrence.count as i64 * 30)
-                        }
-                        TodoRecurrenceUnit::Yearly => {
-                            // Approximate years as 365 days
-                            base_date + Duration::days(recurrence.count as i64 * 365)
-                        }
-                    };
-                    let new_item = TodoItem {
-                        done: false,
-                        priority: self.items[index].priority.clone(),
-                        completion_date: None,
-                        creation_date: Some(Local::now().date_naive()),
-                        description: self.items[index].description.clone(),
-                        projects: self.items[index].projects.clone(),
-                        contexts: self.items[index].contexts.clone(),
-                        due: Some(new_due),
-                        recurrence: Some(recurrence.clone()),
-                        threshold: self.items[index].threshold,
-                        uuid: None,
-                        sub: None,
-                    };
-                    self.items.push(new_item);
+        if has_recurrence
+            && let Some(ref recurrence) = self.items[index].recurrence
+            && let Some(current_due) = self.items[index].due
+        {
+            let base_date = if recurrence.strict {
+                current_due
+            } else {
+                Local::now().date_naive()
+            };
+            let new_due = match recurrence.unit {
+                TodoRecurrenceUnit::Daily => base_date + Duration::days(recurrence.count as i64),
+                TodoRecurrenceUnit::BusinessDay => {
+                    // Skip weekends or something, but for simplicity, treat as daily
+                    base_date + Duration::days(recurrence.count as i64)
                 }
-            }
+                TodoRecurrenceUnit::Weekly => base_date + Duration::weeks(recurrence.count as i64),
+                TodoRecurrenceUnit::Monthly => {
+                    // Approximate months as 30 days
+                    base_date + Duration::days(recurrence.count as i64 * 30)
+                }
+                TodoRecurrenceUnit::Yearly => {
+                    // Approximate years as 365 days
+                    base_date + Duration::days(recurrence.count as i64 * 365)
+                }
+            };
+            let new_item = TodoItem {
+                done: false,
+                priority: self.items[index].priority.clone(),
+                completion_date: None,
+                creation_date: Some(Local::now().date_naive()),
+                description: self.items[index].description.clone(),
+                projects: self.items[index].projects.clone(),
+                contexts: self.items[index].contexts.clone(),
+                due: Some(new_due),
+                due_time: self.items[index].due_time,
+                recurrence: Some(recurrence.clone()),
+                threshold: self.items[index].threshold,
+                threshold_time: self.items[index].threshold_time,
+                uuid: None,
+                sub: None,
+                deps: Vec::new(),
+            };
+            self.items.push(new_item);
         }
         Some(has_recurrence)
     }
 
+    /// Adds the VTODOs of an iCalendar document as items; see [`Self::import_items`]. The
+    /// document is parsed in full first, so a parse error leaves the library unchanged.
+    pub fn import_ical(&mut self, ics: &str) -> Result<TodoImportSummary, IcalParseError> {
+        Ok(self.import_items(parse_vtodos(ics)?))
+    }
+
+    /// Adds the tasks of a Taskwarrior JSON export as items; see [`Self::import_items`]. As
+    /// with import_ical, a parse error leaves the library unchanged.
+    pub fn import_taskwarrior(
+        &mut self,
+        json: &str,
+    ) -> Result<TodoImportSummary, TaskwarriorError> {
+        Ok(self.import_items(from_taskwarrior_json(json)?))
+    }
+
+    /// Adds items converted from another tool, skipping any whose `uuid:` matches an item
+    /// already in the library (or one added earlier in the same batch), so importing the same
+    /// export twice is harmless.
+    ///
+    /// Completed items keep the dates they came with rather than getting today's creation
+    /// date, which could be later than their completion; one without a completion date is
+    /// taken to have been completed today.
+    ///
+    /// Text from the other tool can hold words that mean something in todo.txt, like
+    /// `due:tomorrow`. An item whose line wouldn't load back as itself is left out and
+    /// reported in the summary, rather than saved for the next load to drop or misread.
+    pub fn import_items(&mut self, items: Vec<TodoItem>) -> TodoImportSummary {
+        let mut summary = TodoImportSummary::default();
+        for mut item in items {
+            if item
+                .uuid
+                .is_some_and(|u| self.items.iter().any(|existing| existing.uuid == Some(u)))
+            {
+                summary.skipped += 1;
+                continue;
+            }
+            if item.done {
+                item.completion_date = item.completion_date.or(Some(Local::now().date_naive()));
+                self.items.push(item);
+            } else {
+                self.add_item(item);
+            }
+            let added = self.items.last().expect("an item was just added");
+            let line = added.to_string();
+            if TodoItem::parse_with(&line, &self.grammar).ok().as_ref() == Some(added) {
+                summary.imported += 1;
+            } else {
+                self.items.pop();
+                summary.rejected.push(line);
+            }
+        }
+        summary
+    }
+
+    /// Makes the item at `index` wait for the one at `on` with a `dep:` tag, giving `on` a
+    /// uuid if it has none. Refused if `on` already depends on the item, which would make a
+    /// cycle.
+    pub fn add_dependency(&mut self, index: usize, on: usize) -> Result<(), DependencyError> {
+        let len = self.items.len();
+        if let Some(&bad) = [index, on].iter().find(|&&i| i >= len) {
+            return Err(DependencyError::NoItem(bad));
+        }
+        if index == on {
+            return Err(DependencyError::SelfDependency);
+        }
+        if let Some(uuid) = self.items[index].uuid
+            && todo_deps::depends_on(&self.items, &self.items[on], uuid)
+        {
+            return Err(DependencyError::Cycle);
+        }
+        let dep = *self.items[on].uuid.get_or_insert_with(Uuid::new_v4);
+        if !self.items[index].deps.contains(&dep) {
+            self.items[index].deps.push(dep);
+        }
+        Ok(())
+    }
+
+    /// Removes the `dep:` tag making the item at `index` wait for the one at `on`, if any.
+    pub fn remove_dependency(&mut self, index: usize, on: usize) -> Result<bool, DependencyError> {
+        let len = self.items.len();
+        if let Some(&bad) = [index, on].iter().find(|&&i| i >= len) {
+            return Err(DependencyError::NoItem(bad));
+        }
+        let Some(dep) = self.items[on].uuid else {
+            return Ok(false);
+        };
+        let deps = &mut self.items[index].deps;
+        let before = deps.len();
+        deps.retain(|&d| d != dep);
+        Ok(deps.len() != before)
+    }
+
+    /// The indices of the items waiting for an open item; see [`todo_deps::blocked`].
+    pub fn blocked_items(&self) -> Vec<usize> {
+        todo_deps::blocked(&self.items)
+    }
+
+    /// Renames project `from` to `to` in every item, along with its subprojects, so
+    /// `+work.clientA` becomes `+job.clientA` when `work` is renamed to `job`. A project
+    /// typed in the description is renamed where it stands; the rest of the text is left
+    /// alone. Renaming to a project an item already has leaves it with one, which is how two
+    /// projects are merged. Returns the number of items changed.
+    pub fn rename_project(&mut self, from: &str, to: &str) -> Result<usize, TodoRenameError> {
+        self.check_name(to)?;
+        let grammar = &self.grammar;
+        Ok(self
+            .items
+            .iter_mut()
+            .map(|item| {
+                let renamed: Vec<(TodoProject, TodoProject)> = item
+                    .projects
+                    .iter()
+                    .filter_map(|p| {
+                        let name = grammar.renamed(&p.name, from, to)?;
+                        Some((p.clone(), TodoProject { name }))
+                    })
+                    .collect();
+                for (old, new) in &renamed {
+                    item.retag_with(&old.to_string(), Some(&new.to_string()), grammar);
+                }
+                rename_tags(&mut item.projects, |p| {
+                    renamed
+                        .iter()
+                        .find(|(old, _)| old == p)
+                        .map(|(_, new)| new.clone())
+                })
+            })
+            .filter(|&changed| changed)
+            .count())
+    }
+
+    /// Renames context `from` to `to`, and the contexts within it, like rename_project.
+    pub fn rename_context(&mut self, from: &str, to: &str) -> Result<usize, TodoRenameError> {
+        self.check_name(to)?;
+        let grammar = &self.grammar;
+        Ok(self
+            .items
+            .iter_mut()
+            .map(|item| {
+                let renamed: Vec<(TodoContext, TodoContext)> = item
+                    .contexts
+                    .iter()
+                    .filter_map(|c| {
+                        let name = grammar.renamed(&c.name, from, to)?;
+                        Some((c.clone(), TodoContext { name }))
+                    })
+                    .collect();
+                for (old, new) in &renamed {
+                    item.retag_with(&old.to_string(), Some(&new.to_string()), grammar);
+                }
+                rename_tags(&mut item.contexts, |c| {
+                    renamed
+                        .iter()
+                        .find(|(old, _)| old == c)
+                        .map(|(_, new)| new.clone())
+                })
+            })
+            .filter(|&changed| changed)
+            .count())
+    }
+
+    /// Merges the projects `from` into `into`: every item in any of them ends up in `into`
+    /// instead. Returns the number of items changed.
+    pub fn merge_projects(&mut self, from: &[&str], into: &str) -> Result<usize, TodoRenameError> {
+        self.check_name(into)?;
+        let before = self.items.clone();
+        for name in from {
+            self.rename_project(name, into)?;
+        }
+        Ok(changed_count(&before, &self.items))
+    }
+
+    /// Merges the contexts `from` into `into`, like merge_projects.
+    pub fn merge_contexts(&mut self, from: &[&str], into: &str) -> Result<usize, TodoRenameError> {
+        self.check_name(into)?;
+        let before = self.items.clone();
+        for name in from {
+            self.rename_context(name, into)?;
+        }
+        Ok(changed_count(&before, &self.items))
+    }
+
+    fn check_name(&self, name: &str) -> Result<(), TodoRenameError> {
+        if !self.grammar.is_valid(name) {
+            return Err(TodoRenameError(name.to_string()));
+        }
+        Ok(())
+    }
+
     pub fn uncomplete_item(&mut self, index: usize) -> Option<()> {
         if index >= self.items.len() {
             return None;
@@ -142,6 +358,25 @@ impl TodoLibrary {
     }
 }
 
+/// Replaces the tags `rename` gives a new name, dropping any that end up twice. Returns
+/// whether anything changed.
+fn rename_tags<T: Clone + PartialEq>(tags: &mut Vec<T>, rename: impl Fn(&T) -> Option<T>) -> bool {
+    let mut renamed: Vec<T> = Vec::with_capacity(tags.len());
+    for tag in tags.iter() {
+        let tag = rename(tag).unwrap_or_else(|| tag.clone());
+        if !renamed.contains(&tag) {
+            renamed.push(tag);
+        }
+    }
+    let changed = renamed != *tags;
+    *tags = renamed;
+    changed
+}
+
+fn changed_count(before: &[TodoItem], after: &[TodoItem]) -> usize {
+    before.iter().zip(after).filter(|(b, a)| b != a).count()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -324,6 +559,94 @@ mod tests {
         assert_eq!(lib.items.len(), 0);
     }
 
+    #[test]
+    fn test_dependencies_block_until_completed() {
+        let mut lib = TodoLibrary::new("dummy.txt".to_string());
+        for text in ["Design", "Build", "Ship"] {
+            lib.add_item(text.parse().unwrap());
+        }
+        lib.add_dependency(1, 0).unwrap();
+        lib.add_dependency(2, 1).unwrap();
+        assert!(lib.items[0].uuid.is_some());
+        assert_eq!(lib.blocked_items(), vec![1, 2]);
+        assert_eq!(lib.add_dependency(0, 2), Err(DependencyError::Cycle));
+        assert_eq!(
+            lib.add_dependency(0, 0),
+            Err(DependencyError::SelfDependency)
+        );
+        assert_eq!(lib.add_dependency(0, 3), Err(DependencyError::NoItem(3)));
+
+        // Completing unblocks without touching the dep: tags, so un-completing blocks again.
+        lib.complete_item(0);
+        assert_eq!(lib.items[1].deps.len(), 1);
+        assert_eq!(lib.blocked_items(), vec![2]);
+        assert_eq!(lib.remove_dependency(2, 1), Ok(true));
+        assert!(lib.blocked_items().is_empty());
+    }
+
+    #[test]
+    fn test_rename_project_with_subprojects() {
+        let mut lib = TodoLibrary::new("dummy.txt".to_string());
+        for text in [
+            "Migrate work files +work.clientA +work",
+            "Invoice +work +job",
+            "Tidy +workshop @home:garage",
+        ] {
+            lib.items.push(text.parse().unwrap());
+        }
+        assert_eq!(lib.rename_project("work", "job"), Ok(2));
+        // The word "work" in the description is left alone, unlike with sed.
+        assert_eq!(
+            lib.items[0].to_string(),
+            "Migrate work files +job.clientA +job"
+        );
+        assert_eq!(lib.items[1].to_string(), "Invoice +job");
+        assert_eq!(lib.items[2].projects[0].name, "workshop");
+        assert_eq!(lib.rename_context("home", "house"), Ok(1));
+        assert_eq!(lib.items[2].contexts[0].name, "house:garage");
+        assert_eq!(
+            lib.rename_project("job", "two words"),
+            Err(TodoRenameError("two words".to_string()))
+        );
+
+        // A longer name typed next to it is another project.
+        lib.items = vec!["Weed +garden_ and +garden-2 +garden".parse().unwrap()];
+        assert_eq!(lib.rename_project("garden", "yard"), Ok(1));
+        assert_eq!(
+            lib.items[0].to_string(),
+            "Weed +garden_ and +garden-2 +yard"
+        );
+    }
+
+    #[test]
+    fn test_merge_projects() {
+        let mut lib = TodoLibrary::new("dummy.txt".to_string());
+        for text in ["A +alpha", "B +beta +gamma", "C +gamma", "D +delta"] {
+            lib.items.push(text.parse().unwrap());
+        }
+        assert_eq!(lib.merge_projects(&["alpha", "beta"], "gamma"), Ok(2));
+        let projects: Vec<String> = lib
+            .items
+            .iter()
+            .map(|i| i.projects[0].name.clone())
+            .collect();
+        assert_eq!(projects, vec!["gamma", "gamma", "gamma", "delta"]);
+        assert_eq!(lib.items[1].projects.len(), 1);
+    }
+
+    #[test]
+    fn test_rename_with_case_insensitive_grammar() {
+        let mut lib = TodoLibrary::new("dummy.txt".to_string());
+        for text in ["A +Work.api", "B +work"] {
+            lib.items.push(text.parse().unwrap());
+        }
+        assert_eq!(lib.rename_project("work", "job"), Ok(1));
+        lib.grammar.case_insensitive = true;
+        assert_eq!(lib.rename_project("WORK", "job"), Ok(1));
+        assert_eq!(lib.items[0].projects[0].name, "job.api");
+        assert!(lib.rename_project("job", "c++").is_err());
+    }
+
     #[test]
     fn test_uncomplete_item_clears_done_and_completion_date() {
         let mut lib = TodoLibrary::new("dummy.txt".to_string());
@@ -561,6 +884,103 @@ mod tests {
         assert!(lib.items[0].done);
     }
 
+    #[test]
+    fn test_import_ical_skips_existing_uuids() {
+        let mut lib = TodoLibrary::new("dummy.txt".to_string());
+        lib.add_item(
+            "Existing uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"
+                .parse()
+                .unwrap(),
+        );
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VTODO\r\nUID:67e55044-10b1-426f-9247-bb680e5fe0c8\r\nSUMMARY:Dup\r\nEND:VTODO\r\n\
+                   BEGIN:VTODO\r\nUID:new@example.com\r\nSUMMARY:New\r\nEND:VTODO\r\n\
+                   BEGIN:VTODO\r\nUID:new@example.com\r\nSUMMARY:New again\r\nEND:VTODO\r\n\
+                   END:VCALENDAR\r\n";
+        let summary = lib.import_ical(ics).unwrap();
+        assert_eq!(
+            summary,
+            TodoImportSummary {
+                imported: 1,
+                skipped: 2,
+                rejected: vec![],
+            }
+        );
+        assert_eq!(lib.item_count(), 2);
+        assert_eq!(lib.items[1].description, "New");
+        assert_eq!(
+            lib.items[1].creation_date,
+            Some(chrono::Local::now().date_naive())
+        );
+    }
+
+    #[test]
+    fn test_import_completed_items_keep_their_dates() {
+        let mut lib = TodoLibrary::new("dummy.txt".to_string());
+        let ics = "BEGIN:VTODO\nSUMMARY:Filed\nCOMPLETED:20200102T000000Z\nEND:VTODO\n\
+                   BEGIN:VTODO\nSUMMARY:Undated\nSTATUS:COMPLETED\nEND:VTODO\n";
+        lib.import_ical(ics).unwrap();
+        assert_eq!(lib.items[0].to_string(), "x 2020-01-02 Filed");
+        assert_eq!(lib.items[0].creation_date, None);
+        assert_eq!(
+            lib.items[1].completion_date,
+            Some(chrono::Local::now().date_naive())
+        );
+    }
+
+    #[test]
+    fn test_import_leaves_out_items_that_would_not_load_back() {
+        let path = std::env::temp_dir().join("test_import_round_trip.txt");
+        let mut lib = TodoLibrary::new(path.to_str().unwrap().to_string());
+        let ics = "BEGIN:VTODO\nSUMMARY:Pay rent  +home\nEND:VTODO\n\
+                   BEGIN:VTODO\nSUMMARY:Call Bob due:tomorrow\nEND:VTODO\n\
+                   BEGIN:VTODO\nSUMMARY:Water plants rec:x\nEND:VTODO\n";
+        let summary = lib.import_ical(ics).unwrap();
+        assert_eq!(summary.imported, 1);
+        assert_eq!(summary.rejected.len(), 2);
+        assert!(summary.rejected[0].ends_with("Call Bob due:tomorrow"));
+        lib.save().unwrap();
+
+        let mut reloaded = TodoLibrary::new(lib.file_name.clone());
+        reloaded.load().unwrap();
+        assert_eq!(reloaded.items, lib.items);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_import_taskwarrior() {
+        let mut lib = TodoLibrary::new("dummy.txt".to_string());
+        let json = r#"[
+            {"description":"Water plants","status":"pending","uuid":"67e55044-10b1-426f-9247-bb680e5fe0c8"},
+            {"description":"Water plants","status":"pending","uuid":"67e55044-10b1-426f-9247-bb680e5fe0c8"}
+        ]"#;
+        let summary = lib.import_taskwarrior(json).unwrap();
+        assert_eq!(summary.imported, 1);
+        assert_eq!(summary.skipped, 1);
+        assert!(lib.import_taskwarrior("not json").is_err());
+        assert_eq!(lib.item_count(), 1);
+    }
+
+    #[test]
+    fn test_import_taskwarrior_leaves_out_items_that_would_not_load_back() {
+        let path = std::env::temp_dir().join("test_import_taskwarrior_round_trip.txt");
+        let mut lib = TodoLibrary::new(path.to_str().unwrap().to_string());
+        let json = r#"[
+            {"description":"Call  Bob","status":"pending"},
+            {"description":"Call Bob t:junk","status":"pending"},
+            {"description":"Filed due:2026-13-01","status":"completed"}
+        ]"#;
+        let summary = lib.import_taskwarrior(json).unwrap();
+        assert_eq!(summary.imported, 1);
+        assert_eq!(summary.rejected.len(), 2);
+        lib.save().unwrap();
+
+        let mut reloaded = TodoLibrary::new(lib.file_name.clone());
+        reloaded.load().unwrap();
+        assert_eq!(reloaded.items, lib.items);
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_library_equality() {
         let lib1 = TodoLibrary::new("test.txt".to_string());
@@ -0,0 +1,366 @@
+//! Checks a todo file for mistakes: lines that don't parse and so would be dropped on
+//! load, dates and priorities that contradict each other, dangling references, stray
+//! whitespace and tags nothing reads. Works on the file's text rather than parsed items,
+//! since it is the lines that don't parse that matter most. [`fix`] repairs the problems
+//! that have only one sensible repair.
+
+use crate::todo_item::{TodoItem, TodoItemParseError};
+use crate::todo_names::{NameGrammar, NameLint};
+use chrono::NaiveDate;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use uuid::Uuid;
+
+/// The `key:value` tags this crate reads or writes; any others are reported as unknown.
+pub const KNOWN_TAGS: [&str; 11] = [
+    "due", "duetime", "t", "rec", "uuid", "sub", "dep", "pri", "spent", "est", "remind",
+];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LintOptions {
+    /// Reference date for overdue recurring items.
+    pub today: NaiveDate,
+    pub grammar: NameGrammar,
+    /// Uuids from other files, such as done.txt, that `sub:` may point at.
+    pub other_uuids: HashSet<Uuid>,
+    /// Tags to accept besides [`KNOWN_TAGS`], for other tools' extensions.
+    pub extra_tags: Vec<String>,
+}
+
+impl LintOptions {
+    pub fn new(today: NaiveDate) -> Self {
+        LintOptions {
+            today,
+            grammar: NameGrammar::default(),
+            other_uuids: HashSet::new(),
+            extra_tags: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LintKind {
+    /// The line doesn't parse, so loading the file drops it.
+    Unparseable(String),
+    CompletedBeforeCreated {
+        completed: NaiveDate,
+        created: NaiveDate,
+    },
+    DoneWithoutCompletionDate,
+    /// The uuid is also on the given earlier line.
+    DuplicateUuid(Uuid, usize),
+    MissingParent(Uuid),
+    /// `(A)` and a `pri:` tag that disagree; the line doesn't parse.
+    ConflictingPriority(char, char),
+    /// `(A)` and a `pri:A` tag that say the same thing.
+    RedundantPriority(char),
+    OverdueRecurring(NaiveDate),
+    TrailingWhitespace,
+    BlankLine,
+    UnknownTag(String),
+    SuspiciousName(NameLint),
+}
+
+/// A problem on a 1-based line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LintIssue {
+    pub line: usize,
+    pub kind: LintKind,
+}
+
+impl LintIssue {
+    /// Whether [`fix`] repairs it.
+    pub fn is_fixable(&self) -> bool {
+        matches!(
+            self.kind,
+            LintKind::RedundantPriority(_) | LintKind::TrailingWhitespace | LintKind::BlankLine
+        )
+    }
+}
+
+impl Display for LintKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LintKind::Unparseable(e) => write!(f, "doesn't parse ({}) and is skipped on load", e),
+            LintKind::CompletedBeforeCreated { completed, created } => write!(
+                f,
+                "completed on {} before it was created on {}",
+                completed, created
+            ),
+            LintKind::DoneWithoutCompletionDate => write!(f, "done without a completion date"),
+            LintKind::DuplicateUuid(uuid, line) => {
+                write!(f, "uuid {} is also on line {}", uuid, line)
+            }
+            LintKind::MissingParent(uuid) => write!(f, "sub:{} names no item", uuid),
+            LintKind::ConflictingPriority(a, b) => {
+                write!(f, "conflicting priorities ({}) and pri:{}", a, b)
+            }
+            LintKind::RedundantPriority(p) => write!(f, "both ({}) and pri:{}", p, p),
+            LintKind::OverdueRecurring(due) => write!(f, "recurring item overdue since {}", due),
+            LintKind::TrailingWhitespace => write!(f, "trailing whitespace"),
+            LintKind::BlankLine => write!(f, "blank line"),
+            LintKind::UnknownTag(tag) => write!(f, "unknown tag '{}'", tag),
+            LintKind::SuspiciousName(lint) => {
+                // NameLint starts with its own line number.
+                let text = lint.to_string();
+                write!(f, "{}", text.split_once(": ").map_or(&*text, |(_, t)| t))
+            }
+        }
+    }
+}
+
+impl Display for LintIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+/// The problems in `content`, the text of a todo or done file, in line order.
+pub fn lint(content: &str, options: &LintOptions) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mut push = |line: usize, kind| issues.push(LintIssue { line, kind });
+
+    let mut parsed: Vec<(usize, TodoItem)> = Vec::new();
+    for (index, text) in content.lines().enumerate() {
+        let line = index + 1;
+        if text.trim().is_empty() {
+            push(line, LintKind::BlankLine);
+            continue;
+        }
+        if text != text.trim_end() {
+            push(line, LintKind::TrailingWhitespace);
+        }
+        match TodoItem::parse_with(text, &options.grammar) {
+            Ok(item) => {
+                if let Some(p) = redundant_priority(text) {
+                    push(line, LintKind::RedundantPriority(p));
+                }
+                parsed.push((line, item));
+            }
+            Err(TodoItemParseError::ConflictingPriority(a, b)) => {
+                push(line, LintKind::ConflictingPriority(a, b))
+            }
+            Err(e) => push(line, LintKind::Unparseable(e.to_string())),
+        }
+    }
+
+    let mut seen: HashMap<Uuid, usize> = HashMap::new();
+    let uuids: HashSet<Uuid> = parsed.iter().filter_map(|(_, item)| item.uuid).collect();
+    for (line, item) in &parsed {
+        let line = *line;
+        if item.done {
+            match (item.completion_date, item.creation_date) {
+                (None, _) => push(line, LintKind::DoneWithoutCompletionDate),
+                (Some(completed), Some(created)) if completed < created => push(
+                    line,
+                    LintKind::CompletedBeforeCreated { completed, created },
+                ),
+                _ => {}
+            }
+        }
+        if let Some(uuid) = item.uuid
+            && let Some(&first) = seen.get(&uuid)
+        {
+            push(line, LintKind::DuplicateUuid(uuid, first));
+        } else if let Some(uuid) = item.uuid {
+            seen.insert(uuid, line);
+        }
+        if let Some(parent) = item.sub
+            && !uuids.contains(&parent)
+            && !options.other_uuids.contains(&parent)
+        {
+            push(line, LintKind::MissingParent(parent));
+        }
+        if !item.done
+            && item.recurrence.is_some()
+            && let Some(due) = item.due.filter(|&due| due < options.today)
+        {
+            push(line, LintKind::OverdueRecurring(due));
+        }
+        for tag in unknown_tags(&item.description, &options.extra_tags) {
+            push(line, LintKind::UnknownTag(tag));
+        }
+    }
+
+    for name in options.grammar.lint(content) {
+        push(name.line, LintKind::SuspiciousName(name));
+    }
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+/// `content` with the fixable problems repaired, and how many were.
+pub fn fix(content: &str, options: &LintOptions) -> (String, usize) {
+    let issues = lint(content, options);
+    let fixes = |line: usize| {
+        issues
+            .iter()
+            .filter(move |issue| issue.line == line && issue.is_fixable())
+    };
+    let mut fixed = 0;
+    let mut lines = Vec::new();
+    for (index, text) in content.lines().enumerate() {
+        let mut text = text.to_string();
+        for issue in fixes(index + 1) {
+            let before = text.clone();
+            match issue.kind {
+                LintKind::BlankLine => text.clear(),
+                LintKind::TrailingWhitespace => text.truncate(text.trim_end().len()),
+                LintKind::RedundantPriority(p) => {
+                    text = without_word(&text, &format!("pri:{}", p));
+                }
+                _ => {}
+            }
+            // Only what was actually repaired counts, so a rerun doesn't report it again. A
+            // blank line is always dropped, even an empty one.
+            fixed += usize::from(matches!(issue.kind, LintKind::BlankLine) || text != before);
+        }
+        if !text.is_empty() {
+            lines.push(text);
+        }
+    }
+    // Saved the way TodoLibrary saves, without a final newline.
+    (lines.join("\n"), fixed)
+}
+
+/// `text` without the words that are `word` ignoring ASCII case, as redundant_priority
+/// matches them, each with the whitespace before it; the rest is kept as it was.
+fn without_word(text: &str, word: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let start = rest
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(rest.len());
+        let end = rest[start..]
+            .find(char::is_whitespace)
+            .map_or(rest.len(), |len| start + len);
+        if !rest[start..end].eq_ignore_ascii_case(word) {
+            out.push_str(&rest[..end]);
+        }
+        rest = &rest[end..];
+    }
+    out
+}
+
+/// The priority of a line that has both `(X)` and `pri:X`.
+fn redundant_priority(text: &str) -> Option<char> {
+    let mut words = text.split_whitespace().peekable();
+    words.next_if_eq(&"x");
+    let parens = words.next()?;
+    let p = parens.strip_prefix('(')?.strip_suffix(')')?;
+    let mut chars = p.chars();
+    let (Some(p), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    let tag = format!("pri:{}", p);
+    words
+        .any(|word| word.eq_ignore_ascii_case(&tag))
+        .then_some(p)
+}
+
+/// The `key:value` words in a description whose key is neither known nor extra. URLs and
+/// times of day aren't tags.
+fn unknown_tags(description: &str, extra: &[String]) -> Vec<String> {
+    description
+        .split_whitespace()
+        .filter(|word| {
+            let Some((key, value)) = word.split_once(':') else {
+                return false;
+            };
+            !key.is_empty()
+                && key.chars().all(|c| c.is_ascii_alphabetic())
+                && !value.is_empty()
+                && !value.starts_with("//")
+                && !KNOWN_TAGS.contains(&key)
+                && !extra.iter().any(|e| e == key)
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> LintOptions {
+        LintOptions::new(NaiveDate::from_ymd_opt(2026, 10, 18).unwrap())
+    }
+
+    fn kinds(content: &str) -> Vec<(usize, LintKind)> {
+        lint(content, &options())
+            .into_iter()
+            .map(|issue| (issue.line, issue.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_dates_and_priorities() {
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        assert_eq!(
+            kinds(
+                "x 2026-10-01 2026-10-05 Backdated\nx Undated\n(A) Both pri:A\n(A) Clash pri:B\nWater rec:1w due:2026-10-11"
+            ),
+            vec![
+                (
+                    1,
+                    LintKind::CompletedBeforeCreated {
+                        completed: date("2026-10-01"),
+                        created: date("2026-10-05")
+                    }
+                ),
+                (2, LintKind::DoneWithoutCompletionDate),
+                (3, LintKind::RedundantPriority('A')),
+                (4, LintKind::ConflictingPriority('A', 'B')),
+                (5, LintKind::OverdueRecurring(date("2026-10-11"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_uuids_and_tags() {
+        const A: &str = "00000000-0000-0000-0000-00000000000a";
+        const B: &str = "00000000-0000-0000-0000-00000000000b";
+        let uuid = |s| Uuid::parse_str(s).unwrap();
+        let content = format!(
+            "One uuid:{A}\nTwo uuid:{A}\nChild sub:{B}\nSee https://example.com at 10:30 color:red\nBad due:soon"
+        );
+        assert_eq!(
+            kinds(&content),
+            vec![
+                (2, LintKind::DuplicateUuid(uuid(A), 1)),
+                (3, LintKind::MissingParent(uuid(B))),
+                (4, LintKind::UnknownTag("color:red".to_string())),
+                (5, LintKind::Unparseable("invalid date".to_string())),
+            ]
+        );
+
+        let mut options = options();
+        options.other_uuids.insert(uuid(B));
+        options.extra_tags.push("color".to_string());
+        assert_eq!(lint(&content, &options).len(), 2);
+    }
+
+    #[test]
+    fn test_fix() {
+        let content = "(B) Tidy pri:B  \n\n  \nCall @mom, then";
+        let issues = lint(content, &options());
+        assert_eq!(issues.len(), 5);
+        assert_eq!(issues.iter().filter(|i| i.is_fixable()).count(), 4);
+
+        let (fixed, count) = fix(content, &options());
+        assert_eq!(fixed, "(B) Tidy\nCall @mom, then");
+        assert_eq!(count, 4);
+        let remaining = lint(&fixed, &options());
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].to_string(), "line 2: '@mom,' is read as @mom");
+
+        // The tag is found whatever its case or the whitespace around it, and once removed
+        // there is nothing left to fix.
+        let (fixed, count) = fix("(A) Tidy\tpri:a  now", &options());
+        assert_eq!((fixed.as_str(), count), ("(A) Tidy  now", 1));
+        assert_eq!(fix(&fixed, &options()).1, 0);
+        let (fixed, count) = fix("(A) Tidy pri:a", &options());
+        assert_eq!((fixed.as_str(), count), ("(A) Tidy", 1));
+    }
+}
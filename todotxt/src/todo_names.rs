@@ -0,0 +1,189 @@
+//! What counts as a project or context name. By default a name is letters, digits and
+//! `-_.:/#&`, and punctuation that prose leaves at the end of a word is dropped, so
+//! `Call +garden, then` is in `+garden`. A `+` or `@` word that isn't a valid name, like
+//! `c++` or `@`, stays description text. [`NameGrammar::lint`] reports the tags that were
+//! probably not meant as such.
+
+use crate::todo_hierarchy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct NameGrammar {
+    /// Characters allowed in a name besides letters and digits.
+    pub extra_chars: String,
+    /// Characters dropped from the end of a name.
+    pub strip_trailing: String,
+    /// Whether filters and renames treat `+Work` and `+work` as the same project. Names are
+    /// still written as they were typed.
+    pub case_insensitive: bool,
+}
+
+impl Default for NameGrammar {
+    fn default() -> Self {
+        NameGrammar {
+            extra_chars: "-_.:/#&".to_string(),
+            strip_trailing: ".,;:!?)]}'\"".to_string(),
+            case_insensitive: false,
+        }
+    }
+}
+
+impl NameGrammar {
+    /// The name in `word`, the part after the sigil, with trailing punctuation dropped; `None`
+    /// if that leaves nothing or a character that isn't allowed.
+    pub fn parse<'a>(&self, word: &'a str) -> Option<&'a str> {
+        let name = word.trim_end_matches(|c| self.strip_trailing.contains(c));
+        (!name.is_empty() && name.chars().all(|c| self.allows(c))).then_some(name)
+    }
+
+    /// Whether `name` is a name as it stands, with nothing to drop.
+    pub fn is_valid(&self, name: &str) -> bool {
+        self.parse(name) == Some(name)
+    }
+
+    fn allows(&self, c: char) -> bool {
+        c.is_alphanumeric() || self.extra_chars.contains(c)
+    }
+
+    /// [`todo_hierarchy::is_within`], ignoring case if the grammar says so.
+    pub fn is_within(&self, name: &str, ancestor: &str) -> bool {
+        if !self.case_insensitive {
+            return todo_hierarchy::is_within(name, ancestor);
+        }
+        name.get(..ancestor.len()).is_some_and(|prefix| {
+            prefix.to_lowercase() == ancestor.to_lowercase()
+                && todo_hierarchy::is_within(name, prefix)
+        })
+    }
+
+    /// [`todo_hierarchy::renamed`], ignoring case if the grammar says so.
+    pub fn renamed(&self, name: &str, from: &str, to: &str) -> Option<String> {
+        self.is_within(name, from)
+            .then(|| format!("{}{}", to, &name[from.len()..]))
+    }
+
+    /// The suspicious `+` and `@` words in the lines of a todo file.
+    pub fn lint(&self, content: &str) -> Vec<NameLint> {
+        let mut lints = Vec::new();
+        // The first spelling of each name, by its lowercase form, to spot case variants.
+        let mut spellings: BTreeMap<String, String> = BTreeMap::new();
+        for (index, line) in content.lines().enumerate() {
+            let words = line
+                .split_whitespace()
+                .filter(|w| w.len() > 1 && w.starts_with(['+', '@']));
+            for word in words {
+                let lint = |problem| NameLint {
+                    line: index + 1,
+                    word: word.to_string(),
+                    problem,
+                };
+                let Some(name) = self.parse(&word[1..]) else {
+                    lints.push(lint(NameProblem::Invalid));
+                    continue;
+                };
+                let tag = format!("{}{}", &word[..1], name);
+                if name.len() < word.len() - 1 {
+                    lints.push(lint(NameProblem::Stripped(tag.clone())));
+                }
+                if !name.chars().any(char::is_alphabetic) {
+                    lints.push(lint(NameProblem::NoLetters));
+                }
+                let first = spellings.entry(tag.to_lowercase()).or_insert(tag.clone());
+                if !self.case_insensitive && *first != tag {
+                    lints.push(lint(NameProblem::CaseVariant(first.clone())));
+                }
+            }
+        }
+        lints
+    }
+}
+
+/// Why [`NameGrammar::lint`] reports a word.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NameProblem {
+    /// Not a valid name, so it stays description text.
+    Invalid,
+    /// Read as this tag, with trailing punctuation dropped.
+    Stripped(String),
+    /// Only digits and punctuation, like the `+5` in "+5 more".
+    NoLetters,
+    /// Differs from this earlier tag only in case.
+    CaseVariant(String),
+}
+
+/// A suspicious `+` or `@` word, on a 1-based line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NameLint {
+    pub line: usize,
+    pub word: String,
+    pub problem: NameProblem,
+}
+
+impl Display for NameLint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: '{}' ", self.line, self.word)?;
+        match &self.problem {
+            NameProblem::Invalid => write!(f, "is not a valid name and is kept as text"),
+            NameProblem::Stripped(tag) => write!(f, "is read as {}", tag),
+            NameProblem::NoLetters => write!(f, "has no letters"),
+            NameProblem::CaseVariant(tag) => write!(f, "differs from {} only in case", tag),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let grammar = NameGrammar::default();
+        assert_eq!(grammar.parse("garden,"), Some("garden"));
+        assert_eq!(grammar.parse("home."), Some("home"));
+        assert_eq!(grammar.parse("work.clientA"), Some("work.clientA"));
+        assert_eq!(grammar.parse("c++"), None);
+        assert_eq!(grammar.parse("!"), None);
+        assert!(!grammar.is_valid("garden,"));
+
+        let custom = NameGrammar {
+            extra_chars: "+".to_string(),
+            strip_trailing: String::new(),
+            ..NameGrammar::default()
+        };
+        assert_eq!(custom.parse("c++"), Some("c++"));
+        assert_eq!(custom.parse("garden,"), None);
+    }
+
+    #[test]
+    fn test_case_insensitive_matching() {
+        let mut grammar = NameGrammar::default();
+        assert!(!grammar.is_within("Work.api", "work"));
+        grammar.case_insensitive = true;
+        assert!(grammar.is_within("Work.api", "work"));
+        assert!(!grammar.is_within("Workshop", "work"));
+        assert_eq!(
+            grammar.renamed("Work.api", "work", "job").as_deref(),
+            Some("job.api")
+        );
+    }
+
+    #[test]
+    fn test_lint() {
+        let lints = NameGrammar::default().lint("Learn +c++\nCall @mom, +5 more\nPlan +Work +work");
+        let problems: Vec<(usize, &NameProblem)> =
+            lints.iter().map(|l| (l.line, &l.problem)).collect();
+        assert_eq!(
+            problems,
+            vec![
+                (1, &NameProblem::Invalid),
+                (2, &NameProblem::Stripped("@mom".to_string())),
+                (2, &NameProblem::NoLetters),
+                (3, &NameProblem::CaseVariant("+Work".to_string())),
+            ]
+        );
+        assert_eq!(lints[1].to_string(), "line 2: '@mom,' is read as @mom");
+    }
+}
@@ -0,0 +1,368 @@
+use crate::todo_context::TodoContext;
+use crate::todo_item::{self, TodoItem};
+use crate::todo_names::NameGrammar;
+use crate::todo_priority::TodoPriority;
+use crate::todo_project::TodoProject;
+use crate::todo_recurrence::TodoRecurrence;
+use chrono::{Local, NaiveDate, NaiveTime};
+use serde::{Deserialize, Deserializer};
+use uuid::Uuid;
+
+/// What to do with one field of an item.
+///
+/// Deserialized as a field of a struct with `#[serde(default)]`, a missing field keeps the
+/// value, `null` clears it and anything else sets it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum FieldPatch<T> {
+    #[default]
+    Keep,
+    Set(T),
+    Clear,
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for FieldPatch<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => FieldPatch::Set(value),
+            None => FieldPatch::Clear,
+        })
+    }
+}
+
+impl<T> FieldPatch<T> {
+    /// Applies the patch to `current`, converting a set value with `parse`.
+    fn apply<U, E>(
+        &self,
+        current: &mut Option<U>,
+        parse: impl FnOnce(&T) -> Result<U, E>,
+    ) -> Result<(), E> {
+        match self {
+            FieldPatch::Keep => {}
+            FieldPatch::Set(value) => *current = Some(parse(value)?),
+            FieldPatch::Clear => *current = None,
+        }
+        Ok(())
+    }
+}
+
+/// A change to some fields of an item, in the text form the fields have in todo.txt: dates
+/// as YYYY-MM-DD (`due` and `threshold` with an optional time, as in `2026-10-20T14:00`),
+/// the priority as a letter, recurrences like `+1w`, and project and context names without
+/// their sigil.
+///
+/// Values are only checked by [`TodoPatch::apply`], which reports every invalid one.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TodoPatch {
+    pub description: Option<String>,
+    pub done: Option<bool>,
+    pub priority: FieldPatch<String>,
+    pub creation_date: FieldPatch<String>,
+    pub completion_date: FieldPatch<String>,
+    pub due: FieldPatch<String>,
+    pub threshold: FieldPatch<String>,
+    pub recurrence: FieldPatch<String>,
+    pub projects: Option<Vec<String>>,
+    pub contexts: Option<Vec<String>>,
+    pub uuid: FieldPatch<String>,
+    pub sub: FieldPatch<String>,
+    /// The uuids of the items this one waits for.
+    pub deps: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[error("invalid {field} '{value}': {reason}")]
+pub struct TodoPatchError {
+    pub field: &'static str,
+    pub value: String,
+    pub reason: String,
+}
+
+impl TodoPatch {
+    /// [`Self::apply_with`] the default name grammar.
+    pub fn apply(&self, item: &TodoItem) -> Result<TodoItem, Vec<TodoPatchError>> {
+        self.apply_with(item, &NameGrammar::default())
+    }
+
+    /// The item with the patch applied, or every field that couldn't be, in which case
+    /// nothing has changed. The result is the item as it will load back from the file, so
+    /// projects and contexts typed in a new description are picked up. A patch whose line
+    /// wouldn't load back, or would load back with other fields, is refused rather than lost
+    /// or misread on the next load.
+    pub fn apply_with(
+        &self,
+        item: &TodoItem,
+        grammar: &NameGrammar,
+    ) -> Result<TodoItem, Vec<TodoPatchError>> {
+        let mut item = item.clone();
+        let mut errors = Vec::new();
+        let mut check = |result: Result<(), TodoPatchError>| {
+            if let Err(e) = result {
+                errors.push(e);
+            }
+        };
+
+        if let Some(ref description) = self.description {
+            check(if description.contains(['\n', '\r']) {
+                Err(error("description", description, "must be a single line"))
+            } else {
+                // Tags typed in the old description go with it, unless the patch lists the
+                // projects or contexts itself; those in the new one are read back below.
+                let old = item.clone();
+                if self.projects.is_none() {
+                    item.projects
+                        .retain(|p| !old.is_inline_with(&p.to_string(), grammar));
+                }
+                if self.contexts.is_none() {
+                    item.contexts
+                        .retain(|c| !old.is_inline_with(&c.to_string(), grammar));
+                }
+                item.description = description.clone();
+                Ok(())
+            });
+        }
+        if let Some(done) = self.done {
+            // As when completing in the app, unless the patch gives a completion date too.
+            if done && !item.done {
+                item.completion_date = Some(Local::now().date_naive());
+            } else if !done {
+                item.completion_date = None;
+            }
+            item.done = done;
+        }
+        match self.priority {
+            FieldPatch::Keep => {}
+            FieldPatch::Set(ref s) => check(
+                TodoPriority::from_tag_value(s)
+                    .map(|p| item.priority = p)
+                    .map_err(|e| error("priority", s, e)),
+            ),
+            FieldPatch::Clear => item.priority = TodoPriority { priority: None },
+        }
+        check(
+            self.creation_date
+                .apply(&mut item.creation_date, |s| parse_date("creation_date", s)),
+        );
+        check(self.completion_date.apply(&mut item.completion_date, |s| {
+            parse_date("completion_date", s)
+        }));
+        let mut due = item.due.map(|d| (d, item.due_time));
+        check(self.due.apply(&mut due, |s| parse_date_time("due", s)));
+        (item.due, item.due_time) = (due.map(|(d, _)| d), due.and_then(|(_, t)| t));
+        let mut threshold = item.threshold.map(|t| (t, item.threshold_time));
+        check(
+            self.threshold
+                .apply(&mut threshold, |s| parse_date_time("threshold", s)),
+        );
+        (item.threshold, item.threshold_time) = (
+            threshold.map(|(t, _)| t),
+            threshold.and_then(|(_, time)| time),
+        );
+        check(self.recurrence.apply(&mut item.recurrence, |s| {
+            s.parse::<TodoRecurrence>()
+                .map_err(|e| error("recurrence", s, e))
+        }));
+        if let Some(ref names) = self.projects {
+            let projects: Result<Vec<TodoProject>, _> = names
+                .iter()
+                .map(|name| {
+                    parse_name("project", '+', name, |s| {
+                        TodoProject::parse_with(s, grammar)
+                    })
+                })
+                .collect();
+            check(projects.map(|p| item = item.set_projects(p)));
+        }
+        if let Some(ref names) = self.contexts {
+            let contexts: Result<Vec<TodoContext>, _> = names
+                .iter()
+                .map(|name| {
+                    parse_name("context", '@', name, |s| {
+                        TodoContext::parse_with(s, grammar)
+                    })
+                })
+                .collect();
+            check(contexts.map(|c| item = item.set_contexts(c)));
+        }
+        check(self.uuid.apply(&mut item.uuid, |s| parse_uuid("uuid", s)));
+        check(self.sub.apply(&mut item.sub, |s| parse_uuid("sub", s)));
+        if let Some(ref deps) = self.deps {
+            let deps: Result<Vec<Uuid>, _> = deps.iter().map(|s| parse_uuid("dep", s)).collect();
+            check(deps.map(|d| item.deps = d));
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let line = item.to_string();
+        let reparsed = TodoItem::parse_with(&line, grammar)
+            .map_err(|e| vec![error("item", &line, format!("would not load back: {}", e))])?;
+        // Only the tags are re-read from the description. Text that reads as another field,
+        // like a leading "x " or "(A) ", would change the item on the next load.
+        let expected = TodoItem {
+            description: reparsed.description.clone(),
+            projects: reparsed.projects.clone(),
+            contexts: reparsed.contexts.clone(),
+            ..item
+        };
+        if expected != reparsed {
+            return Err(vec![error(
+                "item",
+                &line,
+                "would load back as a different item",
+            )]);
+        }
+        Ok(reparsed)
+    }
+}
+
+fn error(field: &'static str, value: &str, reason: impl ToString) -> TodoPatchError {
+    TodoPatchError {
+        field,
+        value: value.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn parse_date(field: &'static str, s: &str) -> Result<NaiveDate, TodoPatchError> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| error(field, s, e))
+}
+
+/// A date, or a date and time of day as in `2026-10-20T14:00`.
+fn parse_date_time(
+    field: &'static str,
+    s: &str,
+) -> Result<(NaiveDate, Option<NaiveTime>), TodoPatchError> {
+    todo_item::parse_date_time(s).map_err(|e| error(field, s, e))
+}
+
+fn parse_uuid(field: &'static str, s: &str) -> Result<Uuid, TodoPatchError> {
+    Uuid::parse_str(s).map_err(|e| error(field, s, e))
+}
+
+/// Parses a project or context name given without its sigil. Names can't contain
+/// whitespace, which would split them into several words in the file.
+fn parse_name<T, E: ToString>(
+    field: &'static str,
+    sigil: char,
+    name: &str,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<T, TodoPatchError> {
+    if name.contains(char::is_whitespace) {
+        return Err(error(field, name, "must not contain whitespace"));
+    }
+    parse(&format!("{}{}", sigil, name)).map_err(|e| error(field, name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patch(json: &str) -> TodoPatch {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_missing_keeps_null_clears() {
+        let patch = patch(r#"{"due": null, "priority": "C", "projects": ["home"]}"#);
+        assert_eq!(patch.due, FieldPatch::Clear);
+        assert_eq!(patch.threshold, FieldPatch::Keep);
+
+        let item: TodoItem = "(A) 2026-10-01 Paint fence +work due:2026-10-20 t:2026-10-15"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            patch.apply(&item).unwrap().to_string(),
+            "(C) 2026-10-01 Paint fence +home t:2026-10-15"
+        );
+    }
+
+    #[test]
+    fn test_sets_ids_and_dates() {
+        let patch = patch(
+            r#"{"creation_date": "2026-09-30", "recurrence": "+2w",
+                "uuid": "12345678-1234-1234-1234-123456789abc",
+                "sub": "87654321-4321-4321-4321-abc123456789"}"#,
+        );
+        let item: TodoItem = "Water plants".parse().unwrap();
+        let patched = patch.apply(&item).unwrap();
+        assert_eq!(patched.creation_date, NaiveDate::from_ymd_opt(2026, 9, 30));
+        assert_eq!(patched.recurrence.as_ref().unwrap().to_string(), "+2w");
+        assert!(patched.uuid.is_some() && patched.sub.is_some());
+
+        let timed = serde_json::from_str::<TodoPatch>(r#"{"due": "2026-10-20T14:00"}"#)
+            .unwrap()
+            .apply(&patched)
+            .unwrap();
+        assert_eq!(timed.due, NaiveDate::from_ymd_opt(2026, 10, 20));
+        assert_eq!(timed.due_time, NaiveTime::from_hms_opt(14, 0, 0));
+    }
+
+    #[test]
+    fn test_reports_every_error_and_changes_nothing() {
+        let patch = patch(
+            r#"{"description": "New", "due": "2026-13-01", "recurrence": "1x",
+                "contexts": ["ok", "two words"], "priority": "AB"}"#,
+        );
+        let item: TodoItem = "Old due:2026-10-20".parse().unwrap();
+        let errors = patch.apply(&item).unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field).collect();
+        assert_eq!(fields, vec!["priority", "due", "recurrence", "context"]);
+        assert_eq!(
+            errors[3].to_string(),
+            "invalid context 'two words': must not contain whitespace"
+        );
+    }
+
+    #[test]
+    fn test_result_loads_back() {
+        let item: TodoItem = "2026-10-01 Call @mom about +garden".parse().unwrap();
+        let patched = patch(r#"{"description": "Call dad @phone"}"#)
+            .apply(&item)
+            .unwrap();
+        assert_eq!(patched.to_string(), "2026-10-01 Call dad @phone");
+        assert_eq!(patched.contexts[0].name, "phone");
+        assert!(patched.projects.is_empty());
+        // Projects only listed after the description stay.
+        let listed = item.set_projects(vec!["+home".parse().unwrap()]);
+        let patched = patch(r#"{"description": "Call dad"}"#)
+            .apply(&listed)
+            .unwrap();
+        assert_eq!(patched.to_string(), "2026-10-01 Call dad +home");
+        // Listed projects win over the description, as when the app saves its edit dialog.
+        let patched = patch(r#"{"description": "Call @mom about +garden", "projects": []}"#)
+            .apply(&item)
+            .unwrap();
+        assert_eq!(patched.to_string(), "2026-10-01 Call @mom about");
+
+        let errors = patch(r#"{"description": "Water plants due:soon"}"#)
+            .apply(&item)
+            .unwrap_err();
+        assert_eq!(errors[0].field, "item");
+
+        // Without a creation date or priority in front, the description starts the line.
+        let bare: TodoItem = "Call Bob".parse().unwrap();
+        for description in ["x call Bob", "(A) call Bob", "2026-01-01 call Bob"] {
+            let errors = patch(&format!(r#"{{"description": "{}"}}"#, description))
+                .apply(&bare)
+                .unwrap_err();
+            assert_eq!(errors[0].field, "item", "{}", description);
+        }
+        let errors = patch(r#"{"description": "Call rec:+w"}"#)
+            .apply(&item)
+            .unwrap_err();
+        assert_eq!(errors[0].field, "item");
+
+        let done = patch(r#"{"done": true}"#).apply(&item).unwrap();
+        assert_eq!(done.completion_date, Some(Local::now().date_naive()));
+        let undone = patch(r#"{"done": false}"#).apply(&done).unwrap();
+        assert_eq!(undone.completion_date, None);
+        assert_eq!(undone, item);
+    }
+
+    #[test]
+    fn test_unknown_fields_are_rejected() {
+        assert!(serde_json::from_str::<TodoPatch>(r#"{"colour": "red"}"#).is_err());
+        assert!(serde_json::from_str::<TodoPatch>(r#"{"due": 3}"#).is_err());
+    }
+}
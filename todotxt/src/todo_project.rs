@@ -1,3 +1,5 @@
+use crate::todo_hierarchy;
+use crate::todo_names::NameGrammar;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
@@ -7,20 +9,38 @@ pub struct TodoProject {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
-#[error("project must start with '+' and be non-empty")]
+#[error("project must start with '+' followed by a valid name")]
 pub struct TodoProjectParseError;
 
 impl FromStr for TodoProject {
     type Err = TodoProjectParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with('+') && s.len() > 1 {
-            Ok(TodoProject {
-                name: s[1..].to_string(),
+        Self::parse_with(s, &NameGrammar::default())
+    }
+}
+
+impl TodoProject {
+    /// Parses `+name` with the names `grammar` allows, dropping trailing punctuation; see
+    /// [`NameGrammar`].
+    pub fn parse_with(s: &str, grammar: &NameGrammar) -> Result<Self, TodoProjectParseError> {
+        s.strip_prefix('+')
+            .and_then(|name| grammar.parse(name))
+            .map(|name| TodoProject {
+                name: name.to_string(),
             })
-        } else {
-            Err(TodoProjectParseError)
-        }
+            .ok_or(TodoProjectParseError)
+    }
+
+    /// Whether this is `ancestor` or within it, as `work.clientA` is within `work`; see
+    /// [`todo_hierarchy`].
+    pub fn is_within(&self, ancestor: &str) -> bool {
+        todo_hierarchy::is_within(&self.name, ancestor)
+    }
+
+    /// This with `from` renamed to `to`, if it is `from` or within it.
+    pub fn renamed(&self, from: &str, to: &str) -> Option<Self> {
+        todo_hierarchy::renamed(&self.name, from, to).map(|name| TodoProject { name })
     }
 }
 
@@ -102,4 +122,26 @@ mod tests {
         let p2 = p1.clone();
         assert_eq!(p1, p2);
     }
+
+    #[test]
+    fn hierarchy() {
+        let child = TodoProject::from_str("+work.clientA").unwrap();
+        assert!(child.is_within("work"));
+        assert!(!child.is_within("works"));
+        assert_eq!(child.renamed("work", "new").unwrap().name, "new.clientA");
+    }
+
+    #[test]
+    fn trailing_punctuation_and_invalid_names() {
+        assert_eq!(TodoProject::from_str("+garden,").unwrap().name, "garden");
+        assert!(TodoProject::from_str("+c++").is_err());
+        let grammar = NameGrammar {
+            extra_chars: "+".to_string(),
+            ..NameGrammar::default()
+        };
+        assert_eq!(
+            TodoProject::parse_with("+c++", &grammar).unwrap().name,
+            "c++"
+        );
+    }
 }
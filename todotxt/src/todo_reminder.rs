@@ -0,0 +1,302 @@
+//! When to remind about items: on the due date, some days before it and on the threshold
+//! date, at the time given by an item's `remind:HH:MM` tag, else the time of the date itself
+//! (`due:2026-10-20T14:00`), else a configured time of day.
+//!
+//! Nothing here keeps time or shows anything; a caller polls [`ReminderLog::pending`] and
+//! records what it has shown, so reminders aren't repeated across restarts.
+
+use crate::todo_item::TodoItem;
+use chrono::{Days, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReminderSettings {
+    /// Time of day for reminders of items without a `remind:` tag.
+    pub time: NaiveTime,
+    /// Also remind this many days before the due date.
+    pub days_before: Option<u32>,
+    /// Also remind when an item's threshold date arrives.
+    pub on_threshold: bool,
+}
+
+impl Default for ReminderSettings {
+    /// 9:00 on the due and threshold dates.
+    fn default() -> Self {
+        ReminderSettings {
+            time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            days_before: None,
+            on_threshold: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReminderKind {
+    Due,
+    /// Some days before the due date.
+    Upcoming,
+    Threshold,
+}
+
+impl Display for ReminderKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReminderKind::Due => write!(f, "due"),
+            ReminderKind::Upcoming => write!(f, "upcoming"),
+            ReminderKind::Threshold => write!(f, "threshold"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Reminder {
+    /// Identifies this reminder of this item for [`ReminderLog`]: the item's
+    /// [`stable_uuid`](TodoItem::stable_uuid), the kind and the date it is about.
+    pub key: String,
+    pub kind: ReminderKind,
+    pub at: NaiveDateTime,
+    /// The due or threshold date the reminder is about.
+    pub date: NaiveDate,
+    pub uuid: Uuid,
+    pub description: String,
+}
+
+impl Reminder {
+    /// A notification title, e.g. "Due in 2 days".
+    pub fn title(&self) -> String {
+        match self.kind {
+            ReminderKind::Due => "Due today".to_string(),
+            ReminderKind::Upcoming => match (self.date - self.at.date()).num_days() {
+                1 => "Due tomorrow".to_string(),
+                days => format!("Due in {} days", days),
+            },
+            ReminderKind::Threshold => "Starting today".to_string(),
+        }
+    }
+}
+
+/// The time from the item's `remind:HH:MM` tag, which stays part of the description.
+pub fn remind_time(item: &TodoItem) -> Option<NaiveTime> {
+    item.description
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("remind:"))
+        .and_then(|time| NaiveTime::parse_from_str(time, "%H:%M").ok())
+}
+
+/// Every reminder of an open item, whether or not its time has come.
+pub fn item_reminders(item: &TodoItem, settings: &ReminderSettings) -> Vec<Reminder> {
+    if item.done {
+        return Vec::new();
+    }
+    let tagged = remind_time(item);
+    let uuid = item.stable_uuid();
+    let reminder = |kind: ReminderKind, date: NaiveDate, at: NaiveDateTime| Reminder {
+        key: format!("{}:{}:{}", uuid, kind, date.format("%Y-%m-%d")),
+        kind,
+        at,
+        date,
+        uuid,
+        description: item.description.clone(),
+    };
+
+    let mut reminders = Vec::new();
+    if let Some(due) = item.due {
+        let time = tagged.or(item.due_time).unwrap_or(settings.time);
+        reminders.push(reminder(ReminderKind::Due, due, due.and_time(time)));
+        // A reminder that would fall before the earliest representable date is left out.
+        if let Some(on) = settings
+            .days_before
+            .filter(|&d| d > 0)
+            .and_then(|days| due.checked_sub_days(Days::new(days.into())))
+        {
+            let time = tagged.unwrap_or(settings.time);
+            reminders.push(reminder(ReminderKind::Upcoming, due, on.and_time(time)));
+        }
+    }
+    if let Some(threshold) = item.threshold.filter(|_| settings.on_threshold) {
+        let time = tagged.or(item.threshold_time).unwrap_or(settings.time);
+        reminders.push(reminder(
+            ReminderKind::Threshold,
+            threshold,
+            threshold.and_time(time),
+        ));
+    }
+    reminders
+}
+
+/// The reminders already shown and the ones snoozed, by key. Serialized, this is what a
+/// caller persists between runs.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ReminderLog {
+    #[serde(default)]
+    pub notified: BTreeMap<String, NaiveDateTime>,
+    /// When each snoozed reminder is due again.
+    #[serde(default)]
+    pub snoozed: BTreeMap<String, NaiveDateTime>,
+}
+
+impl ReminderLog {
+    /// The reminders to show at `now`: snoozed ones whose snooze is over, and ones whose time
+    /// has come today and that haven't been shown yet. Reminders of earlier days that were
+    /// missed, say because nothing was running, are not caught up on.
+    pub fn pending(
+        &self,
+        items: &[TodoItem],
+        settings: &ReminderSettings,
+        now: NaiveDateTime,
+    ) -> Vec<Reminder> {
+        items
+            .iter()
+            .flat_map(|item| item_reminders(item, settings))
+            .filter(|r| match self.snoozed.get(&r.key) {
+                Some(&until) => until <= now,
+                None => {
+                    !self.notified.contains_key(&r.key) && r.at <= now && r.at.date() == now.date()
+                }
+            })
+            .collect()
+    }
+
+    pub fn mark_notified(&mut self, key: &str, now: NaiveDateTime) {
+        self.snoozed.remove(key);
+        self.notified.insert(key.to_string(), now);
+    }
+
+    /// Shows the reminder again at `until`.
+    pub fn snooze(&mut self, key: &str, until: NaiveDateTime) {
+        self.snoozed.insert(key.to_string(), until);
+    }
+
+    /// Forgets reminders shown, or snoozed until, before `before`, so the log doesn't grow
+    /// forever.
+    pub fn prune(&mut self, before: NaiveDateTime) {
+        self.notified.retain(|_, &mut at| at >= before);
+        self.snoozed.retain(|_, &mut until| until >= before);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn items() -> Vec<TodoItem> {
+        [
+            "Pay rent due:2026-10-20",
+            "Call dentist remind:14:30 due:2026-10-18",
+            "Paint fence t:2026-10-18",
+            "x Done already due:2026-10-18",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect()
+    }
+
+    fn titles(reminders: &[Reminder]) -> Vec<(String, String)> {
+        reminders
+            .iter()
+            .map(|r| (r.title(), r.description.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_item_reminders() {
+        let settings = ReminderSettings {
+            days_before: Some(2),
+            ..Default::default()
+        };
+        let reminders = item_reminders(&items()[0], &settings);
+        assert_eq!(reminders.len(), 2);
+        assert_eq!(reminders[0].at, at("2026-10-20 09:00"));
+        assert_eq!(reminders[1].at, at("2026-10-18 09:00"));
+        assert_eq!(reminders[1].title(), "Due in 2 days");
+        assert!(reminders[1].key.ends_with(":upcoming:2026-10-20"));
+
+        let tagged = item_reminders(&items()[1], &settings);
+        assert_eq!(tagged[0].at, at("2026-10-18 14:30"));
+        let timed: TodoItem = "Deploy due:2026-10-20T16:00".parse().unwrap();
+        assert_eq!(
+            item_reminders(&timed, &settings)[0].at,
+            at("2026-10-20 16:00")
+        );
+        assert!(item_reminders(&items()[3], &settings).is_empty());
+
+        // Too many days before to represent: only the due date is reminded of.
+        let settings = ReminderSettings {
+            days_before: Some(u32::MAX),
+            ..Default::default()
+        };
+        let reminders = item_reminders(&items()[0], &settings);
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].kind, ReminderKind::Due);
+    }
+
+    #[test]
+    fn test_pending_and_notified() {
+        let settings = ReminderSettings {
+            days_before: Some(2),
+            ..Default::default()
+        };
+        let mut log = ReminderLog::default();
+        let now = at("2026-10-18 10:00");
+        let pending = log.pending(&items(), &settings, now);
+        assert_eq!(
+            titles(&pending),
+            vec![
+                ("Due in 2 days".to_string(), "Pay rent".to_string()),
+                ("Starting today".to_string(), "Paint fence".to_string()),
+            ]
+        );
+
+        for reminder in &pending {
+            log.mark_notified(&reminder.key, now);
+        }
+        assert!(log.pending(&items(), &settings, now).is_empty());
+        let later = log.pending(&items(), &settings, at("2026-10-18 15:00"));
+        assert_eq!(titles(&later)[0].0, "Due today");
+
+        // Yesterday's missed reminders are not caught up on.
+        assert!(
+            ReminderLog::default()
+                .pending(&items(), &settings, at("2026-10-21 08:00"))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_snooze_and_prune() {
+        let settings = ReminderSettings::default();
+        let mut log = ReminderLog::default();
+        let now = at("2026-10-18 10:00");
+        let key = log.pending(&items(), &settings, now)[0].key.clone();
+        log.mark_notified(&key, now);
+        log.snooze(&key, at("2026-10-18 10:15"));
+        assert!(log.pending(&items(), &settings, now).is_empty());
+        assert_eq!(
+            log.pending(&items(), &settings, at("2026-10-18 10:15"))[0].key,
+            key
+        );
+
+        log.prune(at("2026-10-19 00:00"));
+        assert_eq!(log, ReminderLog::default());
+    }
+
+    #[test]
+    fn test_log_round_trips_through_json() {
+        let mut log = ReminderLog::default();
+        log.mark_notified("a:due:2026-10-18", at("2026-10-18 09:00"));
+        let json = serde_json::to_string(&log).unwrap();
+        assert_eq!(serde_json::from_str::<ReminderLog>(&json).unwrap(), log);
+        assert_eq!(
+            serde_json::from_str::<ReminderLog>("{}").unwrap(),
+            ReminderLog::default()
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use crate::todo_item::TodoItem;
+use crate::todo_names::NameGrammar;
+use chrono::NaiveTime;
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ReportFormat {
+    /// Markdown task-list checkboxes under `##` headings.
+    #[default]
+    Markdown,
+    /// Org-mode TODO/DONE headlines with DEADLINE, SCHEDULED and CLOSED planning lines.
+    Org,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ReportGrouping {
+    #[default]
+    Project,
+    Context,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReportOptions {
+    pub format: ReportFormat,
+    pub group_by: ReportGrouping,
+    /// Rendered as the document title when set.
+    pub title: Option<String>,
+    /// Also show completed items, with their completion dates.
+    pub include_done: bool,
+    /// When non-empty, only items in at least one of these projects or their subprojects
+    /// (names without `+`).
+    pub projects: Vec<String>,
+    /// When non-empty, only items in at least one of these contexts or the contexts within
+    /// them (names without `@`).
+    pub contexts: Vec<String>,
+    /// How the `projects` and `contexts` filters match names, e.g. ignoring case.
+    pub grammar: NameGrammar,
+}
+
+/// Heading for items that have no project (or context, when grouping by context).
+const UNGROUPED_HEADING: &str = "Other";
+
+/// Renders items as a status report, with one section per project or context in name
+/// order. An item in several projects appears in each of their sections; items in none
+/// come last, under "Other". Within a section, subtasks are nested beneath their `sub:`
+/// parent when the parent is in the same section too.
+pub fn render_report(items: &[TodoItem], options: &ReportOptions) -> String {
+    let selected: Vec<&TodoItem> = items
+        .iter()
+        .filter(|item| options.include_done || !item.done)
+        .filter(|item| {
+            options.projects.is_empty()
+                || item.projects.iter().any(|p| {
+                    options
+                        .projects
+                        .iter()
+                        .any(|name| options.grammar.is_within(&p.name, name))
+                })
+        })
+        .filter(|item| {
+            options.contexts.is_empty()
+                || item.contexts.iter().any(|c| {
+                    options
+                        .contexts
+                        .iter()
+                        .any(|name| options.grammar.is_within(&c.name, name))
+                })
+        })
+        .collect();
+
+    let mut groups: BTreeMap<String, Vec<&TodoItem>> = BTreeMap::new();
+    let mut ungrouped: Vec<&TodoItem> = Vec::new();
+    for item in &selected {
+        let keys = group_keys(item, options.group_by);
+        if keys.is_empty() {
+            ungrouped.push(item);
+        }
+        for key in keys {
+            groups.entry(key).or_default().push(item);
+        }
+    }
+
+    let mut out = String::new();
+    if let Some(ref title) = options.title {
+        match options.format {
+            ReportFormat::Markdown => out.push_str(&format!("# {}\n\n", title)),
+            ReportFormat::Org => out.push_str(&format!("#+TITLE: {}\n\n", title)),
+        }
+    }
+
+    let sections = groups
+        .iter()
+        .map(|(name, items)| (name.clone(), items))
+        .chain((!ungrouped.is_empty()).then(|| (UNGROUPED_HEADING.to_string(), &ungrouped)));
+    for (heading, items) in sections {
+        match options.format {
+            ReportFormat::Markdown => out.push_str(&format!("## {}\n\n", heading)),
+            ReportFormat::Org => out.push_str(&format!("* {}\n", heading)),
+        }
+        let in_section: HashSet<Uuid> = items.iter().filter_map(|i| i.uuid).collect();
+        let mut rendered = HashSet::new();
+        for (index, item) in items.iter().enumerate() {
+            // Roots are items whose parent isn't rendered in this section.
+            if item.sub.is_none_or(|parent| !in_section.contains(&parent)) {
+                render_tree(&mut out, index, items, 0, options, &mut rendered);
+            }
+        }
+        // Items caught in a sub: cycle have no root to hang from; show them rather than
+        // silently leave them out of the report.
+        for index in 0..items.len() {
+            render_tree(&mut out, index, items, 0, options, &mut rendered);
+        }
+        if options.format == ReportFormat::Markdown {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn group_keys(item: &TodoItem, grouping: ReportGrouping) -> Vec<String> {
+    match grouping {
+        ReportGrouping::Project => item.projects.iter().map(|p| p.to_string()).collect(),
+        ReportGrouping::Context => item.contexts.iter().map(|c| c.to_string()).collect(),
+    }
+}
+
+/// Renders `section[index]` and, nested beneath it, its subtasks. `rendered` holds the
+/// indices already output, so each item appears once per section even if `sub:` links
+/// form a cycle.
+fn render_tree(
+    out: &mut String,
+    index: usize,
+    section: &[&TodoItem],
+    depth: usize,
+    options: &ReportOptions,
+    rendered: &mut HashSet<usize>,
+) {
+    if !rendered.insert(index) {
+        return;
+    }
+    let item = section[index];
+    match options.format {
+        ReportFormat::Markdown => render_markdown_item(out, item, depth, options),
+        ReportFormat::Org => render_org_item(out, item, depth, options),
+    }
+    if let Some(uuid) = item.uuid {
+        for (child, _) in section
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.sub == Some(uuid))
+        {
+            render_tree(out, child, section, depth + 1, options, rendered);
+        }
+    }
+}
+
+/// The tags of the dimension the report is *not* grouped by, e.g. contexts in a report
+/// grouped by project.
+fn secondary_tags(item: &TodoItem, grouping: ReportGrouping) -> Vec<String> {
+    match grouping {
+        ReportGrouping::Project => item.contexts.iter().map(|c| c.to_string()).collect(),
+        ReportGrouping::Context => item.projects.iter().map(|p| p.to_string()).collect(),
+    }
+}
+
+fn render_markdown_item(out: &mut String, item: &TodoItem, depth: usize, options: &ReportOptions) {
+    let mut text = Vec::new();
+    if !item.done && item.priority.priority.is_some() {
+        text.push(item.priority.to_string());
+    }
+    text.push(item.plain_description_with(&options.grammar));
+    text.extend(secondary_tags(item, options.group_by));
+    if let Some(due) = item.due {
+        text.push(format!(
+            "(due {}{})",
+            due.format("%Y-%m-%d"),
+            time_suffix(item.due_time)
+        ));
+    }
+    if item.done
+        && let Some(completed) = item.completion_date
+    {
+        text.push(format!("(done {})", completed.format("%Y-%m-%d")));
+    }
+    out.push_str(&format!(
+        "{}- [{}] {}\n",
+        "  ".repeat(depth),
+        if item.done { "x" } else { " " },
+        text.join(" ")
+    ));
+}
+
+/// " 14:00" for a time of day, to follow a date in either format.
+fn time_suffix(time: Option<NaiveTime>) -> String {
+    time.map(|t| t.format(" %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn render_org_item(out: &mut String, item: &TodoItem, depth: usize, options: &ReportOptions) {
+    let stars = "*".repeat(depth + 2);
+    let mut headline = format!("{} {}", stars, if item.done { "DONE" } else { "TODO" });
+    if let Some(p) = item.priority.priority {
+        headline.push_str(&format!(" [#{}]", (p + b'A') as char));
+    }
+    headline.push(' ');
+    headline.push_str(&item.plain_description_with(&options.grammar));
+    let tags: Vec<String> = secondary_tags(item, options.group_by)
+        .iter()
+        .map(|t| org_tag(t))
+        .collect();
+    if !tags.is_empty() {
+        headline.push_str(&format!(" :{}:", tags.join(":")));
+    }
+    out.push_str(&headline);
+    out.push('\n');
+
+    let mut planning = Vec::new();
+    if item.done
+        && let Some(completed) = item.completion_date
+    {
+        planning.push(format!("CLOSED: [{}]", completed.format("%Y-%m-%d %a")));
+    }
+    if let Some(due) = item.due {
+        planning.push(format!(
+            "DEADLINE: <{}{}>",
+            due.format("%Y-%m-%d %a"),
+            time_suffix(item.due_time)
+        ));
+    }
+    if let Some(threshold) = item.threshold {
+        planning.push(format!(
+            "SCHEDULED: <{}{}>",
+            threshold.format("%Y-%m-%d %a"),
+            time_suffix(item.threshold_time)
+        ));
+    }
+    if !planning.is_empty() {
+        out.push_str(&format!(
+            "{} {}\n",
+            " ".repeat(stars.len()),
+            planning.join(" ")
+        ));
+    }
+}
+
+/// Org tags may only contain letters, digits, `_`, `@`, `#` and `%`. A project's `+` is
+/// dropped and anything else becomes `_`.
+fn org_tag(tag: &str) -> String {
+    tag.trim_start_matches('+')
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '_' | '@' | '#' | '%') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<TodoItem> {
+        [
+            "(A) Plan release +work @office due:2026-10-20 uuid:67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "Write notes +work sub:67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "x 2026-10-02 2026-10-01 Book venue +work +party",
+            "Buy milk @store",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_markdown_by_project() {
+        let options = ReportOptions {
+            title: Some("Weekly".to_string()),
+            include_done: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_report(&items(), &options),
+            "# Weekly\n\n\
+             ## +party\n\n\
+             - [x] Book venue (done 2026-10-02)\n\n\
+             ## +work\n\n\
+             - [ ] (A) Plan release @office (due 2026-10-20)\n  \
+             - [ ] Write notes\n\
+             - [x] Book venue (done 2026-10-02)\n\n\
+             ## Other\n\n\
+             - [ ] Buy milk @store\n\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_hides_done_by_default_and_filters() {
+        let options = ReportOptions {
+            group_by: ReportGrouping::Context,
+            contexts: vec!["office".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            render_report(&items(), &options),
+            "## @office\n\n- [ ] (A) Plan release +work (due 2026-10-20)\n\n"
+        );
+    }
+
+    #[test]
+    fn test_org_by_project() {
+        let options = ReportOptions {
+            format: ReportFormat::Org,
+            include_done: true,
+            projects: vec!["work".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            render_report(&items(), &options),
+            "* +party\n\
+             ** DONE Book venue\n   CLOSED: [2026-10-02 Fri]\n\
+             * +work\n\
+             ** TODO [#A] Plan release :@office:\n   DEADLINE: <2026-10-20 Tue>\n\
+             *** TODO Write notes\n\
+             ** DONE Book venue\n   CLOSED: [2026-10-02 Fri]\n"
+        );
+    }
+
+    #[test]
+    fn test_filters_follow_the_grammar() {
+        let mut options = ReportOptions {
+            projects: vec!["WORK".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(render_report(&items(), &options), "");
+        options.grammar.case_insensitive = true;
+        assert!(render_report(&items(), &options).starts_with("## +work\n\n"));
+    }
+
+    #[test]
+    fn test_sub_cycle_is_rendered_once() {
+        let items: Vec<TodoItem> = [
+            "A +p uuid:67e55044-10b1-426f-9247-bb680e5fe0c8 sub:0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d",
+            "B +p uuid:0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d sub:67e55044-10b1-426f-9247-bb680e5fe0c8",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+        assert_eq!(
+            render_report(&items, &ReportOptions::default()),
+            "## +p\n\n- [ ] A\n  - [ ] B\n\n"
+        );
+    }
+}
@@ -0,0 +1,515 @@
+use crate::todo_hierarchy;
+use crate::todo_item::TodoItem;
+use chrono::{Datelike, Days, Duration, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Formatter};
+
+/// The bucket size of [`TodoStats::throughput`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsPeriod {
+    #[default]
+    Day,
+    /// ISO weeks, starting on Monday.
+    Week,
+}
+
+/// The most periods worth asking for: a century of days. Much larger counts would step
+/// back past the earliest date chrono can represent.
+pub const MAX_PERIODS: usize = 36_500;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatsOptions {
+    /// Reference time for overdue counts; the last throughput bucket is the one containing
+    /// its date.
+    pub now: NaiveDateTime,
+    pub period: StatsPeriod,
+    /// How many buckets of throughput to report, ending with the current one.
+    pub periods: usize,
+}
+
+impl StatsOptions {
+    /// The last two weeks, day by day.
+    pub fn new(now: NaiveDateTime) -> Self {
+        StatsOptions {
+            now,
+            period: StatsPeriod::Day,
+            periods: 14,
+        }
+    }
+}
+
+/// Items created and completed in one day or week.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PeriodStats {
+    pub start: NaiveDate,
+    pub created: usize,
+    pub completed: usize,
+}
+
+/// Counts for a single project or context, including the items of its subprojects or
+/// subcontexts; see [`todo_hierarchy`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct GroupStats {
+    pub name: String,
+    /// 0 for a top-level name, 1 for `work.clientA` and so on.
+    pub depth: usize,
+    pub open: usize,
+    pub done: usize,
+    pub overdue: usize,
+}
+
+/// How many open items have a given priority; `None` counts items without one.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PriorityStats {
+    pub priority: Option<char>,
+    pub open: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TodoStats {
+    pub period: StatsPeriod,
+    pub open: usize,
+    pub done: usize,
+    /// Open items whose due date and time, if any, have passed; see [`TodoItem::is_overdue`].
+    pub overdue: usize,
+    /// Open items due later today.
+    pub due_today: usize,
+    /// Mean days from creation to completion, over done items that have both dates.
+    pub average_lead_time_days: Option<f64>,
+    pub throughput: Vec<PeriodStats>,
+    pub projects: Vec<GroupStats>,
+    pub contexts: Vec<GroupStats>,
+    pub priorities: Vec<PriorityStats>,
+}
+
+/// Computes statistics over `items`, which would usually be the contents of todo.txt and
+/// done.txt together so that archived completions count towards throughput.
+pub fn compute_stats(items: &[TodoItem], options: &StatsOptions) -> TodoStats {
+    let today = options.now.date();
+    let is_overdue = |item: &TodoItem| item.is_overdue(options.now);
+
+    let current = period_start(today, options.period);
+    let mut throughput: Vec<PeriodStats> = (0..options.periods)
+        .rev()
+        .map(|n| PeriodStats {
+            start: step_back(current, options.period, n),
+            created: 0,
+            completed: 0,
+        })
+        .collect();
+    let bucket = |date: NaiveDate| {
+        let start = period_start(date, options.period);
+        throughput.iter().position(|p| p.start == start)
+    };
+    let created: Vec<usize> = items
+        .iter()
+        .filter_map(|i| i.creation_date.and_then(bucket))
+        .collect();
+    let completed: Vec<usize> = items
+        .iter()
+        .filter(|i| i.done)
+        .filter_map(|i| i.completion_date.and_then(bucket))
+        .collect();
+    for index in created {
+        throughput[index].created += 1;
+    }
+    for index in completed {
+        throughput[index].completed += 1;
+    }
+
+    let lead_times: Vec<i64> = items
+        .iter()
+        .filter(|i| i.done)
+        .filter_map(|i| Some((i.completion_date? - i.creation_date?).num_days()))
+        .collect();
+    let average_lead_time_days = (!lead_times.is_empty())
+        .then(|| lead_times.iter().sum::<i64>() as f64 / lead_times.len() as f64);
+
+    let mut projects: BTreeMap<String, GroupStats> = BTreeMap::new();
+    let mut contexts: BTreeMap<String, GroupStats> = BTreeMap::new();
+    let mut priorities: BTreeMap<Option<u8>, usize> = BTreeMap::new();
+    for item in items {
+        let overdue = is_overdue(item);
+        // Each item counts once towards each of its projects and their parents.
+        let project_names: BTreeSet<&str> = item
+            .projects
+            .iter()
+            .flat_map(|p| todo_hierarchy::ancestors(&p.name))
+            .collect();
+        for name in project_names {
+            tally(&mut projects, name, item.done, overdue);
+        }
+        let context_names: BTreeSet<&str> = item
+            .contexts
+            .iter()
+            .flat_map(|c| todo_hierarchy::ancestors(&c.name))
+            .collect();
+        for name in context_names {
+            tally(&mut contexts, name, item.done, overdue);
+        }
+        if !item.done {
+            *priorities.entry(item.priority.priority).or_default() += 1;
+        }
+    }
+    // Highest priority first, unprioritised items last.
+    let mut priorities: Vec<PriorityStats> = priorities
+        .into_iter()
+        .map(|(p, open)| PriorityStats {
+            priority: p.map(|p| (p + b'A') as char),
+            open,
+        })
+        .collect();
+    priorities.sort_by_key(|p| p.priority.is_none());
+
+    TodoStats {
+        period: options.period,
+        open: items.iter().filter(|i| !i.done).count(),
+        done: items.iter().filter(|i| i.done).count(),
+        overdue: items.iter().filter(|i| is_overdue(i)).count(),
+        due_today: items
+            .iter()
+            .filter(|i| !i.done && i.due == Some(today) && !is_overdue(i))
+            .count(),
+        average_lead_time_days,
+        throughput,
+        projects: as_tree(projects),
+        contexts: as_tree(contexts),
+        priorities,
+    }
+}
+
+/// The state of a set of items at the end of one day.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct FlowPoint {
+    pub date: NaiveDate,
+    /// Items created by this date and not yet completed.
+    pub open: usize,
+    /// Items completed by this date.
+    pub done: usize,
+}
+
+/// Open and done counts for every day from `from` to `to`, for burndown and cumulative-flow
+/// charts. Items without a creation date count as open from the start; done items without a
+/// completion date are left out, as there's no telling when they left the open count.
+pub fn cumulative_flow(items: &[TodoItem], from: NaiveDate, to: NaiveDate) -> Vec<FlowPoint> {
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| {
+            let created = |item: &&TodoItem| item.creation_date.is_none_or(|c| c <= date);
+            let completed =
+                |item: &TodoItem| item.done && item.completion_date.is_some_and(|c| c <= date);
+            let existing: Vec<&TodoItem> = items
+                .iter()
+                .filter(|i| !i.done || i.completion_date.is_some())
+                .filter(created)
+                .collect();
+            let done = existing.iter().filter(|i| completed(i)).count();
+            FlowPoint {
+                date,
+                open: existing.len() - done,
+                done,
+            }
+        })
+        .collect()
+}
+
+/// The groups in tree order, each right before its children.
+fn as_tree(groups: BTreeMap<String, GroupStats>) -> Vec<GroupStats> {
+    let mut groups: Vec<GroupStats> = groups.into_values().collect();
+    groups
+        .sort_by(|a, b| todo_hierarchy::sort_key(&a.name).cmp(&todo_hierarchy::sort_key(&b.name)));
+    groups
+}
+
+fn tally(groups: &mut BTreeMap<String, GroupStats>, name: &str, done: bool, overdue: bool) {
+    let group = groups
+        .entry(name.to_string())
+        .or_insert_with(|| GroupStats {
+            name: name.to_string(),
+            depth: todo_hierarchy::depth(name),
+            open: 0,
+            done: 0,
+            overdue: 0,
+        });
+    if done {
+        group.done += 1;
+    } else {
+        group.open += 1;
+    }
+    if overdue {
+        group.overdue += 1;
+    }
+}
+
+fn period_start(date: NaiveDate, period: StatsPeriod) -> NaiveDate {
+    match period {
+        StatsPeriod::Day => date,
+        StatsPeriod::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+    }
+}
+
+/// `n` periods before `start`, or the earliest date there is if that's out of range.
+fn step_back(start: NaiveDate, period: StatsPeriod, n: usize) -> NaiveDate {
+    let days = match period {
+        StatsPeriod::Day => n as u64,
+        StatsPeriod::Week => (n as u64).saturating_mul(7),
+    };
+    start
+        .checked_sub_days(Days::new(days))
+        .unwrap_or(NaiveDate::MIN)
+}
+
+/// A plain-text summary, as printed by `rtmcli report`.
+impl Display for TodoStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} open, {} done, {} overdue, {} due today",
+            self.open, self.done, self.overdue, self.due_today
+        )?;
+        match self.average_lead_time_days {
+            Some(days) => writeln!(f, "Average lead time: {:.1} days", days)?,
+            None => writeln!(f, "Average lead time: -")?,
+        }
+
+        let heading = match self.period {
+            StatsPeriod::Day => "Day",
+            StatsPeriod::Week => "Week of",
+        };
+        writeln!(f, "\n{:<12} {:>7} {:>9}", heading, "created", "completed")?;
+        for p in &self.throughput {
+            writeln!(
+                f,
+                "{:<12} {:>7} {:>9}",
+                p.start.format("%Y-%m-%d").to_string(),
+                p.created,
+                p.completed
+            )?;
+        }
+
+        for (title, sigil, groups) in [
+            ("Projects", '+', &self.projects),
+            ("Contexts", '@', &self.contexts),
+        ] {
+            if groups.is_empty() {
+                continue;
+            }
+            let width = groups
+                .iter()
+                .map(|g| 2 * g.depth + g.name.chars().count() + 1)
+                .max()
+                .unwrap_or(0)
+                .max(title.len());
+            writeln!(
+                f,
+                "\n{:<width$} {:>5} {:>5} {:>8}",
+                title, "open", "done", "overdue"
+            )?;
+            for g in groups {
+                writeln!(
+                    f,
+                    "{:<width$} {:>5} {:>5} {:>8}",
+                    format!("{}{}{}", "  ".repeat(g.depth), sigil, g.name),
+                    g.open,
+                    g.done,
+                    g.overdue
+                )?;
+            }
+        }
+
+        if !self.priorities.is_empty() {
+            writeln!(f, "\nPriority  open")?;
+            for p in &self.priorities {
+                let label = p
+                    .priority
+                    .map_or("none".to_string(), |c| format!("({})", c));
+                writeln!(f, "{:<8} {:>5}", label, p.open)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn noon(s: &str) -> NaiveDateTime {
+        date(s).and_hms_opt(12, 0, 0).unwrap()
+    }
+
+    fn items() -> Vec<TodoItem> {
+        [
+            "(A) 2026-10-16 Plan release +work @office due:2026-10-17",
+            "2026-10-18 Write notes +work due:2026-10-18",
+            "(A) Call plumber @phone",
+            "Buy milk @store",
+            "x 2026-10-17 2026-10-15 Book venue +work +party",
+            "x 2026-10-18 2026-10-18 Water plants",
+            "x 2026-09-01 Old task",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_counts_and_lead_time() {
+        let options = StatsOptions {
+            periods: 3,
+            ..StatsOptions::new(noon("2026-10-18"))
+        };
+        let stats = compute_stats(&items(), &options);
+        assert_eq!(stats.open, 4);
+        assert_eq!(stats.done, 3);
+        assert_eq!(stats.overdue, 1);
+        assert_eq!(stats.due_today, 1);
+        // (2 + 0) / 2: the old task has no creation date.
+        assert_eq!(stats.average_lead_time_days, Some(1.0));
+        assert_eq!(
+            stats.throughput,
+            vec![
+                PeriodStats {
+                    start: date("2026-10-16"),
+                    created: 1,
+                    completed: 0
+                },
+                PeriodStats {
+                    start: date("2026-10-17"),
+                    created: 0,
+                    completed: 1
+                },
+                PeriodStats {
+                    start: date("2026-10-18"),
+                    created: 2,
+                    completed: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_timed_due_dates_are_overdue_once_passed() {
+        let items: Vec<TodoItem> = [
+            "Standup due:2026-10-18T09:00",
+            "Review due:2026-10-18T17:00",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+        let stats = compute_stats(&items, &StatsOptions::new(noon("2026-10-18")));
+        assert_eq!((stats.overdue, stats.due_today), (1, 1));
+    }
+
+    #[test]
+    fn test_project_tree_rolls_up() {
+        let items: Vec<TodoItem> = [
+            "Migrate db +work.clientA.migration",
+            "Invoice +work.clientA +work",
+            "Tidy desk +work-space",
+            "x 2026-10-18 Fix shelf @home:garage",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+        let stats = compute_stats(&items, &StatsOptions::new(noon("2026-10-18")));
+        let tree: Vec<(&str, usize, usize)> = stats
+            .projects
+            .iter()
+            .map(|g| (g.name.as_str(), g.depth, g.open))
+            .collect();
+        assert_eq!(
+            tree,
+            vec![
+                ("work", 0, 2),
+                ("work.clientA", 1, 2),
+                ("work.clientA.migration", 2, 1),
+                ("work-space", 0, 1),
+            ]
+        );
+        assert_eq!(stats.contexts[0].name, "home");
+        assert_eq!(stats.contexts[0].done, 1);
+        assert!(stats.to_string().contains("\n  @home:garage "));
+    }
+
+    #[test]
+    fn test_weekly_throughput() {
+        let options = StatsOptions {
+            period: StatsPeriod::Week,
+            periods: 2,
+            ..StatsOptions::new(noon("2026-10-18"))
+        };
+        let stats = compute_stats(&items(), &options);
+        assert_eq!(stats.throughput[0].start, date("2026-10-05"));
+        assert_eq!(stats.throughput[1].start, date("2026-10-12"));
+        assert_eq!(stats.throughput[1].created, 4);
+        assert_eq!(stats.throughput[1].completed, 2);
+    }
+
+    #[test]
+    fn test_breakdowns() {
+        let stats = compute_stats(&items(), &StatsOptions::new(noon("2026-10-18")));
+        assert_eq!(
+            stats.projects,
+            vec![
+                GroupStats {
+                    name: "party".to_string(),
+                    depth: 0,
+                    open: 0,
+                    done: 1,
+                    overdue: 0
+                },
+                GroupStats {
+                    name: "work".to_string(),
+                    depth: 0,
+                    open: 2,
+                    done: 1,
+                    overdue: 1
+                },
+            ]
+        );
+        assert_eq!(stats.contexts.len(), 3);
+        assert_eq!(
+            stats.priorities,
+            vec![
+                PriorityStats {
+                    priority: Some('A'),
+                    open: 2
+                },
+                PriorityStats {
+                    priority: None,
+                    open: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cumulative_flow() {
+        let points = cumulative_flow(&items(), date("2026-10-15"), date("2026-10-18"));
+        let counts: Vec<(usize, usize)> = points.iter().map(|p| (p.open, p.done)).collect();
+        // The two open items without a creation date are there from the start.
+        assert_eq!(counts, vec![(3, 1), (4, 1), (3, 2), (4, 3)]);
+        assert_eq!(points[3].date, date("2026-10-18"));
+    }
+
+    #[test]
+    fn test_text_output() {
+        let options = StatsOptions {
+            periods: 1,
+            ..StatsOptions::new(noon("2026-10-18"))
+        };
+        let text = compute_stats(&items(), &options).to_string();
+        assert!(text.starts_with("4 open, 3 done, 1 overdue, 1 due today\n"));
+        assert!(text.contains("Average lead time: 1.0 days\n"));
+        assert!(text.contains("2026-10-18         2         1\n"));
+        assert!(text.contains("+work        2     1        1\n"));
+        assert!(text.contains("(A)          2\n"));
+    }
+}
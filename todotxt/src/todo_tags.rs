@@ -0,0 +1,239 @@
+//! Per-project and per-context summaries, plus the optional metadata file that gives
+//! projects and contexts a description, a color and an archived flag. The file is TOML,
+//! keyed by name without the sigil; hierarchical names need quoting:
+//!
+//! ```toml
+//! [projects.work]
+//! description = "Day job"
+//! color = "#1f6feb"
+//!
+//! [projects."work.clientA"]
+//! archived = true
+//!
+//! [contexts.home]
+//! color = "green"
+//! ```
+
+use crate::todo_hierarchy;
+use crate::todo_item::TodoItem;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TagMetadataError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+/// What the metadata file says about one project or context.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TagMetadata {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Any CSS color, e.g. `#1f6feb` or `green`.
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Archived names are left out of listings unless asked for.
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TagMetadataFile {
+    #[serde(skip)]
+    pub file_name: String,
+    #[serde(default)]
+    pub projects: BTreeMap<String, TagMetadata>,
+    #[serde(default)]
+    pub contexts: BTreeMap<String, TagMetadata>,
+}
+
+impl TagMetadataFile {
+    pub fn new(file_name: String) -> Self {
+        TagMetadataFile {
+            file_name,
+            ..Default::default()
+        }
+    }
+
+    /// Reads the file. A missing file means no metadata, as the file is optional.
+    pub fn load(&mut self) -> Result<(), TagMetadataError> {
+        let content = match std::fs::read_to_string(&self.file_name) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let parsed: TagMetadataFile = toml::from_str(&content)?;
+        self.projects = parsed.projects;
+        self.contexts = parsed.contexts;
+        Ok(())
+    }
+}
+
+/// One project or context in use, with counts that include its subprojects or subcontexts.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TagSummary {
+    pub name: String,
+    /// 0 for a top-level name, 1 for `work.clientA` and so on.
+    pub depth: usize,
+    pub open: usize,
+    pub done: usize,
+    /// The earliest due date among the open items.
+    pub next_due: Option<NaiveDate>,
+    /// The latest creation or completion date among all the items.
+    pub last_activity: Option<NaiveDate>,
+    pub description: Option<String>,
+    /// The name's own color, or else that of its nearest parent with one, so subprojects
+    /// look like their parent.
+    pub color: Option<String>,
+    /// Whether the name or one of its parents is archived.
+    pub archived: bool,
+}
+
+/// A summary of every project in `items`, which would usually be the contents of todo.txt
+/// and done.txt together, in tree order.
+pub fn project_summaries(items: &[TodoItem], metadata: &TagMetadataFile) -> Vec<TagSummary> {
+    summaries(
+        items,
+        |item| item.projects.iter().map(|p| p.name.as_str()).collect(),
+        &metadata.projects,
+    )
+}
+
+/// Like [`project_summaries`], for contexts.
+pub fn context_summaries(items: &[TodoItem], metadata: &TagMetadataFile) -> Vec<TagSummary> {
+    summaries(
+        items,
+        |item| item.contexts.iter().map(|c| c.name.as_str()).collect(),
+        &metadata.contexts,
+    )
+}
+
+fn summaries(
+    items: &[TodoItem],
+    names: impl Fn(&TodoItem) -> Vec<&str>,
+    metadata: &BTreeMap<String, TagMetadata>,
+) -> Vec<TagSummary> {
+    let mut summaries: BTreeMap<&str, TagSummary> = BTreeMap::new();
+    for item in items {
+        // Each item counts once towards each of its names and their parents.
+        let item_names: BTreeSet<&str> = names(item)
+            .into_iter()
+            .flat_map(todo_hierarchy::ancestors)
+            .collect();
+        let activity = item.creation_date.max(item.completion_date);
+        for name in item_names {
+            let summary = summaries
+                .entry(name)
+                .or_insert_with(|| new_summary(name, metadata));
+            if item.done {
+                summary.done += 1;
+            } else {
+                summary.open += 1;
+                summary.next_due = earliest(summary.next_due, item.due);
+            }
+            summary.last_activity = summary.last_activity.max(activity);
+        }
+    }
+    let mut summaries: Vec<TagSummary> = summaries.into_values().collect();
+    summaries
+        .sort_by(|a, b| todo_hierarchy::sort_key(&a.name).cmp(&todo_hierarchy::sort_key(&b.name)));
+    summaries
+}
+
+fn new_summary(name: &str, metadata: &BTreeMap<String, TagMetadata>) -> TagSummary {
+    // Top-down, so the last match is the nearest.
+    let inherited: Vec<&TagMetadata> = todo_hierarchy::ancestors(name)
+        .filter_map(|n| metadata.get(n))
+        .collect();
+    TagSummary {
+        name: name.to_string(),
+        depth: todo_hierarchy::depth(name),
+        open: 0,
+        done: 0,
+        next_due: None,
+        last_activity: None,
+        description: metadata.get(name).and_then(|m| m.description.clone()),
+        color: inherited.iter().rev().find_map(|m| m.color.clone()),
+        archived: inherited.iter().any(|m| m.archived),
+    }
+}
+
+fn earliest(a: Option<NaiveDate>, b: Option<NaiveDate>) -> Option<NaiveDate> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        _ => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(lines: &[&str]) -> Vec<TodoItem> {
+        lines.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_project_summaries() {
+        let items = items(&[
+            "2026-10-01 Plan +work due:2026-10-25",
+            "2026-10-02 Migrate +work.clientA due:2026-10-20",
+            "x 2026-10-15 2026-10-03 Invoice +work.clientA",
+            "Garden +home @outside",
+        ]);
+        let summaries = project_summaries(&items, &TagMetadataFile::default());
+        let names: Vec<&str> = summaries.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["home", "work", "work.clientA"]);
+
+        let work = &summaries[1];
+        assert_eq!((work.open, work.done, work.depth), (2, 1, 0));
+        assert_eq!(work.next_due, Some(date("2026-10-20")));
+        assert_eq!(work.last_activity, Some(date("2026-10-15")));
+        assert_eq!(summaries[0].last_activity, None);
+
+        let contexts = context_summaries(&items, &TagMetadataFile::default());
+        assert_eq!(contexts.len(), 1);
+        assert_eq!(contexts[0].name, "outside");
+    }
+
+    #[test]
+    fn test_metadata_is_inherited() {
+        let metadata: TagMetadataFile = toml::from_str(
+            r##"
+            [projects.work]
+            description = "Day job"
+            color = "#1f6feb"
+
+            [projects."work.clientA"]
+            archived = true
+            "##,
+        )
+        .unwrap();
+        let items = items(&["Plan +work", "Migrate +work.clientA.db"]);
+        let summaries = project_summaries(&items, &metadata);
+
+        assert_eq!(summaries[0].description.as_deref(), Some("Day job"));
+        assert!(!summaries[0].archived);
+        let db = &summaries[2];
+        assert_eq!(db.name, "work.clientA.db");
+        assert_eq!(db.description, None);
+        assert_eq!(db.color.as_deref(), Some("#1f6feb"));
+        assert!(db.archived);
+    }
+
+    #[test]
+    fn test_missing_metadata_file_is_empty() {
+        let mut metadata = TagMetadataFile::new("/nonexistent/projects.toml".to_string());
+        metadata.load().unwrap();
+        assert!(metadata.projects.is_empty());
+    }
+}
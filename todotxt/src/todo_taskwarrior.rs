@@ -0,0 +1,405 @@
+use crate::todo_context::TodoContext;
+use crate::todo_item::TodoItem;
+use crate::todo_priority::TodoPriority;
+use crate::todo_project::TodoProject;
+use crate::todo_recurrence::{TodoRecurrence, TodoRecurrenceUnit};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+/// Taskwarrior's date format in `task export` / `task import` JSON: UTC, basic ISO 8601.
+const TASKWARRIOR_DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// One task in Taskwarrior's JSON export format. Only the attributes that have a todo.txt
+/// counterpart are kept; the rest (urgency, annotations, UDAs, ...) are dropped on import.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TaskwarriorTask {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    pub description: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recur: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_depends"
+    )]
+    pub depends: Vec<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TaskwarriorError {
+    #[error("invalid Taskwarrior JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid Taskwarrior date '{0}'")]
+    Date(String),
+    #[error("invalid uuid '{0}'")]
+    Uuid(String),
+}
+
+/// Taskwarrior before 2.6 exports `depends` as one comma-separated string rather than an
+/// array; accept both.
+fn deserialize_depends<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Depends {
+        List(Vec<String>),
+        Joined(String),
+    }
+    Ok(match Depends::deserialize(deserializer)? {
+        Depends::List(list) => list,
+        Depends::Joined(joined) => joined
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+impl From<&TodoItem> for TaskwarriorTask {
+    /// The first project becomes the Taskwarrior project. Taskwarrior has only one, so any
+    /// further projects are exported as tags alongside the contexts. `(A)`/`(B)`/`(C)` map
+    /// to H/M/L and lower priorities to L. `sub:` is exported as `parent`, the `dep:` tags
+    /// as `depends`, and `t:` as `scheduled`. The description keeps the tags typed in it.
+    fn from(item: &TodoItem) -> Self {
+        TaskwarriorTask {
+            uuid: Some(item.stable_uuid().to_string()),
+            description: item.description.clone(),
+            status: if item.done { "completed" } else { "pending" }.to_string(),
+            entry: item.creation_date.map(format_date),
+            end: item.completion_date.filter(|_| item.done).map(format_date),
+            due: item.due.map(|d| format_moment(d, item.due_time)),
+            wait: None,
+            scheduled: item
+                .threshold
+                .map(|t| format_moment(t, item.threshold_time)),
+            project: item.projects.first().map(|p| p.name.clone()),
+            tags: item
+                .projects
+                .iter()
+                .skip(1)
+                .map(|p| p.name.clone())
+                .chain(item.contexts.iter().map(|c| c.name.clone()))
+                .collect(),
+            priority: item.priority.priority.map(|p| {
+                match p {
+                    0 => "H",
+                    1 => "M",
+                    _ => "L",
+                }
+                .to_string()
+            }),
+            recur: item.recurrence.as_ref().map(recurrence_to_recur),
+            parent: item.sub.map(|u| u.to_string()),
+            depends: item.deps.iter().map(|u| u.to_string()).collect(),
+        }
+    }
+}
+
+impl TryFrom<&TaskwarriorTask> for TodoItem {
+    type Error = TaskwarriorError;
+
+    /// The inverse of the `From<&TodoItem>` conversion. `scheduled` takes precedence over
+    /// `wait` for `t:`. `parent` becomes `sub:` and each of `depends` a `dep:` tag. A `recur` value
+    /// with no `rec:` equivalent is dropped. Only the dates of `due` and `scheduled` are
+    /// kept, as Taskwarrior gives every date a time of day. Tags typed in the description
+    /// are read in place, as from a todo.txt line, and a tag naming one of its projects
+    /// isn't also taken as a context.
+    fn try_from(task: &TaskwarriorTask) -> Result<Self, Self::Error> {
+        let done = task.status == "completed";
+        let mut projects: Vec<TodoProject> = Vec::new();
+        let mut contexts: Vec<TodoContext> = Vec::new();
+        // Taskwarrior allows any spacing; a todo.txt line has single spaces.
+        let description = task
+            .description
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        for word in description.split_whitespace() {
+            if let Ok(project) = word.parse::<TodoProject>() {
+                if !projects.contains(&project) {
+                    projects.push(project);
+                }
+            } else if let Ok(context) = word.parse::<TodoContext>()
+                && !contexts.contains(&context)
+            {
+                contexts.push(context);
+            }
+        }
+        if let Some(ref name) = task.project
+            && !projects.iter().any(|p| &p.name == name)
+        {
+            projects.push(TodoProject { name: name.clone() });
+        }
+        for name in &task.tags {
+            if !projects.iter().any(|p| &p.name == name)
+                && !contexts.iter().any(|c| &c.name == name)
+            {
+                contexts.push(TodoContext { name: name.clone() });
+            }
+        }
+        Ok(TodoItem {
+            done,
+            priority: TodoPriority {
+                priority: match task.priority.as_deref() {
+                    Some("H") => Some(0),
+                    Some("M") => Some(1),
+                    Some("L") => Some(2),
+                    _ => None,
+                },
+            },
+            completion_date: if done {
+                task.end.as_deref().map(parse_date).transpose()?
+            } else {
+                None
+            },
+            creation_date: task.entry.as_deref().map(parse_date).transpose()?,
+            description,
+            projects,
+            contexts,
+            due: task.due.as_deref().map(parse_date).transpose()?,
+            due_time: None,
+            recurrence: task.recur.as_deref().and_then(recur_to_recurrence),
+            threshold: task
+                .scheduled
+                .as_deref()
+                .or(task.wait.as_deref())
+                .map(parse_date)
+                .transpose()?,
+            threshold_time: None,
+            uuid: task.uuid.as_deref().map(parse_uuid).transpose()?,
+            sub: task.parent.as_deref().map(parse_uuid).transpose()?,
+            deps: task
+                .depends
+                .iter()
+                .map(|u| parse_uuid(u))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Converts the output of `task export` into items. Deleted tasks are skipped.
+pub fn from_taskwarrior_json(json: &str) -> Result<Vec<TodoItem>, TaskwarriorError> {
+    let tasks: Vec<TaskwarriorTask> = serde_json::from_str(json)?;
+    tasks
+        .iter()
+        .filter(|task| task.status != "deleted")
+        .map(TodoItem::try_from)
+        .collect()
+}
+
+/// Renders items as a JSON array that `task import` accepts.
+pub fn to_taskwarrior_json(items: &[TodoItem]) -> String {
+    let tasks: Vec<TaskwarriorTask> = items.iter().map(TaskwarriorTask::from).collect();
+    // Serializing a Vec of plain string/option fields cannot fail.
+    serde_json::to_string_pretty(&tasks).unwrap()
+}
+
+/// todo.txt dates are local calendar days, so they are exported as local midnight converted
+/// to UTC -- which is also how Taskwarrior itself stores a date-only `due:2026-10-20`.
+fn format_date(date: NaiveDate) -> String {
+    format_moment(date, None)
+}
+
+/// A local date and time of day (midnight if none) as a Taskwarrior UTC timestamp.
+fn format_moment(date: NaiveDate, time: Option<NaiveTime>) -> String {
+    let local = date.and_time(time.unwrap_or(NaiveTime::MIN));
+    let utc = Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&local));
+    utc.format(TASKWARRIOR_DATE_FORMAT).to_string()
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, TaskwarriorError> {
+    NaiveDateTime::parse_from_str(value, TASKWARRIOR_DATE_FORMAT)
+        .map(|naive| {
+            DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc)
+                .with_timezone(&Local)
+                .date_naive()
+        })
+        .map_err(|_| TaskwarriorError::Date(value.to_string()))
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid, TaskwarriorError> {
+    Uuid::parse_str(value).map_err(|_| TaskwarriorError::Uuid(value.to_string()))
+}
+
+fn recurrence_to_recur(recurrence: &TodoRecurrence) -> String {
+    match (&recurrence.unit, recurrence.count) {
+        (TodoRecurrenceUnit::Daily, 1) => "daily".to_string(),
+        (TodoRecurrenceUnit::BusinessDay, 1) => "weekdays".to_string(),
+        (TodoRecurrenceUnit::Weekly, 1) => "weekly".to_string(),
+        (TodoRecurrenceUnit::Monthly, 1) => "monthly".to_string(),
+        (TodoRecurrenceUnit::Yearly, 1) => "yearly".to_string(),
+        (TodoRecurrenceUnit::Daily, n) => format!("{}days", n),
+        // Taskwarrior has no business-day multiple; every n weekdays is about n days.
+        (TodoRecurrenceUnit::BusinessDay, n) => format!("{}days", n),
+        (TodoRecurrenceUnit::Weekly, n) => format!("{}weeks", n),
+        (TodoRecurrenceUnit::Monthly, n) => format!("{}months", n),
+        (TodoRecurrenceUnit::Yearly, n) => format!("{}years", n),
+    }
+}
+
+/// Taskwarrior recurrences are anchored to the due date, so they become strict `rec:`s.
+fn recur_to_recurrence(recur: &str) -> Option<TodoRecurrence> {
+    let split = recur
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(recur.len());
+    let (count, unit) = recur.split_at(split);
+    let count = if count.is_empty() {
+        1
+    } else {
+        count.parse().ok()?
+    };
+    let unit = match unit.to_ascii_lowercase().as_str() {
+        "daily" | "day" | "days" | "d" => TodoRecurrenceUnit::Daily,
+        "weekdays" => TodoRecurrenceUnit::BusinessDay,
+        "weekly" | "week" | "weeks" | "wk" | "wks" | "w" => TodoRecurrenceUnit::Weekly,
+        "monthly" | "month" | "months" | "mo" | "mos" => TodoRecurrenceUnit::Monthly,
+        "yearly" | "annual" | "year" | "years" | "yr" | "yrs" | "y" => TodoRecurrenceUnit::Yearly,
+        _ => return None,
+    };
+    Some(TodoRecurrence {
+        strict: true,
+        count,
+        unit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_task() {
+        let json = r#"[
+            {"id":1,"description":"Water plants","entry":"20261001T120000Z","status":"pending",
+             "uuid":"67e55044-10b1-426f-9247-bb680e5fe0c8","project":"garden","tags":["home","weekend"],
+             "priority":"M","due":"20261020T120000Z","scheduled":"20261015T120000Z","wait":"20261014T120000Z",
+             "recur":"2weeks","depends":"0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d","urgency":4.2}
+        ]"#;
+        let items = from_taskwarrior_json(json).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            items[0].to_string(),
+            "(B) 2026-10-01 Water plants +garden @home @weekend due:2026-10-20 rec:+2w t:2026-10-15 uuid:67e55044-10b1-426f-9247-bb680e5fe0c8 dep:0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d"
+        );
+    }
+
+    #[test]
+    fn test_import_completed_and_deleted() {
+        let json = r#"[
+            {"description":"Done","status":"completed","entry":"20261001T120000Z","end":"20261002T120000Z"},
+            {"description":"Gone","status":"deleted"},
+            {"description":"Later","status":"waiting","wait":"20261101T120000Z",
+             "parent":"0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d","depends":["67e55044-10b1-426f-9247-bb680e5fe0c8"]}
+        ]"#;
+        let items = from_taskwarrior_json(json).unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(items[0].done);
+        assert_eq!(
+            items[0].completion_date,
+            NaiveDate::from_ymd_opt(2026, 10, 2)
+        );
+        assert!(!items[1].done);
+        assert_eq!(items[1].threshold, NaiveDate::from_ymd_opt(2026, 11, 1));
+        assert_eq!(
+            items[1].sub,
+            Some(Uuid::parse_str("0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_import_errors() {
+        assert!(matches!(
+            from_taskwarrior_json("{"),
+            Err(TaskwarriorError::Json(_))
+        ));
+        assert!(matches!(
+            from_taskwarrior_json(r#"[{"description":"x","status":"pending","due":"2026-10-20"}]"#),
+            Err(TaskwarriorError::Date(_))
+        ));
+        assert!(matches!(
+            from_taskwarrior_json(r#"[{"description":"x","status":"pending","uuid":"nope"}]"#),
+            Err(TaskwarriorError::Uuid(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_task() {
+        let item: TodoItem =
+            "(A) 2026-10-01 Ship it +work +release @office due:2026-10-20 rec:w sub:0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d"
+                .parse()
+                .unwrap();
+        let task = TaskwarriorTask::from(&item);
+        assert_eq!(task.uuid, Some(item.stable_uuid().to_string()));
+        assert_eq!(task.status, "pending");
+        assert_eq!(task.project.as_deref(), Some("work"));
+        assert_eq!(task.tags, vec!["release", "office"]);
+        assert_eq!(task.priority.as_deref(), Some("H"));
+        assert_eq!(task.recur.as_deref(), Some("weekly"));
+        assert_eq!(
+            task.parent.as_deref(),
+            Some("0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d")
+        );
+        assert!(task.depends.is_empty());
+        assert_eq!(task.end, None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let items: Vec<TodoItem> = vec![
+            "(C) 2026-10-01 Water plants +garden @home due:2026-10-20 t:2026-10-18 rec:+3d uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"
+                .parse()
+                .unwrap(),
+            "x 2026-10-05 2026-10-01 Filed taxes pri:A uuid:0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d"
+                .parse()
+                .unwrap(),
+            // Tags typed mid-sentence, and a second project exported as a tag.
+            "Call @mom about +garden and +shed uuid:4c5d6e7f-8091-4a2b-bc3d-4e5f60718293"
+                .parse()
+                .unwrap(),
+            // Dependencies without a parent stay dependencies.
+            "Ship it uuid:9a8b7c6d-5e4f-4a3b-8c2d-1e0f9a8b7c6d dep:67e55044-10b1-426f-9247-bb680e5fe0c8 dep:0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d"
+                .parse()
+                .unwrap(),
+        ];
+        let json = to_taskwarrior_json(&items);
+        assert_eq!(from_taskwarrior_json(&json).unwrap(), items);
+    }
+
+    #[test]
+    fn test_recur_to_recurrence() {
+        let rec = |s: &str| recur_to_recurrence(s).map(|r| r.to_string());
+        assert_eq!(rec("daily"), Some("+d".to_string()));
+        assert_eq!(rec("weekdays"), Some("+b".to_string()));
+        assert_eq!(rec("3wks"), Some("+3w".to_string()));
+        assert_eq!(rec("2mo"), Some("+2m".to_string()));
+        assert_eq!(rec("P1M"), None);
+        assert_eq!(rec("quarterly"), None);
+    }
+}
@@ -0,0 +1,370 @@
+//! Time tracking. Timers run per item and are kept in a sidecar log, one line per session
+//! keyed by the item's `uuid:`:
+//!
+//! ```text
+//! 67e55044-10b1-426f-9247-bb680e5fe0c8 2026-10-18T09:00:00 2026-10-18T10:30:00
+//! 67e55044-10b1-426f-9247-bb680e5fe0c8 2026-10-18T14:00:00
+//! ```
+//!
+//! A line without an end is a running timer. Stopping one adds the session to the item's
+//! `spent:` tag; `est:` holds an estimate. Both are durations like `1h30m` and stay part of
+//! the description, as other todo.txt tools expect of unknown tags.
+
+use crate::todo_item::TodoItem;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+const LOG_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum TimerError {
+    #[error("a timer is already running for {0}")]
+    AlreadyRunning(Uuid),
+    #[error("no timer is running for {0}")]
+    NotRunning(Uuid),
+}
+
+/// One tracked session; `end` is `None` while the timer runs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimeEntry {
+    pub uuid: Uuid,
+    pub start: NaiveDateTime,
+    pub end: Option<NaiveDateTime>,
+}
+
+impl TimeEntry {
+    /// The length of the session, counting a running one up to `now`.
+    pub fn duration(&self, now: NaiveDateTime) -> Duration {
+        self.end.unwrap_or(now) - self.start
+    }
+
+    /// The part of the session between `from` and `to`.
+    fn overlap(&self, from: NaiveDateTime, to: NaiveDateTime, now: NaiveDateTime) -> Duration {
+        let start = self.start.max(from);
+        let end = self.end.unwrap_or(now).min(to);
+        (end - start).max(Duration::zero())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeLog {
+    pub file_name: String,
+    pub entries: Vec<TimeEntry>,
+}
+
+impl TimeLog {
+    pub fn new(file_name: String) -> Self {
+        TimeLog {
+            file_name,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Reads the log. A missing file is an empty log, and lines that don't parse are skipped.
+    pub fn load(&mut self) -> Result<(), std::io::Error> {
+        let content = match std::fs::read_to_string(&self.file_name) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        self.entries = content.lines().filter_map(parse_entry).collect();
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        let content: String = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut line = format!("{} {}", entry.uuid, entry.start.format(LOG_TIME_FORMAT));
+                if let Some(end) = entry.end {
+                    line.push_str(&format!(" {}", end.format(LOG_TIME_FORMAT)));
+                }
+                line + "\n"
+            })
+            .collect();
+        std::fs::write(&self.file_name, content)
+    }
+
+    pub fn running(&self) -> impl Iterator<Item = &TimeEntry> {
+        self.entries.iter().filter(|entry| entry.end.is_none())
+    }
+
+    pub fn is_running(&self, uuid: Uuid) -> bool {
+        self.running().any(|entry| entry.uuid == uuid)
+    }
+
+    pub fn start(&mut self, uuid: Uuid, now: NaiveDateTime) -> Result<(), TimerError> {
+        if self.is_running(uuid) {
+            return Err(TimerError::AlreadyRunning(uuid));
+        }
+        self.entries.push(TimeEntry {
+            uuid,
+            start: now,
+            end: None,
+        });
+        Ok(())
+    }
+
+    /// Stops the item's timer and returns the length of the session.
+    pub fn stop(&mut self, uuid: Uuid, now: NaiveDateTime) -> Result<Duration, TimerError> {
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.uuid == uuid && entry.end.is_none())
+            .ok_or(TimerError::NotRunning(uuid))?;
+        entry.end = Some(now.max(entry.start));
+        Ok(entry.duration(now))
+    }
+
+    /// Time tracked per item between the start of `from` and the end of `to`. Sessions
+    /// spanning the edges count only the part inside.
+    pub fn totals(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        now: NaiveDateTime,
+    ) -> BTreeMap<Uuid, Duration> {
+        let from = from.and_time(chrono::NaiveTime::MIN);
+        let to = (to + Duration::days(1)).and_time(chrono::NaiveTime::MIN);
+        let mut totals = BTreeMap::new();
+        for entry in &self.entries {
+            let time = entry.overlap(from, to, now);
+            if time > Duration::zero() {
+                *totals.entry(entry.uuid).or_insert_with(Duration::zero) += time;
+            }
+        }
+        totals
+    }
+}
+
+fn parse_entry(line: &str) -> Option<TimeEntry> {
+    let mut words = line.split_whitespace();
+    let uuid = Uuid::parse_str(words.next()?).ok()?;
+    let start = NaiveDateTime::parse_from_str(words.next()?, LOG_TIME_FORMAT).ok()?;
+    let end = match words.next() {
+        Some(end) => Some(NaiveDateTime::parse_from_str(end, LOG_TIME_FORMAT).ok()?),
+        None => None,
+    };
+    Some(TimeEntry { uuid, start, end })
+}
+
+/// Parses a duration like `1h30m`, `2h` or `45m`; `None` if it isn't one or is too long to
+/// represent.
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let mut total = Duration::zero();
+    let mut digits = String::new();
+    for ch in s.chars() {
+        match ch {
+            '0'..='9' => digits.push(ch),
+            'h' | 'm' if !digits.is_empty() => {
+                let n: i64 = digits.parse().ok()?;
+                let part = if ch == 'h' {
+                    Duration::try_hours(n)?
+                } else {
+                    Duration::try_minutes(n)?
+                };
+                total = total.checked_add(&part)?;
+                digits.clear();
+            }
+            _ => return None,
+        }
+    }
+    (digits.is_empty() && !s.is_empty()).then_some(total)
+}
+
+/// Formats a duration as `parse_duration` reads it, to the minute: `1h30m`, `2h`, `45m`.
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
+fn tag_duration(item: &TodoItem, tag: &str) -> Option<Duration> {
+    item.description
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix(tag))
+        .and_then(parse_duration)
+}
+
+/// The time recorded in the item's `spent:` tag.
+pub fn spent(item: &TodoItem) -> Option<Duration> {
+    tag_duration(item, "spent:")
+}
+
+/// The item's `est:` estimate.
+pub fn estimate(item: &TodoItem) -> Option<Duration> {
+    tag_duration(item, "est:")
+}
+
+/// The item with its `spent:` tag set to `duration`, in place of any existing one.
+pub fn set_spent(item: &TodoItem, duration: Duration) -> TodoItem {
+    let tag = format!("spent:{}", format_duration(duration));
+    let mut words: Vec<String> = Vec::new();
+    let mut replaced = false;
+    for word in item.description.split_whitespace() {
+        if word.starts_with("spent:") {
+            if !replaced {
+                words.push(tag.clone());
+                replaced = true;
+            }
+        } else {
+            words.push(word.to_string());
+        }
+    }
+    if !replaced {
+        words.push(tag);
+    }
+    TodoItem {
+        description: words.join(" "),
+        ..item.clone()
+    }
+}
+
+/// Time per project or context, from [`TimeLog::totals`] and the items they belong to. An
+/// item in several projects counts towards each; time of items with none, or no longer in
+/// `items`, is under `None`.
+pub fn time_by_tag(
+    items: &[TodoItem],
+    totals: &BTreeMap<Uuid, Duration>,
+    tags: impl Fn(&TodoItem) -> Vec<String>,
+) -> BTreeMap<Option<String>, Duration> {
+    let mut by_tag: BTreeMap<Option<String>, Duration> = BTreeMap::new();
+    for (uuid, &time) in totals {
+        let names = items
+            .iter()
+            .find(|item| item.uuid == Some(*uuid))
+            .map(&tags)
+            .unwrap_or_default();
+        if names.is_empty() {
+            *by_tag.entry(None).or_insert_with(Duration::zero) += time;
+        }
+        for name in names {
+            *by_tag.entry(Some(name)).or_insert_with(Duration::zero) += time;
+        }
+    }
+    by_tag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    const A: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+    const B: &str = "0b8f5b3e-4d5e-4a5f-9d8e-2f2e1c3b4a5d";
+
+    #[test]
+    fn test_durations() {
+        assert_eq!(parse_duration("1h30m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("45m"), Some(Duration::minutes(45)));
+        assert_eq!(parse_duration("2h"), Some(Duration::hours(2)));
+        for bad in [
+            "",
+            "1h30",
+            "h",
+            "1.5h",
+            "3d",
+            "9999999999999999h",
+            "99999999999999999999m",
+        ] {
+            assert_eq!(parse_duration(bad), None, "{}", bad);
+        }
+        assert_eq!(format_duration(Duration::minutes(90)), "1h30m");
+        assert_eq!(format_duration(Duration::minutes(120)), "2h");
+        assert_eq!(format_duration(Duration::seconds(59)), "0m");
+    }
+
+    #[test]
+    fn test_spent_and_estimate_tags() {
+        let item: TodoItem = "Fix invoice est:2h spent:30m +billing".parse().unwrap();
+        assert_eq!(estimate(&item), Some(Duration::hours(2)));
+        assert_eq!(spent(&item), Some(Duration::minutes(30)));
+
+        let item = set_spent(&item, Duration::minutes(75));
+        assert_eq!(item.to_string(), "Fix invoice est:2h spent:1h15m +billing");
+        let fresh: TodoItem = "Write tests".parse().unwrap();
+        assert_eq!(
+            set_spent(&fresh, Duration::hours(1)).to_string(),
+            "Write tests spent:1h"
+        );
+    }
+
+    #[test]
+    fn test_start_stop_and_round_trip() {
+        let path = std::env::temp_dir().join("test_todo_timer_log.txt");
+        let uuid = Uuid::parse_str(A).unwrap();
+        let mut log = TimeLog::new(path.to_str().unwrap().to_string());
+        log.start(uuid, at("2026-10-18 09:00")).unwrap();
+        assert_eq!(
+            log.start(uuid, at("2026-10-18 09:05")),
+            Err(TimerError::AlreadyRunning(uuid))
+        );
+        assert_eq!(
+            log.stop(uuid, at("2026-10-18 10:30")),
+            Ok(Duration::minutes(90))
+        );
+        assert_eq!(
+            log.stop(uuid, at("2026-10-18 10:31")),
+            Err(TimerError::NotRunning(uuid))
+        );
+        log.start(uuid, at("2026-10-18 14:00")).unwrap();
+        log.save().unwrap();
+
+        let mut loaded = TimeLog::new(log.file_name.clone());
+        loaded.load().unwrap();
+        assert_eq!(loaded, log);
+        assert!(loaded.is_running(uuid));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_totals_by_project() {
+        let (a, b) = (Uuid::parse_str(A).unwrap(), Uuid::parse_str(B).unwrap());
+        let mut log = TimeLog::new(String::new());
+        log.entries = vec![
+            // Crosses midnight into the range; only the last hour counts.
+            TimeEntry {
+                uuid: a,
+                start: at("2026-10-16 23:00"),
+                end: Some(at("2026-10-17 01:00")),
+            },
+            TimeEntry {
+                uuid: b,
+                start: at("2026-10-17 09:00"),
+                end: Some(at("2026-10-17 09:45")),
+            },
+            // Still running.
+            TimeEntry {
+                uuid: a,
+                start: at("2026-10-18 11:30"),
+                end: None,
+            },
+        ];
+        let day = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let totals = log.totals(day("2026-10-17"), day("2026-10-18"), at("2026-10-18 12:00"));
+        assert_eq!(totals[&a], Duration::minutes(90));
+        assert_eq!(totals[&b], Duration::minutes(45));
+
+        let items: Vec<TodoItem> = [format!("Invoices +billing +acme uuid:{}", A)]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let by_project = time_by_tag(&items, &totals, |item| {
+            item.projects.iter().map(|p| p.name.clone()).collect()
+        });
+        assert_eq!(by_project[&Some("acme".to_string())], Duration::minutes(90));
+        assert_eq!(
+            by_project[&Some("billing".to_string())],
+            Duration::minutes(90)
+        );
+        assert_eq!(by_project[&None], Duration::minutes(45));
+    }
+}
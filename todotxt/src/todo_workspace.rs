@@ -0,0 +1,301 @@
+use crate::todo_item::TodoItem;
+use crate::todo_library::TodoLibrary;
+
+/// One named todo list in a workspace: its todo file and, optionally, the done file that
+/// completed items are archived to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoList {
+    pub name: String,
+    pub library: TodoLibrary,
+    pub done: Option<TodoLibrary>,
+}
+
+/// A named set of todo lists (e.g. "work", "personal", "shared") that can be queried and
+/// rearranged as a whole.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TodoWorkspace {
+    pub lists: Vec<TodoList>,
+}
+
+/// An item found by a cross-list query, along with where it lives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TodoWorkspaceItem<'a> {
+    pub list: &'a str,
+    pub index: usize,
+    pub item: &'a TodoItem,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TodoWorkspaceError {
+    #[error("no list named '{0}'")]
+    UnknownList(String),
+    #[error("a list named '{0}' already exists")]
+    DuplicateList(String),
+    #[error("no item at index {0}")]
+    InvalidIndex(usize),
+    #[error("list '{0}' has no done file")]
+    NoDoneFile(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl TodoList {
+    pub fn new(name: String, file_name: String, done_file_name: Option<String>) -> Self {
+        TodoList {
+            name,
+            library: TodoLibrary::new(file_name),
+            done: done_file_name.map(TodoLibrary::new),
+        }
+    }
+
+    /// Loads the todo file, and the done file if there is one. A done file that doesn't
+    /// exist yet is treated as empty, since nothing may have been archived to it so far.
+    pub fn load(&mut self) -> Result<(), std::io::Error> {
+        self.library.load()?;
+        if let Some(ref mut done) = self.done {
+            match done.load() {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => done.clear_items(),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        self.library.save()?;
+        if let Some(ref done) = self.done {
+            done.save()?;
+        }
+        Ok(())
+    }
+
+    /// Moves every completed item from the todo file into the done file, returning how many
+    /// were moved. Neither file is saved; that is left to the caller, as with TodoLibrary.
+    pub fn archive(&mut self) -> Result<usize, TodoWorkspaceError> {
+        let done = self
+            .done
+            .as_mut()
+            .ok_or_else(|| TodoWorkspaceError::NoDoneFile(self.name.clone()))?;
+        let (completed, open): (Vec<TodoItem>, Vec<TodoItem>) =
+            self.library.items.drain(..).partition(|item| item.done);
+        self.library.items = open;
+        let count = completed.len();
+        done.items.extend(completed);
+        Ok(count)
+    }
+}
+
+impl TodoWorkspace {
+    pub fn new() -> Self {
+        TodoWorkspace { lists: Vec::new() }
+    }
+
+    pub fn add_list(
+        &mut self,
+        name: String,
+        file_name: String,
+        done_file_name: Option<String>,
+    ) -> Result<(), TodoWorkspaceError> {
+        if self.list(&name).is_some() {
+            return Err(TodoWorkspaceError::DuplicateList(name));
+        }
+        self.lists
+            .push(TodoList::new(name, file_name, done_file_name));
+        Ok(())
+    }
+
+    pub fn remove_list(&mut self, name: &str) -> Option<TodoList> {
+        let pos = self.lists.iter().position(|l| l.name == name)?;
+        Some(self.lists.remove(pos))
+    }
+
+    pub fn list(&self, name: &str) -> Option<&TodoList> {
+        self.lists.iter().find(|l| l.name == name)
+    }
+
+    pub fn list_mut(&mut self, name: &str) -> Option<&mut TodoList> {
+        self.lists.iter_mut().find(|l| l.name == name)
+    }
+
+    pub fn list_names(&self) -> Vec<&str> {
+        self.lists.iter().map(|l| l.name.as_str()).collect()
+    }
+
+    pub fn load(&mut self) -> Result<(), std::io::Error> {
+        for list in &mut self.lists {
+            list.load()?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        for list in &self.lists {
+            list.save()?;
+        }
+        Ok(())
+    }
+
+    /// Returns every item, across all lists' todo files, for which `predicate` holds, in
+    /// list order and then file order.
+    pub fn query<F>(&self, predicate: F) -> Vec<TodoWorkspaceItem<'_>>
+    where
+        F: Fn(&TodoItem) -> bool,
+    {
+        self.lists
+            .iter()
+            .flat_map(|list| {
+                list.library
+                    .items
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, item)| TodoWorkspaceItem {
+                        list: &list.name,
+                        index,
+                        item,
+                    })
+            })
+            .filter(|found| predicate(found.item))
+            .collect()
+    }
+
+    /// Moves the item at `index` in list `from` to the end of list `to`, unchanged. Nothing
+    /// is saved. A `sub:` link to a parent in the source list is kept as-is; it simply no
+    /// longer resolves within a single file.
+    pub fn move_item(
+        &mut self,
+        from: &str,
+        index: usize,
+        to: &str,
+    ) -> Result<(), TodoWorkspaceError> {
+        if self.list(to).is_none() {
+            return Err(TodoWorkspaceError::UnknownList(to.to_string()));
+        }
+        let item = self
+            .list_mut(from)
+            .ok_or_else(|| TodoWorkspaceError::UnknownList(from.to_string()))?
+            .library
+            .remove_item(index)
+            .ok_or(TodoWorkspaceError::InvalidIndex(index))?;
+        // Pushed directly rather than via TodoLibrary::add_item: the item is not new, and a
+        // missing creation date must stay missing rather than become the date it moved.
+        self.list_mut(to).unwrap().library.items.push(item);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn workspace() -> TodoWorkspace {
+        let mut ws = TodoWorkspace::new();
+        ws.add_list("work".to_string(), "work.txt".to_string(), None)
+            .unwrap();
+        ws.add_list(
+            "home".to_string(),
+            "home.txt".to_string(),
+            Some("home-done.txt".to_string()),
+        )
+        .unwrap();
+        ws.list_mut("work")
+            .unwrap()
+            .library
+            .items
+            .push("Write report +q3".parse().unwrap());
+        ws.list_mut("home")
+            .unwrap()
+            .library
+            .items
+            .push("Fix sink @home".parse().unwrap());
+        ws.list_mut("home")
+            .unwrap()
+            .library
+            .items
+            .push("x Buy milk +q3".parse().unwrap());
+        ws
+    }
+
+    #[test]
+    fn test_add_list_rejects_duplicate_name() {
+        let mut ws = workspace();
+        let result = ws.add_list("work".to_string(), "other.txt".to_string(), None);
+        assert!(matches!(result, Err(TodoWorkspaceError::DuplicateList(n)) if n == "work"));
+        assert_eq!(ws.list_names(), vec!["work", "home"]);
+    }
+
+    #[test]
+    fn test_query_across_lists() {
+        let ws = workspace();
+        let found = ws.query(|item| item.projects.iter().any(|p| p.name == "q3"));
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].list, found[0].index), ("work", 0));
+        assert_eq!((found[1].list, found[1].index), ("home", 1));
+        assert_eq!(found[1].item.plain_description(), "Buy milk");
+    }
+
+    #[test]
+    fn test_move_item() {
+        let mut ws = workspace();
+        ws.move_item("home", 0, "work").unwrap();
+        assert_eq!(ws.list("home").unwrap().library.item_count(), 1);
+        let work = &ws.list("work").unwrap().library;
+        assert_eq!(work.item_count(), 2);
+        assert_eq!(work.items[1].plain_description(), "Fix sink");
+        assert_eq!(work.items[1].creation_date, None);
+    }
+
+    #[test]
+    fn test_move_item_errors_leave_lists_untouched() {
+        let mut ws = workspace();
+        assert!(matches!(
+            ws.move_item("home", 0, "nowhere"),
+            Err(TodoWorkspaceError::UnknownList(_))
+        ));
+        assert!(matches!(
+            ws.move_item("home", 7, "work"),
+            Err(TodoWorkspaceError::InvalidIndex(7))
+        ));
+        assert_eq!(ws.list("home").unwrap().library.item_count(), 2);
+        assert_eq!(ws.list("work").unwrap().library.item_count(), 1);
+    }
+
+    #[test]
+    fn test_archive() {
+        let mut ws = workspace();
+        let home = ws.list_mut("home").unwrap();
+        assert_eq!(home.archive().unwrap(), 1);
+        assert_eq!(home.library.item_count(), 1);
+        assert_eq!(
+            home.done.as_ref().unwrap().items[0].plain_description(),
+            "Buy milk"
+        );
+        assert!(matches!(
+            ws.list_mut("work").unwrap().archive(),
+            Err(TodoWorkspaceError::NoDoneFile(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_tolerates_missing_done_file() {
+        let temp_dir = std::env::temp_dir();
+        let todo = temp_dir.join(format!("{}.txt", uuid::Uuid::new_v4()));
+        let done = temp_dir.join(format!("{}.txt", uuid::Uuid::new_v4()));
+        fs::write(&todo, "Task\n").unwrap();
+        let mut ws = TodoWorkspace::new();
+        ws.add_list(
+            "main".to_string(),
+            todo.to_str().unwrap().to_string(),
+            Some(done.to_str().unwrap().to_string()),
+        )
+        .unwrap();
+        ws.load().unwrap();
+        assert_eq!(ws.list("main").unwrap().library.item_count(), 1);
+        assert_eq!(
+            ws.list("main").unwrap().done.as_ref().unwrap().item_count(),
+            0
+        );
+        fs::remove_file(&todo).unwrap();
+    }
+}